@@ -12,4 +12,10 @@ pub enum Action {
     ClearScreen,
     Error(String),
     Help,
+    LaunchGame(bool),
+    KillGame,
+    RestartGame,
+    GameStarted,
+    GameExited,
+    ModsChanged,
 }
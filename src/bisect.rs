@@ -0,0 +1,206 @@
+//! Narrowing down which mod (or pair of mods) causes a problem by repeatedly enabling half of
+//! the candidates and asking whether the problem still happens.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Single(usize),
+    /// The problem only happens with both mods enabled.
+    Pair(usize, usize),
+    /// There was nothing left to test, e.g. it reproduced with no candidate mods at all.
+    Inconclusive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Phase {
+    /// Looking for a single culprit among `candidates`, testing the first half and then, if that
+    /// did not reproduce, the second half.
+    Single {
+        candidates: Vec<usize>,
+        second_half: bool,
+    },
+    /// Neither half reproduced on its own, so it takes one mod from each. Bisect `left` while
+    /// keeping all of `right` enabled.
+    PairLeft {
+        left: Vec<usize>,
+        right: Vec<usize>,
+    },
+    /// The left half of the pair was found, now bisect `right` with it enabled.
+    PairRight {
+        culprit: usize,
+        right: Vec<usize>,
+    },
+    Done(Outcome),
+}
+
+fn halves(v: &[usize]) -> (&[usize], &[usize]) {
+    v.split_at(v.len().div_ceil(2))
+}
+
+#[derive(Debug, Clone)]
+pub struct Bisect {
+    phase: Phase,
+    pub steps: usize,
+}
+
+impl Bisect {
+    /// Starts bisecting `candidates`, which are indices into the caller's mod list.
+    pub fn new(candidates: Vec<usize>) -> Self {
+        let phase = match candidates.len() {
+            0 => Phase::Done(Outcome::Inconclusive),
+            1 => Phase::Done(Outcome::Single(candidates[0])),
+            _ => Phase::Single {
+                candidates,
+                second_half: false,
+            },
+        };
+        Self { phase, steps: 0 }
+    }
+
+    /// The candidates to enable for the current step.
+    pub fn test_set(&self) -> Vec<usize> {
+        match &self.phase {
+            Phase::Single {
+                candidates,
+                second_half,
+            } => {
+                let (first, second) = halves(candidates);
+                if *second_half { second } else { first }.to_vec()
+            }
+            Phase::PairLeft { left, right } => {
+                let mut set = halves(left).0.to_vec();
+                set.extend(right);
+                set
+            }
+            Phase::PairRight { culprit, right } => {
+                let mut set = vec![*culprit];
+                set.extend(halves(right).0);
+                set
+            }
+            Phase::Done(_) => vec![],
+        }
+    }
+
+    /// How many mods are still suspected.
+    pub fn remaining(&self) -> usize {
+        match &self.phase {
+            Phase::Single { candidates, .. } => candidates.len(),
+            Phase::PairLeft { left, right } => left.len() + right.len(),
+            Phase::PairRight { right, .. } => 1 + right.len(),
+            Phase::Done(Outcome::Single(_)) => 1,
+            Phase::Done(Outcome::Pair(..)) => 2,
+            Phase::Done(Outcome::Inconclusive) => 0,
+        }
+    }
+
+    /// Whether the current phase is looking for a pair of mods.
+    pub fn looking_for_pair(&self) -> bool {
+        matches!(self.phase, Phase::PairLeft { .. } | Phase::PairRight { .. })
+    }
+
+    pub fn outcome(&self) -> Option<&Outcome> {
+        match &self.phase {
+            Phase::Done(outcome) => Some(outcome),
+            _ => None,
+        }
+    }
+
+    /// Records whether the problem reproduced with the current test set and moves on.
+    pub fn answer(&mut self, reproduced: bool) {
+        self.steps += 1;
+        self.phase = match std::mem::replace(&mut self.phase, Phase::Done(Outcome::Inconclusive)) {
+            Phase::Single {
+                candidates,
+                second_half,
+            } => {
+                let (first, second) = halves(&candidates);
+                match (reproduced, second_half) {
+                    (true, false) => Self::single(first.to_vec()),
+                    (true, true) => Self::single(second.to_vec()),
+                    (false, false) => Phase::Single {
+                        candidates,
+                        second_half: true,
+                    },
+                    (false, true) => Self::pair_left(first.to_vec(), second.to_vec()),
+                }
+            }
+            Phase::PairLeft { left, right } => {
+                let (first, second) = halves(&left);
+                let left = if reproduced { first } else { second };
+                Self::pair_left(left.to_vec(), right)
+            }
+            Phase::PairRight { culprit, right } => {
+                let (first, second) = halves(&right);
+                let right = if reproduced { first } else { second };
+                Self::pair_right(culprit, right.to_vec())
+            }
+            done @ Phase::Done(_) => done,
+        };
+    }
+
+    fn single(candidates: Vec<usize>) -> Phase {
+        match candidates.len() {
+            0 => Phase::Done(Outcome::Inconclusive),
+            1 => Phase::Done(Outcome::Single(candidates[0])),
+            _ => Phase::Single {
+                candidates,
+                second_half: false,
+            },
+        }
+    }
+
+    fn pair_left(left: Vec<usize>, right: Vec<usize>) -> Phase {
+        match left.len() {
+            0 => Phase::Done(Outcome::Inconclusive),
+            1 => Self::pair_right(left[0], right),
+            _ => Phase::PairLeft { left, right },
+        }
+    }
+
+    fn pair_right(culprit: usize, right: Vec<usize>) -> Phase {
+        match right.len() {
+            0 => Phase::Done(Outcome::Inconclusive),
+            1 => Phase::Done(Outcome::Pair(culprit, right[0])),
+            _ => Phase::PairRight { culprit, right },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Runs a bisect where the problem happens whenever all of `culprits` are enabled.
+    fn run(count: usize, culprits: &[usize]) -> (Outcome, usize) {
+        let mut bisect = Bisect::new((0..count).collect());
+        while bisect.outcome().is_none() {
+            let set = bisect.test_set();
+            bisect.answer(culprits.iter().all(|c| set.contains(c)));
+            assert!(bisect.steps < 50, "bisect did not converge");
+        }
+        (bisect.outcome().unwrap().clone(), bisect.steps)
+    }
+
+    #[test]
+    fn test_finds_single_mod() {
+        for culprit in 0..40 {
+            let (outcome, steps) = run(40, &[culprit]);
+            assert_eq!(outcome, Outcome::Single(culprit));
+            assert!(steps <= 12);
+        }
+    }
+
+    #[test]
+    fn test_finds_pair() {
+        assert_eq!(run(40, &[3, 31]).0, Outcome::Pair(3, 31));
+        assert_eq!(run(10, &[8, 9]).0, Outcome::Pair(8, 9));
+        assert_eq!(run(2, &[0, 1]).0, Outcome::Pair(0, 1));
+    }
+
+    #[test]
+    fn test_trivial_cases() {
+        assert_eq!(Bisect::new(vec![]).outcome(), Some(&Outcome::Inconclusive));
+        assert_eq!(Bisect::new(vec![7]).outcome(), Some(&Outcome::Single(7)));
+    }
+}
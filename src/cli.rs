@@ -1,4 +1,6 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
 
 use crate::config::{get_config_dir, get_data_dir};
 
@@ -12,6 +14,22 @@ pub struct Cli {
     /// Frame rate, i.e. number of frames per second
     #[arg(short, long, value_name = "FLOAT", default_value_t = 60.0)]
     pub frame_rate: f64,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Decode a .jkr save file and print it as JSON
+    Decode {
+        /// The .jkr file, e.g. `1/save.jkr` in the game's data folder
+        path: PathBuf,
+
+        /// Print the JSON on a single line
+        #[arg(long)]
+        compact: bool,
+    },
 }
 
 const VERSION_MESSAGE: &str = concat!(
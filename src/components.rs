@@ -16,6 +16,27 @@ mod quickoptions;
 mod modlist;
 mod remotemods;
 mod textinput;
+mod logviewer;
+mod crashreport;
+mod saves;
+mod runviewer;
+mod stats;
+mod settingseditor;
+mod modconfig;
+mod gamesource;
+mod patchcheck;
+mod collisions;
+mod dumpviewer;
+mod newmod;
+mod metadatacheck;
+mod localization;
+mod textures;
+mod atlaspacker;
+mod release;
+mod modindex;
+mod devlink;
+mod watcher;
+mod isolated;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 ///
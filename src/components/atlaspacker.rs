@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+
+use balatro_tui::textures::{pack_atlas, sprite_folders, sprites_dir};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::info;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+
+/// Packs the folders in `sprites/` of the mod being edited into atlases.
+pub struct AtlasPacker {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    folders: Vec<String>,
+    selected: usize,
+    /// The Lua snippet of the last atlas packed, or why it couldn't be.
+    packed: Option<Result<String, String>>,
+}
+
+impl AtlasPacker {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            folders: vec![],
+            selected: 0,
+            packed: None,
+        }
+    }
+
+    fn reload(&mut self) {
+        self.folders = sprite_folders(&self.mod_path);
+        self.selected = self.selected.min(self.folders.len().saturating_sub(1));
+    }
+
+    fn pack_selected(&mut self) {
+        let Some(name) = self.folders.get(self.selected) else {
+            return;
+        };
+        self.packed = Some(pack_atlas(&self.mod_path, name).map(|packed| {
+            info!(
+                "Packed {} sprites into the {} atlas",
+                packed.positions.len(),
+                name
+            );
+            packed.lua_snippet()
+        }));
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        if self.folders.is_empty() {
+            return vec![Line::from(format!(
+                "Put the sprites of each atlas in a folder of {}, like sprites/jokers/blueprint.png.",
+                sprites_dir(&self.mod_path).display()
+            ))];
+        }
+        let mut lines: Vec<Line> = self
+            .folders
+            .iter()
+            .enumerate()
+            .map(|(i, folder)| {
+                if i == self.selected {
+                    Line::styled(
+                        format!("sprites/{folder}"),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Line::from(format!("sprites/{folder}"))
+                }
+            })
+            .collect();
+        lines.push(Line::from(""));
+        match &self.packed {
+            None => {}
+            Some(Err(e)) => lines.push(Line::styled(e.clone(), Style::default().fg(Color::Red))),
+            Some(Ok(snippet)) => {
+                lines.push(Line::styled(
+                    "Packed into assets/1x and assets/2x. This was also saved next to the sprites:",
+                    Style::default().fg(Color::Green),
+                ));
+                lines.extend(
+                    snippet
+                        .lines()
+                        .map(|l| Line::styled(l.to_string(), Style::default().fg(Color::Gray))),
+                );
+            }
+        }
+        lines
+    }
+}
+
+impl Component for AtlasPacker {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.folders.len().saturating_sub(1))
+            }
+            KeyCode::Enter => self.pack_selected(),
+            KeyCode::Char('r') => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        frame.render_widget(
+            Paragraph::new(self.lines()).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Sprite folders to pack into atlases")
+                    .title_bottom(Line::from(" enter pack, r reload ").right_aligned())
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
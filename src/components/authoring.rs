@@ -20,14 +20,93 @@ use tokio::sync::mpsc::UnboundedSender;
 use super::Component;
 
 use crate::action::Action;
+use crate::components::atlaspacker::AtlasPacker;
+use crate::components::devlink::DevLinker;
+use crate::components::dumpviewer::DumpViewer;
+use crate::components::gamesource::GameSourceBrowser;
+use crate::components::isolated::IsolatedLaunch;
+use crate::components::localization::LocalizationChecker;
+use crate::components::metadatacheck::MetadataChecker;
+use crate::components::modindex::IndexSubmission;
+use crate::components::newmod::NewModWizard;
+use crate::components::patchcheck::PatchChecker;
+use crate::components::release::ReleaseBuilder;
+use crate::components::textures::TextureTools;
+use crate::components::watcher::DevWatcher;
+use crate::config::Config;
 use crate::mods::Mod;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum Tool {
+    #[default]
+    GameSource,
+    Patches,
+    Dump,
+    Metadata,
+    Localization,
+    Textures,
+    Atlases,
+    Release,
+    Index,
+    Link,
+    Watch,
+    Isolated,
+}
+
+impl Tool {
+    const ALL: [Tool; 12] = [
+        Tool::GameSource,
+        Tool::Patches,
+        Tool::Dump,
+        Tool::Metadata,
+        Tool::Localization,
+        Tool::Textures,
+        Tool::Atlases,
+        Tool::Release,
+        Tool::Index,
+        Tool::Link,
+        Tool::Watch,
+        Tool::Isolated,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Tool::GameSource => "game source",
+            Tool::Patches => "patch checker",
+            Tool::Dump => "lovely dump",
+            Tool::Metadata => "metadata",
+            Tool::Localization => "localization",
+            Tool::Textures => "textures",
+            Tool::Atlases => "atlas packer",
+            Tool::Release => "build release",
+            Tool::Index => "mod index",
+            Tool::Link => "link into game",
+            Tool::Watch => "dev loop",
+            Tool::Isolated => "isolated launch",
+        }
+    }
+}
+
 pub struct AuthoringTools {
     pub action_tx: Option<UnboundedSender<Action>>,
     pub has_focus: bool,
     pub mod_path: PathBuf,
-    edited_mod: Mod
+    edited_mod: Mod,
+    tool: Tool,
+    source_browser: GameSourceBrowser,
+    patch_checker: PatchChecker,
+    dump_viewer: DumpViewer,
+    metadata_checker: MetadataChecker,
+    localization_checker: LocalizationChecker,
+    texture_tools: TextureTools,
+    atlas_packer: AtlasPacker,
+    release_builder: ReleaseBuilder,
+    index_submission: IndexSubmission,
+    dev_linker: DevLinker,
+    dev_watcher: DevWatcher,
+    isolated_launch: IsolatedLaunch,
+    /// Shown instead of the tools while there is no mod to edit.
+    wizard: Option<NewModWizard>,
 }
 
 impl AuthoringTools {
@@ -35,38 +114,144 @@ impl AuthoringTools {
         let path = std::env::current_dir().unwrap();
         // let path = PathBuf::from("/home/julie/Documents/GitHub/SnipersTVCorpMod");
         let edited_mod = Mod::from_directory(path.as_path()).unwrap();
+        let wizard = edited_mod
+            .id
+            .is_empty()
+            .then(|| NewModWizard::new(path.clone()));
         Self {
+            action_tx: None,
+            has_focus: false,
+            patch_checker: PatchChecker::new(path.clone()),
+            metadata_checker: MetadataChecker::new(path.clone()),
+            localization_checker: LocalizationChecker::new(path.clone()),
+            texture_tools: TextureTools::new(path.clone()),
+            atlas_packer: AtlasPacker::new(path.clone()),
+            release_builder: ReleaseBuilder::new(path.clone()),
+            index_submission: IndexSubmission::new(path.clone()),
+            dev_linker: DevLinker::new(path.clone()),
+            dev_watcher: DevWatcher::new(path.clone()),
+            isolated_launch: IsolatedLaunch::new(path.clone()),
             mod_path: path,
             edited_mod,
-            ..Default::default()
+            tool: Tool::default(),
+            source_browser: GameSourceBrowser::new(),
+            dump_viewer: DumpViewer::new(),
+            wizard,
+        }
+    }
+
+    fn current_tool(&mut self) -> &mut dyn Component {
+        match self.wizard {
+            Some(ref mut wizard) => wizard,
+            None => self.selected_tool(),
+        }
+    }
+
+    fn selected_tool(&mut self) -> &mut dyn Component {
+        match self.tool {
+            Tool::GameSource => &mut self.source_browser,
+            Tool::Patches => &mut self.patch_checker,
+            Tool::Dump => &mut self.dump_viewer,
+            Tool::Metadata => &mut self.metadata_checker,
+            Tool::Localization => &mut self.localization_checker,
+            Tool::Textures => &mut self.texture_tools,
+            Tool::Atlases => &mut self.atlas_packer,
+            Tool::Release => &mut self.release_builder,
+            Tool::Index => &mut self.index_submission,
+            Tool::Link => &mut self.dev_linker,
+            Tool::Watch => &mut self.dev_watcher,
+            Tool::Isolated => &mut self.isolated_launch,
         }
     }
+
+    fn next_tool(&mut self, forward: bool) {
+        self.current_tool().unfocus();
+        let i = Tool::ALL.iter().position(|t| *t == self.tool).unwrap();
+        let len = Tool::ALL.len();
+        self.tool = Tool::ALL[if forward { (i + 1) % len } else { (i + len - 1) % len }];
+        self.current_tool().focus();
+    }
+
+    fn tool_tabs(&self) -> Line<'static> {
+        let mut spans = vec![Span::from(" ")];
+        for tool in Tool::ALL {
+            spans.push(if tool == self.tool {
+                Span::styled(
+                    tool.name(),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Span::from(tool.name())
+            });
+            spans.push(Span::from(" | "));
+        }
+        spans.pop();
+        spans.push(Span::from(" (tab to switch) "));
+        Line::from(spans).right_aligned()
+    }
 }
 
 impl Component for AuthoringTools {
     fn focus(&mut self) {
         self.has_focus = true;
+        self.current_tool().focus();
     }
     fn unfocus(&mut self) {
         self.has_focus = false;
+        self.current_tool().unfocus();
     }
 
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
-        self.action_tx = Some(tx);
+        self.action_tx = Some(tx.clone());
+        self.source_browser.register_action_handler(tx.clone())?;
+        self.patch_checker.register_action_handler(tx.clone())?;
+        self.dump_viewer.register_action_handler(tx.clone())?;
+        self.metadata_checker.register_action_handler(tx.clone())?;
+        self.localization_checker.register_action_handler(tx.clone())?;
+        self.texture_tools.register_action_handler(tx.clone())?;
+        self.atlas_packer.register_action_handler(tx.clone())?;
+        self.release_builder.register_action_handler(tx.clone())?;
+        self.index_submission.register_action_handler(tx.clone())?;
+        self.dev_linker.register_action_handler(tx.clone())?;
+        self.dev_watcher.register_action_handler(tx.clone())?;
+        self.isolated_launch.register_action_handler(tx.clone())?;
+        if let Some(wizard) = &mut self.wizard {
+            wizard.register_action_handler(tx)?;
+        }
         Ok(())
     }
 
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.index_submission.register_config_handler(config)
+    }
+
     fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let Some(wizard) = &mut self.wizard {
+            wizard.handle_key_event(key)?;
+            if wizard.created {
+                self.wizard = None;
+                self.edited_mod = Mod::from_directory(self.mod_path.as_path()).unwrap();
+                self.current_tool().focus();
+            }
+            return Ok(None);
+        }
         match key.code {
-            KeyCode::Up => {
-            },
-            KeyCode::Down => {
-            },
-            _ => {}
+            KeyCode::Tab => self.next_tool(true),
+            KeyCode::BackTab => self.next_tool(false),
+            _ => return self.current_tool().handle_key_event(key),
         }
         Ok(None)
     }
 
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        self.source_browser.update(action.clone())?;
+        self.patch_checker.update(action.clone())?;
+        self.dump_viewer.update(action.clone())?;
+        self.metadata_checker.update(action.clone())?;
+        self.dev_watcher.update(action.clone())?;
+        self.isolated_launch.update(action)
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -81,7 +266,7 @@ impl Component for AuthoringTools {
                 Paragraph::new(Line::from(vec![
                     Span::from("No mod was found at "),
                     Span::styled(format!("{}", self.mod_path.display()), Style::default().fg(Color::Yellow)),
-                    Span::from(", sorry! Make sure there is a metadata JSON file in the root of the mod directory."),
+                    Span::from(". Fill in the form below to make one, or run this from a mod directory with a metadata JSON file."),
                 ]))
                     .style(Style::default())
                     .block(
@@ -120,11 +305,14 @@ impl Component for AuthoringTools {
                                 }
                             )
                             .title(format!("Editing mod at {}", self.mod_path.display()))
+                            .title_bottom(self.tool_tabs())
                     ),
                 chunks[0]
             );
         }
 
+        self.current_tool().draw(frame, chunks[1])?;
+
         Ok(())
     }
 }
@@ -0,0 +1,205 @@
+use std::path::PathBuf;
+
+use balatro_tui::gamesource::GameSource;
+use balatro_tui::lovely::collisions::{Collision, CollisionKind, ModPatch, find_collisions};
+use balatro_tui::lovely::patch::mod_patches;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::warn;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::components::gamesource::Extraction;
+
+fn kind_color(kind: CollisionKind) -> Color {
+    match kind {
+        CollisionKind::Conflict | CollisionKind::Splits => Color::Red,
+        CollisionKind::SameAnchor => Color::Yellow,
+    }
+}
+
+fn patch_line(patch: &ModPatch) -> Line<'static> {
+    Line::from(vec![
+        Span::from("    "),
+        Span::styled(patch.mod_name.clone(), Style::default().fg(Color::Yellow)),
+        Span::from(format!(" (priority {}) ", patch.patch.priority)),
+        Span::styled(
+            format!(
+                "{} #{} {} {}",
+                patch
+                    .patch
+                    .file
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+                patch.patch.index,
+                patch.patch.kind_name(),
+                patch.patch.position
+            ),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
+}
+
+/// Lovely patches of enabled mods that touch the same lines of the game.
+pub struct PatchCollisions {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    /// Set when the list wants to be closed.
+    pub closed: bool,
+    /// The collisions, or why the game couldn't be checked.
+    collisions: Result<Vec<Collision>, String>,
+    /// The mods to check once the game is extracted.
+    mods: Vec<(String, PathBuf)>,
+    extraction: Option<Extraction>,
+    checked_mods: usize,
+    scroll: u16,
+    view_height: u16,
+}
+
+impl PatchCollisions {
+    /// Checks the patches of the given mods, as pairs of name and folder.
+    pub fn new(mods: Vec<(String, PathBuf)>) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: true,
+            closed: false,
+            collisions: Ok(vec![]),
+            checked_mods: mods.len(),
+            mods,
+            extraction: Some(Extraction::start()),
+            scroll: 0,
+            view_height: 0,
+        }
+    }
+
+    fn check(&mut self, game: Result<GameSource, String>) {
+        let mods = std::mem::take(&mut self.mods);
+        self.collisions = game.map(|game| {
+            let patches: Vec<(String, _)> = mods
+                .into_iter()
+                .map(|(name, folder)| {
+                    let (patches, errors) = mod_patches(&folder);
+                    for (file, e) in errors {
+                        warn!("Skipping {}: {}", file.display(), e);
+                    }
+                    (name, patches)
+                })
+                .collect();
+            find_collisions(&patches, &game.dir)
+        });
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        if self.extraction.is_some() {
+            return vec![Line::from("Extracting the game from Balatro.exe...")];
+        }
+        let collisions = match &self.collisions {
+            Ok(collisions) => collisions,
+            Err(e) => return vec![Line::styled(e.clone(), Style::default().fg(Color::Red))],
+        };
+        if collisions.is_empty() {
+            return vec![Line::from(format!(
+                "No collisions between the Lovely patches of {} enabled mods.",
+                self.checked_mods
+            ))];
+        }
+        let mut lines = vec![];
+        for collision in collisions {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{} lines {}-{}: ",
+                        collision.target,
+                        collision.lines.start + 1,
+                        collision.lines.end
+                    ),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    collision.kind.to_string(),
+                    Style::default().fg(kind_color(collision.kind)),
+                ),
+            ]));
+            lines.push(patch_line(&collision.first));
+            lines.push(patch_line(&collision.second));
+            lines.push(Line::styled(
+                if collision.order_is_fixed() {
+                    "    applied in this order, lowest priority first"
+                } else {
+                    "    equal priorities, so the order mods are loaded in decides"
+                },
+                Style::default().fg(Color::Gray),
+            ));
+            lines.push(Line::from(""));
+        }
+        lines
+    }
+}
+
+impl Component for PatchCollisions {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.view_height),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.view_height),
+            KeyCode::Esc => self.closed = true,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::Tick = action
+            && let Some(game) = self.extraction.as_ref().and_then(Extraction::finished)
+        {
+            self.extraction = None;
+            self.check(game);
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines = self.lines();
+        self.view_height = area.height.saturating_sub(2);
+        let max_scroll = (lines.len() as u16).saturating_sub(self.view_height);
+        self.scroll = self.scroll.min(max_scroll);
+        let count = self.collisions.as_ref().map(Vec::len).unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!("Lovely patch collisions ({count})"))
+                    .title_bottom(Line::from(" esc back ").right_aligned())
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
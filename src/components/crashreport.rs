@@ -0,0 +1,329 @@
+use std::path::PathBuf;
+
+use balatro_tui::lovely::log::{list_logs, log_dir};
+use balatro_tui::open;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::{error, info};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Wrap};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Eventable};
+use crate::action::Action;
+use crate::components::optionselector::{Actions, OptionSelector, OptionSelectorText};
+use crate::crash::{CrashReport, FrameSource};
+use crate::mods::{Mod, ModList};
+
+/// How many stack frames to show in the summary.
+const SHOWN_FRAMES: usize = 8;
+
+enum QuickAction {
+    DisableMod(usize),
+    OpenModFolder(usize),
+    OpenLog,
+}
+
+pub struct CrashReportView {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    logs: Vec<PathBuf>,
+    selected_log: usize,
+    report: Option<CrashReport>,
+    mods: Vec<Mod>,
+    /// Index into `mods` of the suspected mod.
+    suspect: Option<usize>,
+    options: OptionSelector,
+    quick_actions: Vec<QuickAction>,
+    local_action_tx: mpsc::UnboundedSender<Actions>,
+    local_action_rx: mpsc::UnboundedReceiver<Actions>,
+    loaded: bool,
+}
+
+impl CrashReportView {
+    pub fn new() -> Self {
+        let mut options = OptionSelector::new(vec![]);
+        options.title = "Actions".to_string();
+
+        let (local_tx, local_rx) = mpsc::unbounded_channel();
+
+        Self {
+            action_tx: None,
+            has_focus: false,
+            logs: vec![],
+            selected_log: 0,
+            report: None,
+            mods: vec![],
+            suspect: None,
+            options,
+            quick_actions: vec![],
+            local_action_tx: local_tx,
+            local_action_rx: local_rx,
+            loaded: false,
+        }
+    }
+
+    fn reload(&mut self) {
+        self.loaded = true;
+        self.logs = list_logs(&log_dir());
+        self.selected_log = self.selected_log.min(self.logs.len().saturating_sub(1));
+        self.mods = ModList::get_local_mods();
+
+        self.report = self.logs.get(self.selected_log).and_then(|path| {
+            let text = match std::fs::read(path) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+                Err(e) => {
+                    error!("Failed to read {}: {}", path.display(), e);
+                    return None;
+                }
+            };
+            let lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
+            CrashReport::find(path.clone(), &lines)
+        });
+
+        self.suspect = self.report.as_ref().and_then(|r| {
+            let suspect = r.suspect(&self.mods)?;
+            self.mods.iter().position(|m| std::ptr::eq(m, suspect))
+        });
+        self.build_options();
+    }
+
+    fn build_options(&mut self) {
+        self.quick_actions.clear();
+        if let Some(i) = self.suspect {
+            // force enabled mods like Steamodded can't be turned off
+            if self.mods[i].enabled.unwrap_or(true) && !self.mods[i].force_enable {
+                self.quick_actions.push(QuickAction::DisableMod(i));
+            }
+            self.quick_actions.push(QuickAction::OpenModFolder(i));
+        }
+        if !self.logs.is_empty() {
+            self.quick_actions.push(QuickAction::OpenLog);
+        }
+
+        self.options.options = self
+            .quick_actions
+            .iter()
+            .map(|a| {
+                let text = match a {
+                    QuickAction::DisableMod(i) => format!("Disable {}", self.mods[*i].name),
+                    QuickAction::OpenModFolder(i) => {
+                        format!("Open {}'s folder", self.mods[*i].name)
+                    }
+                    QuickAction::OpenLog => "Open log file".to_string(),
+                };
+                vec![OptionSelectorText::new(text, Style::default())]
+            })
+            .collect();
+        self.options.selected = 0;
+    }
+
+    fn run_quick_action(&mut self, index: usize) -> Result<()> {
+        match self.quick_actions.get(index) {
+            Some(QuickAction::DisableMod(i)) => {
+                let m = &mut self.mods[*i];
+                match m.set_enabled(false) {
+                    Ok(()) => {
+                        info!("Disabled {}", m.name);
+                        if let Some(tx) = &self.action_tx {
+                            tx.send(Action::ModsChanged)?;
+                        }
+                    }
+                    Err(e) => error!("Failed to disable {}: {}", m.name, e),
+                }
+                self.build_options();
+            }
+            Some(QuickAction::OpenModFolder(i)) => open(self.mods[*i].folder.to_str().unwrap()),
+            Some(QuickAction::OpenLog) => match &self.report {
+                Some(report) => open(report.log.to_str().unwrap()),
+                None => open(self.logs[self.selected_log].to_str().unwrap()),
+            },
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn summary(&self) -> Vec<Line<'static>> {
+        let Some(report) = &self.report else {
+            return vec![
+                Line::from("No crash found in this session's log."),
+                Line::from("Use [ and ] to look at older sessions.")
+                    .style(Style::default().fg(Color::Gray)),
+            ];
+        };
+
+        let mut lines = vec![];
+        match self.suspect.map(|i| &self.mods[i]) {
+            Some(m) => lines.push(Line::from(vec![
+                Span::from("Suspected mod: "),
+                Span::styled(
+                    m.name.clone(),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!(" ({}) in {}", m.id, m.folder.display()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])),
+            None => lines.push(Line::from(Span::styled(
+                "No installed mod appears in the trace, this may be a vanilla or Lovely crash.",
+                Style::default().fg(Color::Gray),
+            ))),
+        }
+        let unknown = report.unknown_sources(&self.mods);
+        if !unknown.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "Also in the trace, but not installed: {}",
+                    unknown.join(", ")
+                ),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            report.message.clone(),
+            Style::default().fg(Color::Red),
+        )));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Crashed at line {} of {}",
+                report.line_index + 1,
+                report.log.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.push(Line::from(""));
+        lines.push(Line::from("Top frames:"));
+        for frame in report.frames.iter().take(SHOWN_FRAMES) {
+            let owner = self.mods.iter().find(|m| frame.is_from(m));
+            let (label, style) = match (&frame.source, owner) {
+                (_, Some(m)) => (m.name.clone(), Style::default().fg(Color::Yellow)),
+                (FrameSource::Mod { id, folder }, None) => (
+                    id.clone().or(folder.clone()).unwrap_or_default(),
+                    Style::default().fg(Color::LightRed),
+                ),
+                (FrameSource::Game, None) => {
+                    ("game".to_string(), Style::default().fg(Color::DarkGray))
+                }
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {label:<20} "), style),
+                Span::from(format!("{}:{}", frame.file, frame.line)),
+            ]));
+        }
+        lines
+    }
+}
+
+impl Component for CrashReportView {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx.clone());
+        self.options.register_action_handler(tx)?;
+        self.options
+            .register_local_action_handler(self.local_action_tx.clone())?;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('[') => {
+                if self.selected_log + 1 < self.logs.len() {
+                    self.selected_log += 1;
+                    self.reload();
+                }
+            }
+            KeyCode::Char(']') => {
+                if self.selected_log > 0 {
+                    self.selected_log -= 1;
+                    self.reload();
+                }
+            }
+            KeyCode::Char('r') => self.reload(),
+            _ => {
+                self.options.handle_key_event(key)?;
+            }
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick => {
+                if !self.loaded {
+                    self.reload();
+                }
+                if let Ok(Actions::Selected(c)) = self.local_action_rx.try_recv() {
+                    self.run_quick_action(c)?;
+                }
+            }
+            Action::GameExited => {
+                // look at the session that just ended
+                self.selected_log = 0;
+                self.reload();
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length(self.options.options.len() as u16 + 2),
+            ])
+            .split(area);
+
+        let title = match self.logs.get(self.selected_log) {
+            Some(path) => format!(
+                "Crash report for {} ({}/{})",
+                path.file_name().unwrap_or_default().to_string_lossy(),
+                self.selected_log + 1,
+                self.logs.len()
+            ),
+            None => "Crash report (no Lovely logs found)".to_string(),
+        };
+
+        frame.render_widget(
+            Paragraph::new(self.summary())
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(title)
+                        .title_bottom(
+                            Line::from(" [/] older/newer session, r reload ").right_aligned(),
+                        )
+                        .border_style(if self.has_focus {
+                            Style::default().fg(Color::LightCyan)
+                        } else {
+                            Style::default().fg(Color::White)
+                        }),
+                ),
+            chunks[0],
+        );
+        self.options.draw(frame, chunks[1])?;
+
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.options.focus();
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+        self.options.unfocus();
+    }
+}
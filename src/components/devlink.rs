@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use balatro_tui::devlink::{self, LinkState};
+use balatro_tui::get_balatro_appdata_dir;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::info;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Wrap};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+
+/// Links the mod being edited into the game's Mods folder, so changes don't need copying over.
+pub struct DevLinker {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    mods_dir: PathBuf,
+    id: Option<String>,
+    state: LinkState,
+    /// Other installed mods with the same id, which the game would load as well.
+    copies: Vec<PathBuf>,
+    error: Option<String>,
+}
+
+impl DevLinker {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            mods_dir: PathBuf::new(),
+            id: None,
+            state: LinkState::NotLinked,
+            copies: vec![],
+            error: None,
+        }
+    }
+
+    fn reload(&mut self) {
+        self.mods_dir = get_balatro_appdata_dir().join("Mods");
+        self.id = devlink::mod_id(&self.mod_path);
+        let Some(id) = &self.id else {
+            return;
+        };
+        self.state = devlink::link_state(&self.mods_dir, id, &self.mod_path);
+        self.copies = devlink::installed_copies(&self.mods_dir, id, &self.mod_path);
+        // an occupied link path is the copy that is in the way
+        let link = devlink::link_path(&self.mods_dir, id);
+        self.copies.retain(|c| *c != link);
+    }
+
+    fn set_linked(&mut self, linked: bool) -> Result<()> {
+        let Some(id) = self.id.clone() else {
+            return Ok(());
+        };
+        let result = if linked {
+            devlink::link(&self.mods_dir, &id, &self.mod_path).map(|link| {
+                info!("Linked {} to {}", link.display(), self.mod_path.display());
+            })
+        } else {
+            devlink::unlink(&self.mods_dir, &id, &self.mod_path).map(|()| {
+                info!("Unlinked {} from the Mods folder", id);
+            })
+        };
+        self.error = result.err().map(|e| e.to_string());
+        self.reload();
+        if let Some(tx) = &self.action_tx {
+            tx.send(Action::ModsChanged)?;
+        }
+        Ok(())
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let Some(id) = &self.id else {
+            return vec![Line::from(
+                "The mod needs a metadata JSON with an id before it can be linked.",
+            )];
+        };
+        let link = devlink::link_path(&self.mods_dir, id);
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![match self.state {
+            LinkState::Linked => Line::from(vec![
+                Span::styled("Linked", bold.fg(Color::Green)),
+                Span::from(format!(
+                    ": the game loads this mod from {} through {}",
+                    self.mod_path.display(),
+                    link.display()
+                )),
+            ]),
+            LinkState::NotLinked => Line::from(vec![
+                Span::styled("Not linked", bold),
+                Span::from(format!(", l links it as {}", link.display())),
+            ]),
+            LinkState::Occupied => Line::from(vec![
+                Span::styled("Can't link", bold.fg(Color::Red)),
+                Span::from(format!(
+                    ": {} is already an installed mod, remove it to link this one",
+                    link.display()
+                )),
+            ]),
+        }];
+        if !self.copies.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(
+                format!("Also installed with the id {id}, the game will load these too:"),
+                Style::default().fg(Color::Yellow),
+            ));
+            lines.extend(self.copies.iter().map(|c| {
+                Line::styled(
+                    format!("    {}", c.display()),
+                    Style::default().fg(Color::Yellow),
+                )
+            }));
+        }
+        if let Some(e) = &self.error {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(e.clone(), Style::default().fg(Color::Red)));
+        }
+        lines
+    }
+}
+
+impl Component for DevLinker {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('l') => self.set_linked(true)?,
+            KeyCode::Char('u') => self.set_linked(false)?,
+            KeyCode::Char('r') => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        frame.render_widget(
+            Paragraph::new(self.lines())
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title("Link into the game's Mods folder")
+                        .title_bottom(
+                            Line::from(" l link into game, u unlink, r refresh ").right_aligned(),
+                        )
+                        .border_style(if self.has_focus {
+                            Style::default().fg(Color::LightCyan)
+                        } else {
+                            Style::default().fg(Color::White)
+                        }),
+                ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
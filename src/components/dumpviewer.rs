@@ -0,0 +1,279 @@
+use std::path::PathBuf;
+
+use balatro_tui::gamesource::GameSource;
+use balatro_tui::lovely::dump::{Hunk, diff_hunks, dump_dir, list_dumps, payload_lines};
+use balatro_tui::lovely::patch::mod_patches;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::error;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::components::gamesource::Extraction;
+use crate::components::patchcheck::diff_line;
+use crate::mods::ModList;
+
+enum View {
+    Files,
+    Diff {
+        file: PathBuf,
+        lines: Vec<Line<'static>>,
+    },
+}
+
+/// The payload lines of every enabled mod, to attribute hunks with.
+fn enabled_mod_payloads() -> Vec<(String, Vec<String>)> {
+    ModList::get_local_mods()
+        .into_iter()
+        .filter(|m| m.enabled.unwrap_or(true))
+        .map(|m| {
+            let (patches, _) = mod_patches(&m.folder);
+            let lines = patches
+                .iter()
+                .flat_map(|p| payload_lines(p, &m.folder))
+                .collect();
+            (m.name, lines)
+        })
+        .collect()
+}
+
+fn hunk_lines(hunk: &Hunk) -> Vec<Line<'static>> {
+    let by = if hunk.mods.is_empty() {
+        Span::styled(" unknown mod", Style::default().fg(Color::DarkGray))
+    } else {
+        Span::styled(
+            format!(" from {}", hunk.mods.join(", ")),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+    };
+    let mut lines = vec![Line::from(vec![
+        Span::styled(hunk.header.clone(), Style::default().fg(Color::Cyan)),
+        by,
+    ])];
+    lines.extend(hunk.lines.iter().map(|l| diff_line(l)));
+    lines
+}
+
+/// Lists what Lovely dumped and diffs it against the vanilla source.
+pub struct DumpViewer {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    game: Option<Result<GameSource, String>>,
+    extraction: Option<Extraction>,
+    /// The dumped files, or why they couldn't be listed.
+    files: Result<Vec<PathBuf>, String>,
+    payloads: Vec<(String, Vec<String>)>,
+    view: View,
+    selected: usize,
+    scroll: u16,
+    view_height: u16,
+}
+
+impl DumpViewer {
+    pub fn new() -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            game: None,
+            extraction: None,
+            files: Ok(vec![]),
+            payloads: vec![],
+            view: View::Files,
+            selected: 0,
+            scroll: 0,
+            view_height: 0,
+        }
+    }
+
+    fn reload(&mut self) {
+        // retried until it works, the game may have been installed or fixed since
+        if !matches!(self.game, Some(Ok(_))) {
+            self.game = None;
+            self.extraction.get_or_insert_with(Extraction::start);
+        }
+        let dir = dump_dir();
+        self.files = list_dumps(&dir).map_err(|e| {
+            format!(
+                "No Lovely dump in {} ({}). Lovely writes one when the game starts with mods.",
+                dir.display(),
+                e
+            )
+        });
+        self.payloads = enabled_mod_payloads();
+        self.selected = 0;
+        self.view = View::Files;
+    }
+
+    fn open_selected(&mut self) {
+        let (Ok(files), Some(Ok(game))) = (&self.files, &self.game) else {
+            return;
+        };
+        let Some(file) = files.get(self.selected).cloned() else {
+            return;
+        };
+        let dumped = match std::fs::read_to_string(dump_dir().join(&file)) {
+            Ok(dumped) => dumped,
+            Err(e) => {
+                error!("Failed to read {}: {}", file.display(), e);
+                return;
+            }
+        };
+        let lines = match std::fs::read_to_string(game.dir.join(&file)) {
+            Ok(vanilla) => {
+                let hunks = diff_hunks(&vanilla, &dumped, &self.payloads);
+                if hunks.is_empty() {
+                    vec![Line::from("The dump is the same as the vanilla file.")]
+                } else {
+                    hunks.iter().flat_map(hunk_lines).collect()
+                }
+            }
+            // made by a mod, so the whole file is new
+            Err(_) => {
+                let mut lines = vec![Line::styled(
+                    "Not a vanilla file, showing the dump as is.",
+                    Style::default().fg(Color::DarkGray),
+                )];
+                lines.extend(dumped.lines().map(|l| Line::from(l.replace('\t', "    "))));
+                lines
+            }
+        };
+        self.view = View::Diff { file, lines };
+        self.scroll = 0;
+    }
+
+    fn file_lines(&self) -> Vec<Line<'static>> {
+        if let Some(Err(e)) = &self.game {
+            return vec![Line::styled(e.clone(), Style::default().fg(Color::Red))];
+        }
+        if self.extraction.is_some() {
+            return vec![Line::from("Extracting the game from Balatro.exe...")];
+        }
+        let files = match &self.files {
+            Ok(files) => files,
+            Err(e) => return vec![Line::from(e.clone())],
+        };
+        if files.is_empty() {
+            return vec![Line::from("Lovely didn't dump any files.")];
+        }
+        let start = (self.selected + 1).saturating_sub(self.view_height as usize);
+        files
+            .iter()
+            .enumerate()
+            .skip(start)
+            .map(|(i, f)| {
+                if i == self.selected {
+                    Line::styled(
+                        f.display().to_string(),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Line::from(f.display().to_string())
+                }
+            })
+            .collect()
+    }
+}
+
+impl Component for DumpViewer {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let View::Diff { .. } = self.view {
+            match key.code {
+                KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+                KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+                KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.view_height),
+                KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.view_height),
+                KeyCode::Esc => self.view = View::Files,
+                _ => {}
+            }
+            return Ok(None);
+        }
+        let count = self.files.as_ref().map(Vec::len).unwrap_or_default();
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(count.saturating_sub(1)),
+            KeyCode::Enter => self.open_selected(),
+            KeyCode::Char('r') => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick => {
+                if let Some(game) = self.extraction.as_ref().and_then(Extraction::finished) {
+                    self.extraction = None;
+                    self.game = Some(game);
+                }
+            }
+            // Lovely writes a fresh dump every launch
+            Action::GameExited if self.game.is_some() => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.view_height = area.height.saturating_sub(2);
+        let (title, hints, lines, scroll) = match &self.view {
+            View::Files => (
+                format!("Lovely dump in {}", dump_dir().display()),
+                " enter diff against vanilla, r reload ",
+                self.file_lines(),
+                0,
+            ),
+            View::Diff { file, lines } => {
+                let max_scroll = (lines.len() as u16).saturating_sub(self.view_height);
+                self.scroll = self.scroll.min(max_scroll);
+                (
+                    format!("{} vanilla vs. dumped", file.display()),
+                    " esc back ",
+                    lines.clone(),
+                    self.scroll,
+                )
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(lines).scroll((scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title)
+                    .title_bottom(Line::from(hints).right_aligned())
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        if self.game.is_none() && self.extraction.is_none() {
+            self.reload();
+        }
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
@@ -0,0 +1,473 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, TryRecvError, channel};
+use std::thread;
+
+use balatro_tui::gamesource::{GameSource, Hit};
+use balatro_tui::get_balatro_dir;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::{error, info};
+use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::components::textinput::TextInput;
+use crate::config::get_data_dir;
+
+/// Full-text searches stop after this many matching lines.
+const SEARCH_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Typing {
+    Filter,
+    Search,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum View {
+    Files,
+    Hits,
+    /// A file, and the view to go back to.
+    File {
+        path: PathBuf,
+        from: Box<View>,
+    },
+}
+
+/// Browses the vanilla source extracted from `Balatro.exe`.
+pub struct GameSourceBrowser {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    /// The extracted source, or why it couldn't be extracted.
+    source: Result<Option<GameSource>, String>,
+    extraction: Option<Extraction>,
+    files: Vec<PathBuf>,
+    /// Indices into `files` matching the filter, best match first.
+    shown: Vec<usize>,
+    hits: Vec<Hit>,
+    lines: Vec<String>,
+    view: View,
+    typing: Option<Typing>,
+    input: TextInput,
+    filter: String,
+    selected: usize,
+    scroll: u16,
+    view_height: u16,
+    loaded: bool,
+}
+
+impl Default for GameSourceBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn cache_dir() -> PathBuf {
+    get_data_dir().join("game-source")
+}
+
+/// Extracts the vanilla game into the cache, or finds the copy that is already there.
+pub fn extract_game_source() -> Result<GameSource, String> {
+    let exe = get_balatro_dir().join("Balatro.exe");
+    GameSource::extract(&exe, &cache_dir())
+        .map_err(|e| format!("Failed to extract the game from {}: {}", exe.display(), e))
+}
+
+/// [`extract_game_source`] running on its own thread, as it reads all of `Balatro.exe`.
+pub struct Extraction(Receiver<Result<GameSource, String>>);
+
+impl Extraction {
+    pub fn start() -> Self {
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let _ = tx.send(extract_game_source());
+        });
+        Self(rx)
+    }
+
+    /// The result once the extraction is done, polled on ticks.
+    pub fn finished(&self) -> Option<Result<GameSource, String>> {
+        match self.0.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(Err("The game extraction stopped unexpectedly".to_string()))
+            }
+        }
+    }
+}
+
+fn highlighted(text: String, selected: bool) -> Line<'static> {
+    if selected {
+        Line::styled(
+            text,
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Line::from(text)
+    }
+}
+
+impl GameSourceBrowser {
+    pub fn new() -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            source: Ok(None),
+            extraction: None,
+            files: vec![],
+            shown: vec![],
+            hits: vec![],
+            lines: vec![],
+            view: View::Files,
+            typing: None,
+            input: TextInput::new(),
+            filter: String::new(),
+            selected: 0,
+            scroll: 0,
+            view_height: 0,
+            loaded: false,
+        }
+    }
+
+    /// Extracts the source the first time it's needed, finished by `loaded_source`.
+    fn load(&mut self) {
+        self.loaded = true;
+        self.extraction = Some(Extraction::start());
+    }
+
+    fn loaded_source(&mut self, source: Result<GameSource, String>) {
+        self.source = source.map(Some);
+        match &self.source {
+            Ok(Some(source)) => {
+                info!(
+                    "Balatro {} source is in {}",
+                    source.version,
+                    source.dir.display()
+                );
+                self.files = source.files().unwrap_or_default();
+            }
+            Err(e) => error!("{}", e),
+            _ => {}
+        }
+        self.apply_filter();
+    }
+
+    fn apply_filter(&mut self) {
+        self.selected = 0;
+        if self.filter.is_empty() {
+            self.shown = (0..self.files.len()).collect();
+            return;
+        }
+        let names: Vec<String> = self
+            .files
+            .iter()
+            .map(|f| f.to_string_lossy().into_owned())
+            .collect();
+        let mut matcher = Matcher::new(Config::DEFAULT.match_paths());
+        let matches = Pattern::parse(&self.filter, CaseMatching::Ignore, Normalization::Smart)
+            .match_list(names.iter().map(String::as_str), &mut matcher);
+        self.shown = matches
+            .iter()
+            .filter_map(|(name, _)| names.iter().position(|n| n == name))
+            .collect();
+    }
+
+    fn search(&mut self, query: &str) {
+        let Ok(Some(source)) = &self.source else {
+            return;
+        };
+        match source.search(query, SEARCH_LIMIT) {
+            Ok(hits) => {
+                if hits.len() == SEARCH_LIMIT {
+                    info!(
+                        "Showing the first {} lines matching '{}'",
+                        SEARCH_LIMIT, query
+                    );
+                }
+                self.hits = hits;
+                self.selected = 0;
+                self.view = View::Hits;
+            }
+            Err(e) => error!("Search failed: {}", e),
+        }
+    }
+
+    fn open(&mut self, path: PathBuf, line: usize) {
+        let Ok(Some(source)) = &self.source else {
+            return;
+        };
+        match source.read(&path) {
+            Ok(text) => {
+                self.lines = text.lines().map(|l| l.replace('\t', "    ")).collect();
+                self.scroll = line.saturating_sub(1) as u16;
+                let from = Box::new(self.view.clone());
+                self.view = View::File { path, from };
+            }
+            Err(e) => error!("Failed to read {}: {}", path.display(), e),
+        }
+    }
+
+    fn open_selected(&mut self) {
+        match self.view {
+            View::Files => {
+                if let Some(i) = self.shown.get(self.selected) {
+                    self.open(self.files[*i].clone(), 1);
+                }
+            }
+            View::Hits => {
+                if let Some(hit) = self.hits.get(self.selected) {
+                    self.open(hit.file.clone(), hit.line);
+                }
+            }
+            View::File { .. } => {}
+        }
+    }
+
+    fn list_len(&self) -> usize {
+        match self.view {
+            View::Files => self.shown.len(),
+            View::Hits => self.hits.len(),
+            View::File { .. } => self.lines.len(),
+        }
+    }
+
+    fn start_typing(&mut self, typing: Typing) {
+        self.typing = Some(typing);
+        self.input.text = match typing {
+            Typing::Filter => self.filter.clone(),
+            Typing::Search => String::new(),
+        };
+        self.input.title = match typing {
+            Typing::Filter => "Find file".to_string(),
+            Typing::Search => "Search the Lua files (enter to search)".to_string(),
+        };
+        self.input.focus();
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        if let View::File { .. } = self.view {
+            self.scroll = self.scroll.saturating_add_signed(delta as i16);
+            return;
+        }
+        let last = self.list_len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn title(&self) -> String {
+        let version = match &self.source {
+            Ok(Some(source)) => format!("Balatro {}", source.version),
+            _ => "Balatro".to_string(),
+        };
+        match &self.view {
+            View::Files if self.filter.is_empty() => {
+                format!("{} source ({} files)", version, self.files.len())
+            }
+            View::Files => format!(
+                "{} source ({} of {} files match '{}')",
+                version,
+                self.shown.len(),
+                self.files.len(),
+                self.filter
+            ),
+            View::Hits => format!("{} source ({} matching lines)", version, self.hits.len()),
+            View::File { path, .. } => format!("{} {}", version, path.display()),
+        }
+    }
+
+    fn body(&self) -> Vec<Line<'static>> {
+        match &self.source {
+            Err(e) => {
+                return vec![Line::from(Span::styled(
+                    e.clone(),
+                    Style::default().fg(Color::Red),
+                ))];
+            }
+            Ok(None) => return vec![Line::from("Extracting the game...")],
+            Ok(Some(_)) => {}
+        }
+        // only build the lines that can be seen
+        let start = if self.selected >= self.view_height as usize {
+            self.selected + 1 - self.view_height as usize
+        } else {
+            0
+        };
+        let end = start + self.view_height as usize;
+        match &self.view {
+            View::Files => self
+                .shown
+                .iter()
+                .enumerate()
+                .skip(start)
+                .take(end - start)
+                .map(|(i, f)| highlighted(self.files[*f].display().to_string(), i == self.selected))
+                .collect(),
+            View::Hits if self.hits.is_empty() => vec![Line::from("No matches.")],
+            View::Hits => self
+                .hits
+                .iter()
+                .enumerate()
+                .skip(start)
+                .take(end - start)
+                .map(|(i, hit)| {
+                    let mut line = highlighted(hit.text.clone(), i == self.selected);
+                    line.spans.insert(
+                        0,
+                        Span::styled(
+                            format!("{}:{} ", hit.file.display(), hit.line),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    );
+                    line
+                })
+                .collect(),
+            View::File { .. } => {
+                let width = self.lines.len().to_string().len();
+                self.lines
+                    .iter()
+                    .enumerate()
+                    .skip(self.scroll as usize)
+                    .take(self.view_height as usize)
+                    .map(|(i, text)| {
+                        Line::from(vec![
+                            Span::styled(
+                                format!("{:>width$} ", i + 1),
+                                Style::default().fg(Color::DarkGray),
+                            ),
+                            Span::from(text.clone()),
+                        ])
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl Component for GameSourceBrowser {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let Some(typing) = self.typing {
+            match key.code {
+                KeyCode::Enter | KeyCode::Esc => {
+                    self.typing = None;
+                    self.input.unfocus();
+                    if typing == Typing::Search && key.code == KeyCode::Enter {
+                        let query = self.input.text.clone();
+                        self.search(&query);
+                    }
+                }
+                _ => {
+                    self.input.handle_key_event(key)?;
+                    if typing == Typing::Filter {
+                        self.filter = self.input.text.clone();
+                        self.apply_filter();
+                    }
+                }
+            }
+            return Ok(None);
+        }
+        let page = self.view_height.max(1) as isize;
+        match key.code {
+            KeyCode::Up => self.move_by(-1),
+            KeyCode::Down => self.move_by(1),
+            KeyCode::PageUp => self.move_by(-page),
+            KeyCode::PageDown => self.move_by(page),
+            KeyCode::Enter => self.open_selected(),
+            KeyCode::Char('/') => {
+                self.view = View::Files;
+                self.start_typing(Typing::Filter);
+            }
+            KeyCode::Char('g') => self.start_typing(Typing::Search),
+            KeyCode::Esc => match &self.view {
+                View::File { from, .. } => self.view = (**from).clone(),
+                View::Hits => {
+                    self.view = View::Files;
+                    self.selected = 0;
+                }
+                View::Files if !self.filter.is_empty() => {
+                    self.filter.clear();
+                    self.apply_filter();
+                }
+                View::Files => {}
+            },
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::Tick = action
+            && let Some(source) = self.extraction.as_ref().and_then(Extraction::finished)
+        {
+            self.extraction = None;
+            self.loaded_source(source);
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(if self.typing.is_some() {
+                vec![Constraint::Min(3), Constraint::Length(3)]
+            } else {
+                vec![Constraint::Min(3)]
+            })
+            .split(area);
+        self.view_height = chunks[0].height.saturating_sub(2);
+        if let View::File { .. } = self.view {
+            let max_scroll = (self.lines.len() as u16).saturating_sub(self.view_height);
+            self.scroll = self.scroll.min(max_scroll);
+        }
+
+        let hints = match self.view {
+            View::File { .. } => " esc back ",
+            _ => " enter open, / find file, g search text, esc back ",
+        };
+        frame.render_widget(
+            Paragraph::new(self.body()).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(self.title())
+                    .title_bottom(Line::from(hints).right_aligned())
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            chunks[0],
+        );
+        if self.typing.is_some() {
+            self.input.draw(frame, chunks[1])?;
+        }
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        if !self.loaded {
+            self.load();
+        }
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+        self.typing = None;
+        self.input.unfocus();
+    }
+}
@@ -5,17 +5,24 @@ use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
 use tokio::sync::mpsc::UnboundedSender;
-use log::{info, warn};
+use log::{error, info, warn};
 use tui_logger::{TuiLoggerLevelOutput, TuiLoggerWidget};
+use balatro_tui::game::{GameEvent, GameStatus, GameSupervisor, format_uptime};
 use balatro_tui::{clone_online_mod_list, get_repo_at, update_repo};
 use super::Component;
 use crate::{action::Action, config::Config};
 use crate::app::App;
 use crate::components::authoring::AuthoringTools;
+use crate::components::crashreport::CrashReportView;
+use crate::components::logviewer::LogViewer;
 use crate::components::modlist::ModlistComponent;
 use crate::components::optionselector::{OptionSelector, OptionSelectorText};
 use crate::components::quickoptions::QuickOptions;
 use crate::components::remotemods::RemoteModsComponent;
+use crate::components::settingseditor::SettingsEditor;
+use crate::components::runviewer::RunViewer;
+use crate::components::saves::{SaveBackups, backup_profiles};
+use crate::components::stats::StatsView;
 use crate::config::get_data_dir;
 use crate::mods::{Mod, ModList, RemoteMod};
 use crate::tui::Event;
@@ -25,9 +32,15 @@ enum Focused {
     #[default]
     Modes,
     InstalledMods,
+    Stats,
     RemoteMods,
     Authoring,
     Quicks,
+    Logs,
+    CrashReport,
+    Settings,
+    Saves,
+    Run,
 }
 
 #[derive(Default)]
@@ -50,8 +63,15 @@ pub struct Home {
     mode_selector: OptionSelector,
     focused: Focused,
     authoring: AuthoringTools,
+    log_viewer: LogViewer,
+    crash_report: CrashReportView,
+    saves: SaveBackups,
+    run_viewer: RunViewer,
+    stats: StatsView,
     has_focus: bool,
     state: DrawingState,
+    settings_editor: SettingsEditor,
+    game: GameSupervisor,
 }
 
 impl Home {
@@ -61,8 +81,14 @@ impl Home {
         let mut mode_selector = OptionSelector::new(vec![
             vec![OptionSelectorText::new("Quick Options".to_string(), Style::default())],
             vec![OptionSelectorText::new("Installed Mods".to_string(), Style::default())],
+            vec![OptionSelectorText::new("Stats".to_string(), Style::default())],
             vec![OptionSelectorText::new("Find New Mods".to_string(), Style::default())],
             vec![OptionSelectorText::new("Mod Authoring Tools".to_string(), Style::default())],
+            vec![OptionSelectorText::new("Lovely Logs".to_string(), Style::default())],
+            vec![OptionSelectorText::new("Crash Report".to_string(), Style::default())],
+            vec![OptionSelectorText::new("Save Backups".to_string(), Style::default())],
+            vec![OptionSelectorText::new("Current Run".to_string(), Style::default())],
+            vec![OptionSelectorText::new("Game Settings".to_string(), Style::default())],
         ]);
 
         mode_selector.has_focus = true;
@@ -79,16 +105,74 @@ impl Home {
             remote_mod_selector,
             mode_selector,
             authoring,
+            log_viewer: LogViewer::new(),
+            crash_report: CrashReportView::new(),
+            saves: SaveBackups::new(),
+            run_viewer: RunViewer::new(),
+            stats: StatsView::new(),
             quick_ops,
             command_tx: None,
             config: Config::default(),
             focused: Focused::Modes,
             has_focus: false,
             state: DrawingState::None,
+            settings_editor: SettingsEditor::new(),
+            game: GameSupervisor::new(),
         }
     }
 }
 
+impl Home {
+    fn poll_game(&mut self, force: bool) -> Result<()> {
+        match self.game.poll(force) {
+            Some(GameEvent::Started) => {
+                info!("Balatro is running.");
+                if let Some(tx) = &self.command_tx {
+                    tx.send(Action::GameStarted)?;
+                }
+            }
+            Some(GameEvent::Exited) => {
+                info!("Balatro has stopped.");
+                if let Some(tx) = &self.command_tx {
+                    tx.send(Action::GameExited)?;
+                }
+            }
+            None => {}
+        }
+        // relaunches from the dev loop are launches too
+        if self.config.config.backup_saves_on_launch && self.game.restart_due() {
+            backup_profiles();
+        }
+        if let Err(e) = self.game.continue_restart(&self.config.config.launcher) {
+            error!("Balatro failed to relaunch: {}", e);
+        }
+        Ok(())
+    }
+
+    fn game_status_line(&self) -> Line<'static> {
+        let status = match self.game.status() {
+            GameStatus::Stopped => {
+                Span::styled("○ Stopped", Style::default().fg(Color::DarkGray))
+            }
+            GameStatus::Starting => {
+                Span::styled("◌ Starting...", Style::default().fg(Color::Yellow))
+            }
+            GameStatus::Running { pid, .. } => Span::styled(
+                format!(
+                    "● Running for {} (pid {})",
+                    format_uptime(self.game.uptime().unwrap_or_default()),
+                    pid
+                ),
+                Style::default().fg(Color::LightGreen),
+            ),
+        };
+        Line::from(vec![
+            status,
+            Span::styled("  ctrl-k kill, ctrl-r restart", Style::default().fg(Color::DarkGray)),
+        ])
+    }
+}
+
 impl Component for Home {
     fn focus(&mut self) {
         self.has_focus = true;
@@ -100,10 +184,19 @@ impl Component for Home {
         self.command_tx = Some(tx.clone());
         self.installed_mod_selector.register_action_handler(tx.clone()).expect("Failed to register action handler for installed mod selector");
         self.quick_ops.register_action_handler(tx.clone()).expect("Failed to register action handler for quick ops");
+        self.log_viewer.register_action_handler(tx.clone())?;
+        self.crash_report.register_action_handler(tx.clone())?;
+        self.saves.register_action_handler(tx.clone())?;
+        self.run_viewer.register_action_handler(tx.clone())?;
+        self.stats.register_action_handler(tx.clone())?;
+        self.settings_editor.register_action_handler(tx.clone())?;
+        self.authoring.register_action_handler(tx.clone())?;
         Ok(())
     }
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.saves.backup_on_launch = config.config.backup_saves_on_launch;
+        self.authoring.register_config_handler(config.clone())?;
         self.config = config;
         Ok(())
     }
@@ -125,13 +218,37 @@ impl Component for Home {
                                         self.installed_mod_selector.focus();
                                     }
                                     2 => {
+                                        self.focused = Focused::Stats;
+                                        self.stats.focus();
+                                    }
+                                    3 => {
                                         self.focused = Focused::RemoteMods;
                                         self.remote_mod_selector.focus();
                                     }
-                                    3 => {
+                                    4 => {
                                         self.focused = Focused::Authoring;
                                         self.authoring.focus();
                                     }
+                                    5 => {
+                                        self.focused = Focused::Logs;
+                                        self.log_viewer.focus();
+                                    }
+                                    6 => {
+                                        self.focused = Focused::CrashReport;
+                                        self.crash_report.focus();
+                                    }
+                                    7 => {
+                                        self.focused = Focused::Saves;
+                                        self.saves.focus();
+                                    }
+                                    8 => {
+                                        self.focused = Focused::Run;
+                                        self.run_viewer.focus();
+                                    }
+                                    9 => {
+                                        self.focused = Focused::Settings;
+                                        self.settings_editor.focus();
+                                    }
                                     _ => {}
                                 }
                                 self.mode_selector.has_focus = false;
@@ -165,6 +282,30 @@ impl Component for Home {
                             }
                         }
                     }
+                    Focused::Stats => {
+                        match key.code {
+                            KeyCode::Left => {
+                                self.focused = Focused::Modes;
+                                self.stats.unfocus();
+                                self.mode_selector.focus();
+                            }
+                            _ => {
+                                let _ = self.stats.handle_key_event(key);
+                            }
+                        }
+                    }
+                    Focused::Settings => {
+                        match key.code {
+                            KeyCode::Left => {
+                                self.focused = Focused::Modes;
+                                self.settings_editor.unfocus();
+                                self.mode_selector.focus();
+                            }
+                            _ => {
+                                let _ = self.settings_editor.handle_key_event(key);
+                            }
+                        }
+                    }
                     Focused::RemoteMods => {
                         match key.code {
                             KeyCode::Left => {
@@ -189,6 +330,54 @@ impl Component for Home {
                             }
                         }
                     }
+                    Focused::Logs => {
+                        match key.code {
+                            KeyCode::Left => {
+                                self.focused = Focused::Modes;
+                                self.log_viewer.unfocus();
+                                self.mode_selector.focus();
+                            }
+                            _ => {
+                                let _ = self.log_viewer.handle_key_event(key);
+                            }
+                        }
+                    }
+                    Focused::CrashReport => {
+                        match key.code {
+                            KeyCode::Left => {
+                                self.focused = Focused::Modes;
+                                self.crash_report.unfocus();
+                                self.mode_selector.focus();
+                            }
+                            _ => {
+                                let _ = self.crash_report.handle_key_event(key);
+                            }
+                        }
+                    }
+                    Focused::Saves => {
+                        match key.code {
+                            KeyCode::Left => {
+                                self.focused = Focused::Modes;
+                                self.saves.unfocus();
+                                self.mode_selector.focus();
+                            }
+                            _ => {
+                                let _ = self.saves.handle_key_event(key);
+                            }
+                        }
+                    }
+                    Focused::Run => {
+                        match key.code {
+                            KeyCode::Left => {
+                                self.focused = Focused::Modes;
+                                self.run_viewer.unfocus();
+                                self.mode_selector.focus();
+                            }
+                            _ => {
+                                let _ = self.run_viewer.handle_key_event(key);
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -198,6 +387,7 @@ impl Component for Home {
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
             Action::Tick => {
+                self.poll_game(false)?;
                 match self.state {
                     DrawingState::None => {
                         self.state = DrawingState::Loading;
@@ -229,12 +419,42 @@ impl Component for Home {
             Action::Render => {
                 // add any logic here that should run on every render
             }
+            Action::LaunchGame(disable_console) => {
+                if self.config.config.backup_saves_on_launch && !self.game.is_running() {
+                    backup_profiles();
+                }
+                if let Err(e) = self.game.launch(&self.config.config.launcher, disable_console) {
+                    error!("Balatro failed to launch: {}", e);
+                }
+            }
+            Action::KillGame => {
+                if !self.game.is_running() {
+                    warn!("Balatro is not running.");
+                } else if let Err(e) = self.game.kill() {
+                    error!("Failed to kill Balatro: {}", e);
+                }
+                self.poll_game(true)?;
+            }
+            Action::RestartGame => {
+                info!("Restarting Balatro...");
+                if let Err(e) = self.game.restart() {
+                    error!("Failed to kill Balatro: {}", e);
+                }
+                self.poll_game(true)?;
+            }
             _ => {}
         }
         
         self.installed_mod_selector.update(action.clone())?;
         self.quick_ops.update(action.clone())?;
         self.remote_mod_selector.update(action.clone())?;
+        self.log_viewer.update(action.clone())?;
+        self.crash_report.update(action.clone())?;
+        self.saves.update(action.clone())?;
+        self.run_viewer.update(action.clone())?;
+        self.stats.update(action.clone())?;
+        self.settings_editor.update(action.clone())?;
+        self.authoring.update(action.clone())?;
         
         Ok(None)
     }
@@ -297,6 +517,10 @@ impl Component for Home {
                         ),
                     vertical_chunks[0]
                 );
+                frame.render_widget(
+                    Paragraph::new(self.game_status_line()).right_aligned(),
+                    vertical_chunks[0].inner(Margin::new(2, 1))
+                );
 
 
                 let horizontal_chunks = Layout::default()
@@ -315,12 +539,30 @@ impl Component for Home {
                     1 => { // installed mods
                         self.installed_mod_selector.draw(frame, horizontal_chunks[1])?;
                     }
-                    2 => { // find mods
+                    2 => { // stats
+                        self.stats.draw(frame, horizontal_chunks[1])?;
+                    }
+                    3 => { // find mods
                         self.remote_mod_selector.draw(frame, horizontal_chunks[1])?;
                     }
-                    3 => { // mod tools
+                    4 => { // mod tools
                         self.authoring.draw(frame, horizontal_chunks[1])?;
                     }
+                    5 => { // lovely logs
+                        self.log_viewer.draw(frame, horizontal_chunks[1])?;
+                    }
+                    6 => { // crash report
+                        self.crash_report.draw(frame, horizontal_chunks[1])?;
+                    }
+                    7 => { // save backups
+                        self.saves.draw(frame, horizontal_chunks[1])?;
+                    }
+                    8 => { // current run
+                        self.run_viewer.draw(frame, horizontal_chunks[1])?;
+                    }
+                    9 => { // game settings
+                        self.settings_editor.draw(frame, horizontal_chunks[1])?;
+                    }
                     _ => {}
                 }
 
@@ -0,0 +1,265 @@
+use std::path::PathBuf;
+
+use balatro_tui::devlink::mod_id;
+use balatro_tui::get_balatro_appdata_dir;
+use balatro_tui::watch::{can_sync, needs_sync, sync_mod};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::{error, info};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Wrap};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::isolation::{Isolation, isolated_set, journal_path};
+use crate::mods::{Mod, ModList};
+
+/// Launches the game with only the mod being edited and what it needs, then puts the other mods
+/// back once the game exits.
+pub struct IsolatedLaunch {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    id: Option<String>,
+    mods: Vec<Mod>,
+    keep: Vec<usize>,
+    /// The isolated launch in progress, if any.
+    session: Option<Isolation>,
+    game_running: bool,
+    message: Option<(String, Color)>,
+}
+
+impl IsolatedLaunch {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            id: None,
+            mods: vec![],
+            keep: vec![],
+            session: None,
+            game_running: false,
+            message: None,
+        }
+    }
+
+    fn send(&self, action: Action) -> Result<()> {
+        if let Some(tx) = &self.action_tx {
+            tx.send(action)?;
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        self.id = mod_id(&self.mod_path);
+        self.mods = ModList::get_local_mods();
+        self.mods.sort_by(|a, b| a.name.cmp(&b.name));
+        self.keep = match &self.id {
+            Some(id) => isolated_set(&self.mods, id),
+            None => vec![],
+        };
+    }
+
+    fn launch(&mut self) -> Result<()> {
+        if self.session.is_some() {
+            return Ok(());
+        }
+        if self.game_running {
+            self.message = Some((
+                "Close the game first, mods can't be changed while it is running.".to_string(),
+                Color::Yellow,
+            ));
+            return Ok(());
+        }
+        let Some(id) = mod_id(&self.mod_path) else {
+            return Ok(());
+        };
+        let mods_dir = get_balatro_appdata_dir().join("Mods");
+        if needs_sync(&mods_dir, &id, &self.mod_path) {
+            // an install or a link to another checkout, which the game would load instead
+            if !can_sync(&mods_dir, &id) {
+                self.message = Some((
+                    format!(
+                        "Mods/{id} is already there and wasn't copied from this mod. Move it away or link the mod to launch it isolated."
+                    ),
+                    Color::Yellow,
+                ));
+                return Ok(());
+            }
+            if let Err(e) = sync_mod(&mods_dir, &id, &self.mod_path) {
+                self.message = Some((format!("Failed to copy into Mods/{id}: {e}"), Color::Red));
+                return Ok(());
+            }
+        }
+        // a launch the TUI never got to undo has the state from before it
+        match Isolation::recover(&journal_path()) {
+            Ok(true) => info!("Enabled the mods an unfinished isolated launch had disabled."),
+            Ok(false) => {}
+            Err(e) => {
+                self.message = Some((
+                    format!("Failed to undo an unfinished isolated launch: {e}"),
+                    Color::Red,
+                ));
+                return Ok(());
+            }
+        }
+        self.reload();
+        match Isolation::start(&mut self.mods, &self.keep, &journal_path()) {
+            Ok(session) => {
+                info!(
+                    "Launching with only {} of {} mods enabled",
+                    self.keep.len(),
+                    self.mods.len()
+                );
+                self.session = Some(session);
+                self.message = None;
+                self.send(Action::ModsChanged)?;
+                self.send(Action::LaunchGame(true))?;
+            }
+            Err(e) => {
+                error!("Failed to isolate {}: {}", id, e);
+                self.message = Some((format!("Failed to disable the other mods: {e}"), Color::Red));
+                // undo whatever was disabled before it failed
+                self.restore()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn restore(&mut self) -> Result<()> {
+        let journal = journal_path();
+        let restored = match self.session.take() {
+            Some(session) => session.restore(&journal).map(|()| true),
+            None => Isolation::recover(&journal),
+        };
+        match restored {
+            Ok(true) => info!("Restored the enabled mods from before the isolated launch."),
+            Ok(false) => {}
+            Err(e) => error!("Failed to restore the enabled mods: {}", e),
+        }
+        self.reload();
+        self.send(Action::ModsChanged)
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let Some(id) = &self.id else {
+            return vec![Line::from(
+                "The mod needs a metadata JSON with an id before it can be launched on its own.",
+            )];
+        };
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![if self.session.is_some() {
+            Line::from(vec![
+                Span::styled("Running isolated", bold.fg(Color::Green)),
+                Span::from(", the other mods are enabled again when the game exits"),
+            ])
+        } else {
+            Line::from(vec![
+                Span::from("l launches "),
+                Span::styled(id.clone(), bold),
+                Span::from(format!(
+                    " with {} of the {} installed mods, and enables the rest again when the game exits",
+                    self.keep.len(),
+                    self.mods.len()
+                )),
+            ])
+        }];
+        if let Some((message, color)) = &self.message {
+            lines.push(Line::styled(message.clone(), Style::default().fg(*color)));
+        }
+        if !self
+            .keep
+            .iter()
+            .any(|i| self.mods[*i].id.eq_ignore_ascii_case(id))
+        {
+            lines.push(Line::styled(
+                format!("{id} isn't in the Mods folder yet, it is copied there on launch"),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+        lines.push(Line::from(""));
+        lines.extend(self.mods.iter().enumerate().map(|(i, m)| {
+            if self.keep.contains(&i) {
+                Line::styled(format!("  + {}", m.name), Style::default().fg(Color::Green))
+            } else {
+                Line::styled(
+                    format!("  - {}", m.name),
+                    Style::default().fg(Color::DarkGray),
+                )
+            }
+        }));
+        lines
+    }
+}
+
+impl Component for IsolatedLaunch {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('l') => self.launch()?,
+            KeyCode::Char('u') => self.restore()?,
+            KeyCode::Char('r') => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::GameStarted => self.game_running = true,
+            Action::GameExited => {
+                self.game_running = false;
+                if self.session.is_some() {
+                    self.restore()?;
+                }
+            }
+            Action::Quit if self.session.is_some() => self.restore()?,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        frame.render_widget(
+            Paragraph::new(self.lines())
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title("Isolated test launch")
+                        .title_bottom(
+                            Line::from(
+                                " l launch isolated, u enable the other mods again, r refresh ",
+                            )
+                            .right_aligned(),
+                        )
+                        .border_style(if self.has_focus {
+                            Style::default().fg(Color::LightCyan)
+                        } else {
+                            Style::default().fg(Color::White)
+                        }),
+                ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
@@ -0,0 +1,223 @@
+use std::path::PathBuf;
+
+use balatro_tui::localization::{
+    Comparison, DEFAULT_BASE, KeyUse, Language, compare, load_languages, mod_prefix,
+    unlocalized_keys,
+};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+
+/// Compares the mod's localization files against a base language, and its code against them.
+pub struct LocalizationChecker {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    languages: Vec<Language>,
+    /// Index into `languages` of the one the others are compared against.
+    base: usize,
+    unlocalized: Vec<KeyUse>,
+    scroll: u16,
+    view_height: u16,
+}
+
+impl LocalizationChecker {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            languages: vec![],
+            base: 0,
+            unlocalized: vec![],
+            scroll: 0,
+            view_height: 0,
+        }
+    }
+
+    fn reload(&mut self) {
+        let base_name = self
+            .languages
+            .get(self.base)
+            .map_or(DEFAULT_BASE.to_string(), |l| l.name.clone());
+        self.languages = load_languages(&self.mod_path);
+        self.base = self
+            .languages
+            .iter()
+            .position(|l| l.name == base_name)
+            .unwrap_or(0);
+        self.unlocalized = match mod_prefix(&self.mod_path) {
+            Some(prefix) => unlocalized_keys(&self.mod_path, &prefix, &self.languages),
+            None => vec![],
+        };
+    }
+
+    fn next_base(&mut self) {
+        if !self.languages.is_empty() {
+            self.base = (self.base + 1) % self.languages.len();
+        }
+    }
+
+    fn comparison_lines(language: &Language, comparison: &Comparison) -> Vec<Line<'static>> {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        if comparison.is_complete() {
+            return vec![Line::from(vec![
+                Span::styled(language.name.clone(), bold),
+                Span::styled(" is complete", Style::default().fg(Color::Green)),
+            ])];
+        }
+        let mut lines = vec![Line::from(vec![
+            Span::styled(language.name.clone(), bold),
+            Span::styled(
+                format!(
+                    ": {} missing, {} extra, {} with different formatting tags",
+                    comparison.missing.len(),
+                    comparison.extra.len(),
+                    comparison.mismatched_tags.len()
+                ),
+                Style::default().fg(Color::Yellow),
+            ),
+        ])];
+        lines.extend(comparison.missing.iter().map(|key| {
+            Line::styled(
+                format!("    missing {key}"),
+                Style::default().fg(Color::Red),
+            )
+        }));
+        lines.extend(
+            comparison.extra.iter().map(|key| {
+                Line::styled(format!("    extra {key}"), Style::default().fg(Color::Gray))
+            }),
+        );
+        lines.extend(comparison.mismatched_tags.iter().map(|m| {
+            Line::styled(
+                format!(
+                    "    tags differ in {}: {} instead of {}",
+                    m.key,
+                    m.translated.join(" "),
+                    m.base.join(" ")
+                ),
+                Style::default().fg(Color::Yellow),
+            )
+        }));
+        lines
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let Some(base) = self.languages.get(self.base) else {
+            return vec![Line::from(
+                "The mod has no localization/<language>.lua files.",
+            )];
+        };
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![Line::from(vec![
+            Span::from("Comparing against "),
+            Span::styled(base.name.clone(), bold),
+        ])];
+        match &base.entries {
+            Ok(base_entries) => {
+                for language in &self.languages {
+                    if language.name == base.name {
+                        continue;
+                    }
+                    match &language.entries {
+                        Ok(entries) => lines.extend(Self::comparison_lines(
+                            language,
+                            &compare(base_entries, entries),
+                        )),
+                        Err(e) => lines.push(Line::styled(
+                            format!("{} can't be read: {}", language.name, e),
+                            Style::default().fg(Color::Red),
+                        )),
+                    }
+                }
+            }
+            Err(e) => lines.push(Line::styled(
+                format!("{} can't be read: {}", base.name, e),
+                Style::default().fg(Color::Red),
+            )),
+        }
+        lines.push(Line::from(""));
+        if self.unlocalized.is_empty() {
+            lines.push(Line::styled(
+                "Every key used in the code is localized",
+                Style::default().fg(Color::Green),
+            ));
+        } else {
+            lines.push(Line::styled(
+                format!(
+                    "{} keys used in the code have no localization at all:",
+                    self.unlocalized.len()
+                ),
+                Style::default().fg(Color::Yellow),
+            ));
+            lines.extend(self.unlocalized.iter().map(|u| {
+                Line::styled(
+                    format!("    {} at {}:{}", u.key, u.file.display(), u.line),
+                    Style::default().fg(Color::Red),
+                )
+            }));
+        }
+        lines
+    }
+}
+
+impl Component for LocalizationChecker {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.view_height),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.view_height),
+            KeyCode::Char('b') => self.next_base(),
+            KeyCode::Char('r') => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines = self.lines();
+        self.view_height = area.height.saturating_sub(2);
+        let max_scroll = (lines.len() as u16).saturating_sub(self.view_height);
+        self.scroll = self.scroll.min(max_scroll);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Localization")
+                    .title_bottom(Line::from(" b change base language, r refresh ").right_aligned())
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
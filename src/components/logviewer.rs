@@ -0,0 +1,312 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use balatro_tui::lovely::log::{LogLevel, LogTail, level_of, list_logs, log_dir};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::error;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::components::textinput::TextInput;
+
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct LogViewer {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    logs: Vec<PathBuf>,
+    /// Index into `logs`, 0 being the newest session.
+    selected_log: usize,
+    tail: Option<LogTail>,
+    /// Index of the top visible line.
+    scroll: usize,
+    follow: bool,
+    searchbar: TextInput,
+    searching: bool,
+    last_refresh: Option<Instant>,
+    view_height: usize,
+}
+
+impl LogViewer {
+    pub fn new() -> Self {
+        let mut searchbar = TextInput::new();
+        searchbar.placeholder = "Search...".to_string();
+        searchbar.title = "Search (enter to confirm, esc to clear)".to_string();
+
+        Self {
+            action_tx: None,
+            has_focus: false,
+            logs: vec![],
+            selected_log: 0,
+            tail: None,
+            scroll: 0,
+            follow: true,
+            searchbar,
+            searching: false,
+            last_refresh: None,
+            view_height: 0,
+        }
+    }
+
+    fn refresh(&mut self) {
+        let logs = list_logs(&log_dir());
+        // a new session was started, stick to the newest log when following
+        let newest_changed = logs.first() != self.logs.first();
+        if newest_changed && self.follow {
+            self.selected_log = 0;
+        } else if let Some(current) = self.tail.as_ref().map(|t| t.path.clone()) {
+            self.selected_log = logs.iter().position(|p| *p == current).unwrap_or(0);
+        }
+        self.logs = logs;
+
+        let selected = self.logs.get(self.selected_log).cloned();
+        if self.tail.as_ref().map(|t| &t.path) != selected.as_ref() {
+            self.tail = selected.map(LogTail::new);
+            self.scroll = 0;
+        }
+        if let Some(tail) = self.tail.as_mut()
+            && let Err(e) = tail.refresh()
+        {
+            error!("Failed to read {}: {}", tail.path.display(), e);
+            self.tail = None;
+        }
+    }
+
+    fn select_log(&mut self, index: usize) {
+        if index >= self.logs.len() {
+            return;
+        }
+        self.selected_log = index;
+        self.tail = Some(LogTail::new(self.logs[index].clone()));
+        self.follow = index == 0;
+        self.scroll = 0;
+        self.last_refresh = None;
+        self.refresh();
+    }
+
+    fn line_count(&self) -> usize {
+        self.tail.as_ref().map(|t| t.lines.len()).unwrap_or(0)
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.line_count().saturating_sub(self.view_height)
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.follow = false;
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    /// Jumps to the next (or previous) line containing the search query.
+    fn find(&mut self, forward: bool) {
+        let query = self.searchbar.text.to_lowercase();
+        let Some(tail) = &self.tail else {
+            return;
+        };
+        if query.is_empty() {
+            return;
+        }
+        let matches = |i: &usize| tail.lines[*i].to_lowercase().contains(&query);
+        let found = if forward {
+            (self.scroll + 1..tail.lines.len()).find(matches)
+        } else {
+            (0..self.scroll).rev().find(matches)
+        };
+        if let Some(i) = found {
+            self.follow = false;
+            self.scroll = i.min(self.max_scroll());
+        }
+    }
+
+    fn style_line(&self, line: &str) -> Line<'static> {
+        let style = match level_of(line) {
+            Some(LogLevel::Error) => Style::default().fg(Color::Red),
+            Some(LogLevel::Warn) => Style::default().fg(Color::Yellow),
+            Some(LogLevel::Info) => Style::default().fg(Color::White),
+            Some(LogLevel::Debug) => Style::default().fg(Color::Blue),
+            Some(LogLevel::Trace) => Style::default().fg(Color::DarkGray),
+            None => Style::default().fg(Color::Gray),
+        };
+
+        let query = self.searchbar.text.to_lowercase();
+        let lower = line.to_lowercase();
+        if query.is_empty() || lower.len() != line.len() {
+            return Line::from(Span::styled(line.to_string(), style));
+        }
+
+        // highlight every match of the search query
+        let mut spans = vec![];
+        let mut last = 0;
+        for (start, _) in lower.match_indices(&query) {
+            spans.push(Span::styled(line[last..start].to_string(), style));
+            spans.push(Span::styled(
+                line[start..start + query.len()].to_string(),
+                style
+                    .bg(Color::Yellow)
+                    .fg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            last = start + query.len();
+        }
+        spans.push(Span::styled(line[last..].to_string(), style));
+        Line::from(spans)
+    }
+}
+
+impl Component for LogViewer {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.searching {
+            match key.code {
+                KeyCode::Enter => {
+                    self.searching = false;
+                    self.searchbar.unfocus();
+                    self.find(true);
+                }
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.searchbar.unfocus();
+                    self.searchbar.text.clear();
+                }
+                _ => {
+                    self.searchbar.handle_key_event(key)?;
+                }
+            }
+            return Ok(None);
+        }
+
+        match key.code {
+            KeyCode::Up => self.scroll_by(-1),
+            KeyCode::Down => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-(self.view_height as isize)),
+            KeyCode::PageDown => self.scroll_by(self.view_height as isize),
+            KeyCode::Home => self.scroll_by(-(self.line_count() as isize)),
+            KeyCode::End => self.follow = true,
+            KeyCode::Char('f') => self.follow = !self.follow,
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.searchbar.focus();
+            }
+            KeyCode::Char('n') => self.find(true),
+            KeyCode::Char('N') => self.find(false),
+            // older/newer session
+            KeyCode::Char('[') => self.select_log(self.selected_log + 1),
+            KeyCode::Char(']') => self.select_log(self.selected_log.saturating_sub(1)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick
+                if self
+                    .last_refresh
+                    .is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL) =>
+            {
+                self.last_refresh = Some(Instant::now());
+                self.refresh();
+            }
+            Action::GameStarted => {
+                // pick up the new session's log straight away
+                self.follow = true;
+                self.last_refresh = None;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(if self.searching || !self.searchbar.text.is_empty() {
+                vec![Constraint::Min(3), Constraint::Length(3)]
+            } else {
+                vec![Constraint::Min(3)]
+            })
+            .split(area);
+
+        self.view_height = chunks[0].height.saturating_sub(2) as usize;
+        if self.follow {
+            self.scroll = self.max_scroll();
+        }
+
+        let title = match &self.tail {
+            Some(tail) => format!(
+                "{} ({}/{}){}",
+                tail.path.file_name().unwrap_or_default().to_string_lossy(),
+                self.selected_log + 1,
+                self.logs.len(),
+                if self.follow { " [following]" } else { "" }
+            ),
+            None => "Lovely logs".to_string(),
+        };
+
+        let lines: Vec<Line> = match &self.tail {
+            Some(tail) => tail
+                .lines
+                .iter()
+                .skip(self.scroll)
+                .take(self.view_height)
+                .map(|l| self.style_line(l))
+                .collect(),
+            None => vec![
+                Line::from(format!("No Lovely logs found in {}", log_dir().display())),
+                Line::from(
+                    "Logs show up here once the game has been launched with Lovely installed.",
+                )
+                .style(Style::default().fg(Color::Gray)),
+            ],
+        };
+
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title)
+                    .title_bottom(
+                        Line::from(" / search, n/N next/prev, f follow, [/] older/newer session ")
+                            .right_aligned(),
+                    )
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            chunks[0],
+        );
+
+        if chunks.len() > 1 {
+            self.searchbar.draw(frame, chunks[1])?;
+        }
+
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+        self.searching = false;
+        self.searchbar.unfocus();
+    }
+}
@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use balatro_tui::metadata::{Check, check_metadata, find_metadata_file};
+use color_eyre::Result;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Wrap};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+
+/// Checks the metadata JSON of the mod being edited, again every time it is saved.
+pub struct MetadataChecker {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    file: Option<PathBuf>,
+    /// When the file was last checked, to notice it being saved.
+    checked_at: Option<SystemTime>,
+    checks: Vec<Check>,
+}
+
+impl MetadataChecker {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            file: None,
+            checked_at: None,
+            checks: vec![],
+        }
+    }
+
+    fn recheck_if_changed(&mut self) {
+        if self.file.as_ref().is_none_or(|f| !f.is_file()) {
+            self.file = find_metadata_file(&self.mod_path);
+            self.checked_at = None;
+        }
+        let Some(file) = &self.file else {
+            self.checks.clear();
+            return;
+        };
+        let modified = std::fs::metadata(file).and_then(|m| m.modified()).ok();
+        if modified.is_some() && modified == self.checked_at {
+            return;
+        }
+        self.checked_at = modified;
+        self.checks = match std::fs::read_to_string(file) {
+            Ok(text) => check_metadata(&text, &self.mod_path),
+            Err(_) => vec![],
+        };
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        if self.file.is_none() {
+            return vec![Line::from(format!(
+                "No metadata JSON in {}.",
+                self.mod_path.display()
+            ))];
+        }
+        let mut lines = vec![];
+        for check in &self.checks {
+            let (mark, color) = if check.passed() {
+                ("✓ ", Color::Green)
+            } else {
+                ("✗ ", Color::Red)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(mark, Style::default().fg(color)),
+                Span::styled(check.name, Style::default().add_modifier(Modifier::BOLD)),
+            ]));
+            for problem in &check.problems {
+                lines.push(Line::styled(
+                    format!("    {problem}"),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+        }
+        lines
+    }
+}
+
+impl Component for MetadataChecker {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::Tick = action
+            && self.has_focus
+        {
+            self.recheck_if_changed();
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let title = match &self.file {
+            Some(file) => format!(
+                "{} checked against the Steamodded schema",
+                file.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            None => "Metadata".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(self.lines())
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(title)
+                        .title_bottom(Line::from(" rechecked on save ").right_aligned())
+                        .border_style(if self.has_focus {
+                            Style::default().fg(Color::LightCyan)
+                        } else {
+                            Style::default().fg(Color::White)
+                        }),
+                ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.recheck_if_changed();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
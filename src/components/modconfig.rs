@@ -0,0 +1,463 @@
+use std::path::PathBuf;
+
+use balatro_tui::get_balatro_appdata_dir;
+use balatro_tui::jkr::Value;
+use balatro_tui::modconfig::{self, ModConfig, Row, config_path};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::{error, info, warn};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::components::textinput::TextInput;
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Bool(b) => if *b { "on" } else { "off" }.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("{s:?}"),
+        Value::Nil => "nil".to_string(),
+        Value::Table(_) => String::new(),
+    }
+}
+
+fn block(title: String, hints: &'static str, focused: bool) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(title)
+        .title_bottom(Line::from(hints).right_aligned())
+        .border_style(if focused {
+            Style::default().fg(Color::LightCyan)
+        } else {
+            Style::default().fg(Color::White)
+        })
+}
+
+/// Keeps the selected line on screen in a view `height` lines tall.
+fn scroll_for(selected: usize, height: u16, scroll: u16) -> u16 {
+    let selected = selected as u16;
+    if selected < scroll {
+        selected
+    } else if height > 0 && selected >= scroll + height {
+        selected + 1 - height
+    } else {
+        scroll
+    }
+}
+
+/// Edits one mod's Steamodded config as a tree.
+pub struct ModConfigEditor {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    /// Set when the editor wants to be closed.
+    pub closed: bool,
+    pub game_running: bool,
+    mod_id: String,
+    mod_name: String,
+    /// The config being edited, `None` if the mod is on its defaults, or why it couldn't be read.
+    config: Result<Option<ModConfig>, String>,
+    original: Option<ModConfig>,
+    rows: Vec<Row>,
+    selected: usize,
+    scroll: u16,
+    input: TextInput,
+    editing: bool,
+    confirm_reset: bool,
+}
+
+impl ModConfigEditor {
+    pub fn new(mod_id: String, mod_name: String) -> Self {
+        let mut this = Self {
+            action_tx: None,
+            has_focus: true,
+            closed: false,
+            game_running: false,
+            mod_id,
+            mod_name,
+            config: Ok(None),
+            original: None,
+            rows: vec![],
+            selected: 0,
+            scroll: 0,
+            input: TextInput::new(),
+            editing: false,
+            confirm_reset: false,
+        };
+        this.reload();
+        this
+    }
+
+    fn reload(&mut self) {
+        self.editing = false;
+        self.config = ModConfig::load(&get_balatro_appdata_dir(), &self.mod_id)
+            .map_err(|e| format!("Failed to read the config of {}: {}", self.mod_name, e));
+        self.original = self.config.as_ref().ok().cloned().flatten();
+        self.rows = self
+            .original
+            .as_ref()
+            .map(ModConfig::rows)
+            .unwrap_or_default();
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+    }
+
+    fn current(&self, row: &Row) -> Option<&Value> {
+        match &self.config {
+            Ok(Some(config)) => config.get(&row.path),
+            _ => None,
+        }
+    }
+
+    fn is_modified(&self, row: &Row) -> bool {
+        self.original.as_ref().and_then(|o| o.get(&row.path)) != self.current(row)
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.rows.iter().any(|r| self.is_modified(r))
+    }
+
+    /// Toggles a boolean, or starts typing a number or string.
+    fn edit_selected(&mut self) {
+        let Some(row) = self.rows.get(self.selected).cloned() else {
+            return;
+        };
+        let Ok(Some(config)) = &mut self.config else {
+            return;
+        };
+        match config.get(&row.path).cloned() {
+            Some(Value::Bool(b)) => {
+                config.set(&row.path, Value::Bool(!b));
+            }
+            Some(Value::Number(n)) => {
+                self.input.text = n.to_string();
+                self.input.title = format!("{} (a number, enter to confirm)", row.key());
+                self.input.focus();
+                self.editing = true;
+            }
+            Some(Value::String(s)) => {
+                self.input.text = s;
+                self.input.title = format!("{} (enter to confirm)", row.key());
+                self.input.focus();
+                self.editing = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn finish_editing(&mut self) {
+        self.editing = false;
+        self.input.unfocus();
+        let Some(row) = self.rows.get(self.selected) else {
+            return;
+        };
+        let Ok(Some(config)) = &mut self.config else {
+            return;
+        };
+        let value = match config.get(&row.path) {
+            Some(Value::Number(_)) => match self.input.text.trim().parse::<f64>() {
+                Ok(n) if n.is_finite() => Value::Number(n),
+                _ => {
+                    error!("{} needs a number, not '{}'", row.key(), self.input.text);
+                    return;
+                }
+            },
+            _ => Value::String(self.input.text.clone()),
+        };
+        config.set(&row.path, value);
+    }
+
+    fn save(&mut self) {
+        let Ok(Some(config)) = &self.config else {
+            return;
+        };
+        if self.game_running {
+            error!(
+                "Close Balatro before saving mod configs, Steamodded only reads them on launch."
+            );
+            return;
+        }
+        match config.save() {
+            Ok(()) => {
+                info!("Saved the config of {}.", self.mod_name);
+                self.original = Some(config.clone());
+            }
+            Err(e) => error!("Failed to save the config of {}: {}", self.mod_name, e),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.confirm_reset = false;
+        if self.game_running {
+            error!("Close Balatro before resetting mod configs, it would write them back on exit.");
+            return;
+        }
+        match modconfig::reset(&get_balatro_appdata_dir(), &self.mod_id) {
+            Ok(()) => {
+                info!("Reset {} to its default config.", self.mod_name);
+                self.reload();
+            }
+            Err(e) => error!("Failed to reset the config of {}: {}", self.mod_name, e),
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        if self.confirm_reset {
+            return vec![
+                Line::from(format!(
+                    "Delete {} so {} goes back to its defaults?",
+                    config_path(&get_balatro_appdata_dir(), &self.mod_id).display(),
+                    self.mod_name
+                )),
+                Line::from("y to reset, any other key to cancel"),
+            ];
+        }
+        match &self.config {
+            Ok(Some(_)) if self.rows.is_empty() => vec![Line::from("The config is empty.")],
+            Ok(Some(_)) => self
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    let style = if i == self.selected {
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default()
+                    };
+                    let mut spans = vec![
+                        Span::from("  ".repeat(row.depth())),
+                        Span::styled(row.key().to_string(), style),
+                    ];
+                    match self.current(row) {
+                        Some(Value::Table(_)) | None => {}
+                        Some(value) => spans.push(Span::styled(
+                            format!(" = {}", format_value(value)),
+                            Style::default().fg(Color::LightBlue),
+                        )),
+                    }
+                    if self.is_modified(row) {
+                        spans.push(Span::styled(" *", Style::default().fg(Color::LightRed)));
+                    }
+                    Line::from(spans)
+                })
+                .collect(),
+            Ok(None) => vec![Line::from(format!(
+                "{} has no saved config, it is using its defaults.",
+                self.mod_name
+            ))],
+            Err(e) => vec![Line::from(Span::styled(
+                e.clone(),
+                Style::default().fg(Color::Red),
+            ))],
+        }
+    }
+}
+
+impl Component for ModConfigEditor {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.editing {
+            match key.code {
+                KeyCode::Enter => self.finish_editing(),
+                KeyCode::Esc => {
+                    self.editing = false;
+                    self.input.unfocus();
+                }
+                _ => {
+                    self.input.handle_key_event(key)?;
+                }
+            }
+            return Ok(None);
+        }
+        if self.confirm_reset {
+            if key.code == KeyCode::Char('y') {
+                self.reset();
+            } else {
+                self.confirm_reset = false;
+            }
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.rows.len().saturating_sub(1))
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => self.edit_selected(),
+            KeyCode::Char('s') => self.save(),
+            KeyCode::Char('r') => self.reload(),
+            KeyCode::Char('d') if matches!(self.config, Ok(Some(_))) => self.confirm_reset = true,
+            KeyCode::Esc => {
+                if self.is_dirty() {
+                    warn!(
+                        "Discarded unsaved changes to the config of {}.",
+                        self.mod_name
+                    );
+                }
+                self.closed = true;
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(if self.editing {
+                vec![Constraint::Min(3), Constraint::Length(3)]
+            } else {
+                vec![Constraint::Min(3)]
+            })
+            .split(area);
+
+        self.scroll = scroll_for(
+            self.selected,
+            chunks[0].height.saturating_sub(2),
+            self.scroll,
+        );
+        let title = if self.is_dirty() {
+            format!("Config of {} (unsaved changes)", self.mod_name)
+        } else {
+            format!("Config of {}", self.mod_name)
+        };
+        frame.render_widget(
+            Paragraph::new(self.lines())
+                .scroll((if self.confirm_reset { 0 } else { self.scroll }, 0))
+                .block(block(
+                    title,
+                    " enter change, s save, r discard changes, d reset to defaults, esc back ",
+                    self.has_focus,
+                )),
+            chunks[0],
+        );
+        if self.editing {
+            self.input.draw(frame, chunks[1])?;
+        }
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
+
+/// Config files left behind by mods that are no longer installed.
+pub struct OrphanedConfigs {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    /// Set when the list wants to be closed.
+    pub closed: bool,
+    files: Vec<PathBuf>,
+    selected: usize,
+    scroll: u16,
+}
+
+impl OrphanedConfigs {
+    pub fn new(files: Vec<PathBuf>) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: true,
+            closed: false,
+            files,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    fn delete(&mut self, index: usize) {
+        let path = self.files[index].clone();
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                info!("Deleted {}.", path.display());
+                self.files.remove(index);
+            }
+            Err(e) => error!("Failed to delete {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Component for OrphanedConfigs {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.files.len().saturating_sub(1))
+            }
+            KeyCode::Char('d') if !self.files.is_empty() => self.delete(self.selected),
+            KeyCode::Char('D') => {
+                for i in (0..self.files.len()).rev() {
+                    self.delete(i);
+                }
+            }
+            KeyCode::Esc => self.closed = true,
+            _ => {}
+        }
+        self.selected = self.selected.min(self.files.len().saturating_sub(1));
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines: Vec<Line> = if self.files.is_empty() {
+            vec![Line::from("Every config file belongs to an installed mod.")]
+        } else {
+            self.files
+                .iter()
+                .enumerate()
+                .map(|(i, path)| {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    if i == self.selected {
+                        Line::styled(
+                            name.to_string(),
+                            Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD),
+                        )
+                    } else {
+                        Line::from(name.to_string())
+                    }
+                })
+                .collect()
+        };
+        self.scroll = scroll_for(self.selected, area.height.saturating_sub(2), self.scroll);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(block(
+                format!(
+                    "Configs of mods that are not installed ({})",
+                    self.files.len()
+                ),
+                " d delete, D delete all, esc back ",
+                self.has_focus,
+            )),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
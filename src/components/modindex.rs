@@ -0,0 +1,292 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use balatro_tui::modindex::{
+    Submission, index_mods_dir, known_categories, submission, validate_categories, write_submission,
+};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::info;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::config::{Config, get_data_dir};
+
+/// Writes the files for submitting the mod being edited to the Balatro Mod Index into a local
+/// fork of it.
+pub struct IndexSubmission {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    /// The `index_fork` config option.
+    fork: Option<PathBuf>,
+    known: BTreeSet<String>,
+    chosen: BTreeSet<String>,
+    /// Index into `known` of the highlighted category.
+    selected: usize,
+    preview: Option<Result<Submission, String>>,
+    written: Option<Result<PathBuf, String>>,
+    /// Asking before replacing a submission already in the fork.
+    confirm_overwrite: bool,
+    scroll: u16,
+    view_height: u16,
+}
+
+impl IndexSubmission {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            fork: None,
+            known: BTreeSet::new(),
+            chosen: BTreeSet::new(),
+            selected: 0,
+            preview: None,
+            written: None,
+            confirm_overwrite: false,
+            scroll: 0,
+            view_height: 0,
+        }
+    }
+
+    fn categories(&self) -> Vec<String> {
+        self.chosen.iter().cloned().collect()
+    }
+
+    fn reload(&mut self) {
+        // the mod list's copy of the index, and the fork in case it is newer
+        self.known = known_categories(&get_data_dir().join("mods"));
+        if let Some(fork) = &self.fork {
+            self.known.extend(known_categories(fork));
+        }
+        self.selected = self.selected.min(self.known.len().saturating_sub(1));
+        self.preview =
+            Some(submission(&self.mod_path, &self.categories()).map_err(|e| e.to_string()));
+
+        // start from what an earlier submission picked
+        if self.chosen.is_empty()
+            && let (Some(fork), Some(Ok(preview))) = (&self.fork, &self.preview)
+        {
+            let meta = index_mods_dir(fork).join(&preview.folder).join("meta.json");
+            if let Some(categories) = std::fs::read_to_string(meta)
+                .ok()
+                .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+                .and_then(|json| json.get("categories")?.as_array().cloned())
+            {
+                self.chosen = categories
+                    .iter()
+                    .filter_map(|c| c.as_str().map(str::to_string))
+                    .collect();
+                self.preview =
+                    Some(submission(&self.mod_path, &self.categories()).map_err(|e| e.to_string()));
+            }
+        }
+    }
+
+    fn toggle_selected(&mut self) {
+        let Some(category) = self.known.iter().nth(self.selected).cloned() else {
+            return;
+        };
+        if !self.chosen.remove(&category) {
+            self.chosen.insert(category);
+        }
+        self.preview =
+            Some(submission(&self.mod_path, &self.categories()).map_err(|e| e.to_string()));
+    }
+
+    /// Where the submission would be written, when there is a fork and the mod can be submitted.
+    fn target(&self) -> Option<PathBuf> {
+        match (&self.fork, &self.preview) {
+            (Some(fork), Some(Ok(preview))) => Some(index_mods_dir(fork).join(&preview.folder)),
+            _ => None,
+        }
+    }
+
+    fn write(&mut self) {
+        let Some(fork) = self.fork.clone() else {
+            return;
+        };
+        let categories = self.categories();
+        let written = validate_categories(&categories, &self.known)
+            .and_then(|()| submission(&self.mod_path, &categories))
+            .and_then(|submission| write_submission(&fork, &submission));
+        if let Ok(dir) = &written {
+            info!("Wrote the mod index submission to {}", dir.display());
+        }
+        self.written = Some(written.map_err(|e| e.to_string()));
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        if self.confirm_overwrite
+            && let Some(target) = self.target()
+        {
+            return vec![
+                Line::from(format!(
+                    "{} already exists. Replace its files with this submission?",
+                    target.display()
+                )),
+                Line::from("y to replace, any other key to cancel"),
+            ];
+        }
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![match &self.fork {
+            Some(fork) => Line::from(vec![
+                Span::from("w writes the submission into "),
+                Span::styled(fork.display().to_string(), bold),
+                Span::from(", ready to commit"),
+            ]),
+            None => Line::styled(
+                "Set index_fork in config.json5 to a clone of your fork of the Balatro Mod Index to write into it",
+                Style::default().fg(Color::Yellow),
+            ),
+        }];
+        match &self.written {
+            None => {}
+            Some(Ok(dir)) => lines.push(Line::styled(
+                format!("Wrote {}", dir.display()),
+                Style::default().fg(Color::Green),
+            )),
+            Some(Err(e)) => lines.push(Line::styled(
+                format!("Not written: {e}"),
+                Style::default().fg(Color::Red),
+            )),
+        }
+        lines.push(Line::from(""));
+
+        if self.known.is_empty() {
+            lines.push(Line::styled(
+                "No categories found, download the mod index from the online mods first",
+                Style::default().fg(Color::Yellow),
+            ));
+        } else {
+            lines.push(Line::from("Categories, space to pick:"));
+        }
+        for (i, category) in self.known.iter().enumerate() {
+            let mark = if self.chosen.contains(category) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            let style = if i == self.selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::styled(format!("  {mark} {category}"), style));
+        }
+        lines.push(Line::from(""));
+
+        match &self.preview {
+            None => {}
+            Some(Ok(submission)) => {
+                lines.push(Line::from(vec![
+                    Span::from("mods/"),
+                    Span::styled(format!("{}/meta.json", submission.folder), bold),
+                ]));
+                let json = serde_json::to_string_pretty(&submission.meta).unwrap_or_default();
+                lines.extend(
+                    json.lines().map(|l| {
+                        Line::styled(format!("    {l}"), Style::default().fg(Color::Gray))
+                    }),
+                );
+                lines.push(Line::from(format!(
+                    "description.md from {}, {}",
+                    if self.mod_path.join("README.md").is_file() {
+                        "README.md"
+                    } else {
+                        "the metadata description"
+                    },
+                    if submission.thumbnail.is_some() {
+                        "with thumbnail.jpg"
+                    } else {
+                        "no thumbnail.jpg to include"
+                    }
+                )));
+            }
+            Some(Err(e)) => lines.push(Line::styled(e.clone(), Style::default().fg(Color::Red))),
+        }
+        lines
+    }
+}
+
+impl Component for IndexSubmission {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.fork = config.config.index_fork;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.confirm_overwrite {
+            self.confirm_overwrite = false;
+            if key.code == KeyCode::Char('y') {
+                self.write();
+            }
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.known.len().saturating_sub(1))
+            }
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.view_height),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.view_height),
+            KeyCode::Char(' ') => self.toggle_selected(),
+            KeyCode::Char('w') if self.target().is_some_and(|t| t.exists()) => {
+                self.confirm_overwrite = true
+            }
+            KeyCode::Char('w') => self.write(),
+            KeyCode::Char('r') => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines = self.lines();
+        self.view_height = area.height.saturating_sub(2);
+        let max_scroll = (lines.len() as u16).saturating_sub(self.view_height);
+        self.scroll = self.scroll.min(max_scroll);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Submit to the mod index")
+                    .title_bottom(
+                        Line::from(" space pick category, w write submission, r refresh ")
+                            .right_aligned(),
+                    )
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
@@ -6,7 +6,7 @@ use std::time::Instant;
 
 use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent};
-use log::info;
+use log::{error, info};
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
@@ -23,9 +23,92 @@ use tokio::sync::mpsc::UnboundedSender;
 use super::{Component, Eventable};
 
 use crate::action::Action;
+use crate::bisect::{Bisect, Outcome};
+use crate::components::collisions::PatchCollisions;
+use crate::components::modconfig::{ModConfigEditor, OrphanedConfigs};
 use crate::components::optionselector::{Actions, OptionSelector, OptionSelectorText};
+use crate::isolation::{Isolation, bisect_candidates, bisect_journal_path, bisect_step};
 use crate::mods;
 use crate::mods::{Mod, ModList};
+use balatro_tui::devlink::is_dev_link;
+use balatro_tui::get_balatro_appdata_dir;
+use balatro_tui::modconfig::orphaned_configs;
+
+/// A running bisect, with its own copy of the mod list and a journal of the original enabled
+/// state so it can be restored, even after a crash.
+struct BisectSession {
+    bisect: Bisect,
+    mods: Vec<Mod>,
+    candidates: Vec<usize>,
+    journal: Isolation,
+    /// Launch the game once the previous run has exited.
+    launch_when_stopped: bool,
+}
+
+impl BisectSession {
+    fn start() -> std::io::Result<Self> {
+        let mut mods = ModList::get_local_mods();
+        let enabled: Vec<usize> = (0..mods.len())
+            .filter(|i| mods[*i].enabled.unwrap_or(true))
+            .collect();
+        let candidates = bisect_candidates(&mods);
+        let journal = Isolation::start(&mut mods, &enabled, &bisect_journal_path())?;
+        let mut session = Self {
+            bisect: Bisect::new(candidates.clone()),
+            mods,
+            candidates,
+            journal,
+            launch_when_stopped: false,
+        };
+        if session.bisect.outcome().is_none() {
+            session.apply();
+        }
+        Ok(session)
+    }
+
+    fn enabled_set(&self) -> Vec<usize> {
+        bisect_step(&self.mods, &self.candidates, &self.bisect.test_set())
+    }
+
+    /// Mods enabled for the current step only because something in the test set needs them.
+    fn dependencies(&self) -> Vec<usize> {
+        let test_set = self.bisect.test_set();
+        self.enabled_set()
+            .into_iter()
+            .filter(|i| !test_set.contains(i) && !self.mods[*i].force_enable)
+            .collect()
+    }
+
+    /// Mods in the test set that stay disabled because a mod they need is not being tested.
+    fn left_out(&self) -> Vec<usize> {
+        let enabled = self.enabled_set();
+        self.bisect
+            .test_set()
+            .into_iter()
+            .filter(|i| !enabled.contains(i))
+            .collect()
+    }
+
+    fn apply(&mut self) {
+        let enabled = self.enabled_set();
+        for (i, m) in self.mods.iter_mut().enumerate() {
+            if let Err(e) = m.set_enabled(enabled.contains(&i)) {
+                error!("Failed to toggle {}: {}", m.name, e);
+            }
+        }
+    }
+
+    fn restore(&mut self) {
+        if let Err(e) = self.journal.restore(&bisect_journal_path()) {
+            error!("Failed to remove the bisect journal: {}", e);
+        }
+        info!("Restored the enabled state from before the bisect.");
+    }
+
+    fn name(&self, i: usize) -> String {
+        self.mods[i].name.clone()
+    }
+}
 
 pub struct ModlistComponent {
     pub action_tx: Option<UnboundedSender<Action>>,
@@ -34,12 +117,16 @@ pub struct ModlistComponent {
     mods: Vec<Mod>,
     local_action_tx: mpsc::UnboundedSender<Actions>,
     local_action_rx: mpsc::UnboundedReceiver<Actions>,
+    bisect: Option<BisectSession>,
+    config_editor: Option<ModConfigEditor>,
+    orphaned_configs: Option<OrphanedConfigs>,
+    collisions: Option<PatchCollisions>,
+    game_running: bool,
 }
 
 impl ModlistComponent {
     pub fn new() -> Self {
-        let mut installed_mod_selector = OptionSelector::new(vec![]);
-        installed_mod_selector.title = "Installed mods".to_string();
+        let installed_mod_selector = OptionSelector::new(vec![]);
 
         let mods_ref = Vec::new();
 
@@ -52,6 +139,11 @@ impl ModlistComponent {
             mods: mods_ref,
             local_action_rx: modlist_rx,
             local_action_tx: modlist_tx,
+            bisect: None,
+            config_editor: None,
+            orphaned_configs: None,
+            collisions: None,
+            game_running: false,
         };
         this.mods = ModList::get_local_mods();
         this.build_options();
@@ -64,6 +156,15 @@ impl ModlistComponent {
         });
         
         self.options.options.clear();
+
+        let ids = self.config_ids();
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let orphans = orphaned_configs(&get_balatro_appdata_dir(), &ids).len();
+        self.options.title = if orphans > 0 {
+            format!("Installed mods (enter to toggle, c to configure, p for patch collisions, b to bisect, o to review {orphans} stale configs)")
+        } else {
+            "Installed mods (enter to toggle, c to configure, p for patch collisions, b to bisect)".to_string()
+        };
         
         self.mods.iter_mut().for_each(|m| {
             self.options.options.push(
@@ -74,6 +175,10 @@ impl ModlistComponent {
                 ]
                 //                Span::styled(format!("{} {} by {:?}", m.name, m.version, m.author), Style::default().fg(Color::Green)),
             );
+            // symlinked in from where it is being developed
+            if is_dev_link(&m.folder) {
+                self.options.options.last_mut().unwrap().push(OptionSelectorText::new(" dev".to_string(), Style::default().fg(Color::Magenta)));
+            }
             if !m.enabled.unwrap_or(true) {
                 self.options.options.last_mut().unwrap().push(OptionSelectorText::new(" (disabled)".to_string(), Style::default().fg(Color::Red)));
             }
@@ -81,6 +186,224 @@ impl ModlistComponent {
     }
 }
 
+impl ModlistComponent {
+    fn send(&self, action: Action) -> Result<()> {
+        if let Some(tx) = &self.action_tx {
+            tx.send(action)?;
+        }
+        Ok(())
+    }
+
+    /// Kills the game if it is still running from the previous step, then launches it.
+    fn relaunch(&mut self) -> Result<()> {
+        if self.game_running {
+            self.send(Action::KillGame)?;
+            if let Some(session) = self.bisect.as_mut() {
+                session.launch_when_stopped = true;
+            }
+            Ok(())
+        } else {
+            self.send(Action::LaunchGame(true))
+        }
+    }
+
+    /// The names installed mods keep their config under. Mods without metadata have no id, so
+    /// their folder name stands in to keep their config from looking stale.
+    fn config_ids(&self) -> Vec<String> {
+        self.mods
+            .iter()
+            .map(|m| {
+                if m.id.is_empty() {
+                    m.folder
+                        .file_name()
+                        .map(|f| f.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                } else {
+                    m.id.clone()
+                }
+            })
+            .collect()
+    }
+
+    fn configure_selected(&mut self) -> Result<()> {
+        let Some(m) = self.mods.get(self.options.selected) else {
+            return Ok(());
+        };
+        if m.id.is_empty() {
+            error!("{} has no metadata with an id, so its config can't be found.", m.name);
+            return Ok(());
+        }
+        let mut editor = ModConfigEditor::new(m.id.clone(), m.name.clone());
+        editor.game_running = self.game_running;
+        if let Some(tx) = &self.action_tx {
+            editor.register_action_handler(tx.clone())?;
+        }
+        self.config_editor = Some(editor);
+        Ok(())
+    }
+
+    fn review_orphaned_configs(&mut self) -> Result<()> {
+        let ids = self.config_ids();
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let mut orphans = OrphanedConfigs::new(orphaned_configs(&get_balatro_appdata_dir(), &ids));
+        if let Some(tx) = &self.action_tx {
+            orphans.register_action_handler(tx.clone())?;
+        }
+        self.orphaned_configs = Some(orphans);
+        Ok(())
+    }
+
+    fn check_patch_collisions(&mut self) -> Result<()> {
+        let enabled = self
+            .mods
+            .iter()
+            .filter(|m| m.enabled.unwrap_or(true))
+            .map(|m| (m.name.clone(), m.folder.clone()))
+            .collect();
+        let mut collisions = PatchCollisions::new(enabled);
+        if let Some(tx) = &self.action_tx {
+            collisions.register_action_handler(tx.clone())?;
+        }
+        self.collisions = Some(collisions);
+        Ok(())
+    }
+
+    fn start_bisect(&mut self) -> Result<()> {
+        let mut session = match BisectSession::start() {
+            Ok(session) => session,
+            Err(e) => {
+                error!("Failed to start the bisect: {}", e);
+                return Ok(());
+            }
+        };
+        if session.bisect.outcome().is_some() {
+            session.restore();
+            error!("Bisecting needs at least two enabled mods that are not force enabled or needed by one.");
+            return Ok(());
+        }
+        info!("Starting a bisect of {} mods.", session.bisect.remaining());
+        self.bisect = Some(session);
+        self.send(Action::ModsChanged)?;
+        self.relaunch()
+    }
+
+    fn answer_bisect(&mut self, reproduced: bool) -> Result<()> {
+        let Some(session) = self.bisect.as_mut() else {
+            return Ok(());
+        };
+        session.bisect.answer(reproduced);
+        if session.bisect.outcome().is_some() {
+            session.restore();
+            if self.game_running {
+                self.send(Action::KillGame)?;
+            }
+        } else {
+            session.apply();
+            self.relaunch()?;
+        }
+        self.send(Action::ModsChanged)
+    }
+
+    fn abort_bisect(&mut self) -> Result<()> {
+        if let Some(mut session) = self.bisect.take()
+            && session.bisect.outcome().is_none()
+        {
+            session.restore();
+            info!("Bisect aborted.");
+        }
+        self.send(Action::ModsChanged)
+    }
+
+    fn bisect_lines(&self, session: &BisectSession) -> Vec<Line<'static>> {
+        let highlight = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        let hint = Style::default().fg(Color::Gray);
+        match session.bisect.outcome() {
+            Some(outcome) => {
+                let result = match outcome {
+                    Outcome::Single(i) => {
+                        let needs: Vec<String> = ModList::with_dependencies(&session.mods, &[*i])
+                            .into_iter()
+                            .filter(|d| d != i && !session.mods[*d].force_enable)
+                            .map(|d| session.name(d))
+                            .collect();
+                        let mut spans = vec![
+                            Span::from("The problem is caused by "),
+                            Span::styled(session.name(*i), highlight),
+                        ];
+                        if !needs.is_empty() {
+                            spans.push(Span::from(format!(
+                                ", or one of the mods it needs: {}",
+                                needs.join(", ")
+                            )));
+                        }
+                        Line::from(spans)
+                    }
+                    Outcome::Pair(a, b) => Line::from(vec![
+                        Span::from("The problem happens when both "),
+                        Span::styled(session.name(*a), highlight),
+                        Span::from(" and "),
+                        Span::styled(session.name(*b), highlight),
+                        Span::from(" are enabled"),
+                    ]),
+                    Outcome::Inconclusive => Line::from(
+                        "The bisect was inconclusive, the problem may not be caused by a mod.",
+                    ),
+                };
+                vec![
+                    Line::from(format!("Bisect finished after {} steps.", session.bisect.steps)),
+                    Line::from(""),
+                    result,
+                    Line::from(""),
+                    Line::from("The original enabled mods have been restored.").style(hint),
+                    Line::from("Press any key to go back to the mod list.").style(hint),
+                ]
+            }
+            None => {
+                let mut lines = vec![
+                    Line::from(format!(
+                        "Step {}: {} mods left, looking for {}",
+                        session.bisect.steps + 1,
+                        session.bisect.remaining(),
+                        if session.bisect.looking_for_pair() {
+                            "a pair of mods that conflict"
+                        } else {
+                            "a single mod"
+                        }
+                    )),
+                    Line::from(""),
+                    Line::from("Enabled for this test:"),
+                ];
+                let left_out = session.left_out();
+                for i in session.bisect.test_set() {
+                    if !left_out.contains(&i) {
+                        lines.push(Line::from(format!("  {}", session.name(i))).style(highlight));
+                    }
+                }
+                if !left_out.is_empty() {
+                    lines.push(Line::from("Left disabled because a mod they need isn't being tested:"));
+                    for i in left_out {
+                        lines.push(Line::from(format!("  {}", session.name(i))).style(hint));
+                    }
+                }
+                let dependencies = session.dependencies();
+                if !dependencies.is_empty() {
+                    lines.push(Line::from("Also enabled because they are needed:"));
+                    for i in dependencies {
+                        lines.push(Line::from(format!("  {}", session.name(i))).style(hint));
+                    }
+                }
+                lines.push(Line::from(""));
+                lines.push(Line::from("Did the problem reproduce? (y/n)"));
+                lines.push(
+                    Line::from("l: launch the game again, esc: abort and restore the original mods")
+                        .style(hint),
+                );
+                lines
+            }
+        }
+    }
+}
+
 impl Component for ModlistComponent {
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
         self.action_tx = Some(tx.clone());
@@ -89,7 +412,48 @@ impl Component for ModlistComponent {
         Ok(())
     }
     fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let Some(editor) = self.config_editor.as_mut() {
+            editor.handle_key_event(key)?;
+            if editor.closed {
+                self.config_editor = None;
+                self.build_options();
+            }
+            return Ok(None);
+        }
+        if let Some(orphans) = self.orphaned_configs.as_mut() {
+            orphans.handle_key_event(key)?;
+            if orphans.closed {
+                self.orphaned_configs = None;
+                self.build_options();
+            }
+            return Ok(None);
+        }
+        if let Some(collisions) = self.collisions.as_mut() {
+            collisions.handle_key_event(key)?;
+            if collisions.closed {
+                self.collisions = None;
+            }
+            return Ok(None);
+        }
+        if let Some(session) = &self.bisect {
+            if session.bisect.outcome().is_some() {
+                self.bisect = None;
+                return Ok(None);
+            }
+            match key.code {
+                KeyCode::Char('y') => self.answer_bisect(true)?,
+                KeyCode::Char('n') => self.answer_bisect(false)?,
+                KeyCode::Char('l') => self.relaunch()?,
+                KeyCode::Esc => self.abort_bisect()?,
+                _ => {}
+            }
+            return Ok(None);
+        }
         match key.code {
+            KeyCode::Char('b') => self.start_bisect()?,
+            KeyCode::Char('c') => self.configure_selected()?,
+            KeyCode::Char('o') => self.review_orphaned_configs()?,
+            KeyCode::Char('p') => self.check_patch_collisions()?,
             _ => {
                 self.options.handle_key_event(key)?;
             }
@@ -98,6 +462,9 @@ impl Component for ModlistComponent {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Some(collisions) = self.collisions.as_mut() {
+            collisions.update(action.clone())?;
+        }
         match action {
             Action::Tick => {
                 let act = self.local_action_rx.try_recv();
@@ -112,12 +479,61 @@ impl Component for ModlistComponent {
                     }
                 }                
             },
+            Action::Quit if self.bisect.is_some() => self.abort_bisect()?,
+            Action::ModsChanged => {
+                self.mods = ModList::get_local_mods();
+                self.build_options();
+            }
+            Action::GameStarted => {
+                self.game_running = true;
+                if let Some(editor) = self.config_editor.as_mut() {
+                    editor.game_running = true;
+                }
+            }
+            Action::GameExited => {
+                self.game_running = false;
+                if let Some(editor) = self.config_editor.as_mut() {
+                    editor.game_running = false;
+                }
+                if let Some(session) = self.bisect.as_mut()
+                    && session.launch_when_stopped
+                {
+                    session.launch_when_stopped = false;
+                    self.send(Action::LaunchGame(true))?;
+                }
+            }
             _ => {}
         }
         Ok(None)
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if let Some(editor) = self.config_editor.as_mut() {
+            return editor.draw(frame, area);
+        }
+        if let Some(orphans) = self.orphaned_configs.as_mut() {
+            return orphans.draw(frame, area);
+        }
+        if let Some(collisions) = self.collisions.as_mut() {
+            return collisions.draw(frame, area);
+        }
+        if let Some(session) = &self.bisect {
+            frame.render_widget(
+                Paragraph::new(self.bisect_lines(session)).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title("Bisecting mods")
+                        .border_style(if self.has_focus {
+                            Style::default().fg(Color::LightCyan)
+                        } else {
+                            Style::default().fg(Color::White)
+                        }),
+                ),
+                area,
+            );
+            return Ok(());
+        }
         self.options.draw(frame, area).expect("Options failed to draw!");
         Ok(())
     }
@@ -125,10 +541,28 @@ impl Component for ModlistComponent {
     fn focus(&mut self) {
         self.has_focus = true;
         self.options.focus();
+        if let Some(editor) = self.config_editor.as_mut() {
+            editor.focus();
+        }
+        if let Some(orphans) = self.orphaned_configs.as_mut() {
+            orphans.focus();
+        }
+        if let Some(collisions) = self.collisions.as_mut() {
+            collisions.focus();
+        }
     }
 
     fn unfocus(&mut self) {
         self.has_focus = false;
         self.options.unfocus();
+        if let Some(editor) = self.config_editor.as_mut() {
+            editor.unfocus();
+        }
+        if let Some(orphans) = self.orphaned_configs.as_mut() {
+            orphans.unfocus();
+        }
+        if let Some(collisions) = self.collisions.as_mut() {
+            collisions.unfocus();
+        }
     }
 }
@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+
+use balatro_tui::scaffold::NewMod;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::info;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::components::textinput::TextInput;
+
+const ID: usize = 0;
+const NAME: usize = 1;
+const PREFIX: usize = 2;
+const AUTHORS: usize = 3;
+const DESCRIPTION: usize = 4;
+const STEAMODDED: usize = 5;
+const LOVELY: usize = 6;
+const GIT: usize = 7;
+const CREATE: usize = 8;
+
+/// Asks for the details of a new mod and generates it in `mod_path`.
+pub struct NewModWizard {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    /// Set once the mod has been generated.
+    pub created: bool,
+    mod_path: PathBuf,
+    inputs: Vec<TextInput>,
+    lovely_patch: bool,
+    git_init: bool,
+    selected: usize,
+    error: Option<String>,
+}
+
+impl NewModWizard {
+    pub fn new(mod_path: PathBuf) -> Self {
+        let folder = mod_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let input = |text: &str, placeholder: &str, title: &str| {
+            TextInput::from(text.to_string(), placeholder.to_string(), title.to_string())
+        };
+        let id = folder.replace(|c: char| !c.is_ascii_alphanumeric(), "");
+        let mut inputs = vec![
+            input(&id, "MyMod", "Id"),
+            input(&folder, "My Mod", "Name"),
+            input(&id.to_lowercase(), "mymod", "Prefix"),
+            input("", "Jimbo, Chicot", "Authors, comma separated"),
+            input("", "What the mod does", "Description"),
+            input("1.0.0~BETA-0614a", "1.0.0~BETA-0614a", "Oldest Steamodded"),
+        ];
+        inputs[ID].focus();
+        Self {
+            action_tx: None,
+            has_focus: false,
+            created: false,
+            mod_path,
+            inputs,
+            lovely_patch: false,
+            git_init: true,
+            selected: ID,
+            error: None,
+        }
+    }
+
+    fn new_mod(&self) -> NewMod {
+        let text = |i: usize| self.inputs[i].text.trim().to_string();
+        NewMod {
+            id: text(ID),
+            name: text(NAME),
+            prefix: text(PREFIX),
+            authors: text(AUTHORS)
+                .split(',')
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .map(str::to_string)
+                .collect(),
+            description: text(DESCRIPTION),
+            steamodded_version: text(STEAMODDED),
+            lovely_patch: self.lovely_patch,
+            git_init: self.git_init,
+        }
+    }
+
+    fn select(&mut self, selected: usize) {
+        if let Some(input) = self.inputs.get_mut(self.selected) {
+            input.unfocus();
+        }
+        self.selected = selected;
+        if let Some(input) = self.inputs.get_mut(self.selected) {
+            input.focus();
+        }
+    }
+
+    fn create(&mut self) {
+        match self.new_mod().create(&self.mod_path) {
+            Ok(()) => {
+                info!("Created a new mod in {}", self.mod_path.display());
+                self.created = true;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    fn option_lines(&self) -> Vec<Line<'static>> {
+        let style = |i: usize| {
+            if i == self.selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            }
+        };
+        let checkbox = |checked: bool| if checked { "[x] " } else { "[ ] " };
+        let mut lines = vec![
+            Line::styled(
+                format!(
+                    "{}Add a lovely.toml for Lovely patches",
+                    checkbox(self.lovely_patch)
+                ),
+                style(LOVELY),
+            ),
+            Line::styled(
+                format!("{}Run git init", checkbox(self.git_init)),
+                style(GIT),
+            ),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Create the mod", style(CREATE)),
+                Span::styled(
+                    format!(" in {}", self.mod_path.display()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]),
+        ];
+        if let Some(e) = &self.error {
+            lines.push(Line::styled(e.clone(), Style::default().fg(Color::Red)));
+        }
+        lines
+    }
+}
+
+impl Component for NewModWizard {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Up => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down => self.select((self.selected + 1).min(CREATE)),
+            KeyCode::Enter if self.selected == CREATE => self.create(),
+            KeyCode::Enter => self.select(self.selected + 1),
+            KeyCode::Char(' ') if self.selected == LOVELY => self.lovely_patch = !self.lovely_patch,
+            KeyCode::Char(' ') if self.selected == GIT => self.git_init = !self.git_init,
+            _ => {
+                if let Some(input) = self.inputs.get_mut(self.selected) {
+                    input.handle_key_event(key)?;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title("No mod here yet, fill this in to make one")
+            .title_bottom(
+                Line::from(" up/down move, space toggle, enter on create to make the mod ")
+                    .right_aligned(),
+            )
+            .border_style(if self.has_focus {
+                Style::default().fg(Color::LightCyan)
+            } else {
+                Style::default().fg(Color::White)
+            });
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(9), Constraint::Min(0)])
+            .split(inner);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(rows[0]);
+        for (i, input) in self.inputs.iter_mut().enumerate() {
+            let cells = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3); 3])
+                .split(columns[i / 3]);
+            input.draw(frame, cells[i % 3])?;
+        }
+        frame.render_widget(Paragraph::new(self.option_lines()), rows[1]);
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
@@ -0,0 +1,315 @@
+use std::path::{Path, PathBuf};
+
+use balatro_tui::gamesource::GameSource;
+use balatro_tui::lovely::patch::{self, Patch, Status};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::error;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::components::gamesource::Extraction;
+
+/// Colors the lines of a unified diff.
+pub fn diff_line(line: &str) -> Line<'static> {
+    let color = if line.starts_with("+++") || line.starts_with("---") {
+        Color::White
+    } else if line.starts_with('+') {
+        Color::Green
+    } else if line.starts_with('-') {
+        Color::Red
+    } else if line.starts_with("@@") {
+        Color::Cyan
+    } else {
+        Color::Gray
+    };
+    Line::styled(line.replace('\t', "    "), Style::default().fg(color))
+}
+
+fn status_color(status: &Status) -> Color {
+    match status {
+        Status::Matched(_) => Color::Green,
+        Status::NotVanilla | Status::Skipped => Color::DarkGray,
+        Status::Ambiguous(_) | Status::WrongCount { .. } => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+enum View {
+    List,
+    Diff { target: String, lines: Vec<String> },
+}
+
+/// Checks the Lovely patches of the mod being edited against the vanilla source.
+pub struct PatchChecker {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    game: Option<Result<GameSource, String>>,
+    extraction: Option<Extraction>,
+    patches: Vec<(Patch, Status)>,
+    /// Patch files that couldn't be read, and why.
+    broken_files: Vec<(PathBuf, String)>,
+    view: View,
+    selected: usize,
+    scroll: u16,
+    view_height: u16,
+}
+
+impl PatchChecker {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            game: None,
+            extraction: None,
+            patches: vec![],
+            broken_files: vec![],
+            view: View::List,
+            selected: 0,
+            scroll: 0,
+            view_height: 0,
+        }
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.mod_path).unwrap_or(path)
+    }
+
+    /// Re-reads the patches, extracting the game the first time and again after a failure.
+    fn reload(&mut self) {
+        if !matches!(self.game, Some(Ok(_))) {
+            // the patches are checked once the extraction is done
+            self.game = None;
+            self.extraction.get_or_insert_with(Extraction::start);
+            return;
+        }
+        self.check_patches();
+    }
+
+    fn check_patches(&mut self) {
+        let (patches, errors) = patch::mod_patches(&self.mod_path);
+        self.patches = match &self.game {
+            Some(Ok(game)) => patches
+                .into_iter()
+                .map(|p| {
+                    let status = patch::check(&p, &game.dir, &self.mod_path);
+                    (p, status)
+                })
+                .collect(),
+            _ => vec![],
+        };
+        self.broken_files = errors
+            .into_iter()
+            .map(|(path, e)| (path, e.to_string()))
+            .collect();
+        self.selected = self.selected.min(self.patches.len().saturating_sub(1));
+        self.view = View::List;
+    }
+
+    fn show_diff(&mut self) {
+        let (Some(Ok(game)), Some((selected, _))) = (&self.game, self.patches.get(self.selected))
+        else {
+            return;
+        };
+        if !selected.targets_vanilla() {
+            return;
+        }
+        let target = selected.target.clone();
+        let patches: Vec<Patch> = self.patches.iter().map(|(p, _)| p.clone()).collect();
+        match patch::patch_target(&patches, &target, &game.dir, &self.mod_path) {
+            Ok((vanilla, patched)) => {
+                let diff = patch::unified_diff(&vanilla, &patched, &target);
+                self.view = View::Diff {
+                    target,
+                    lines: diff.lines().map(str::to_string).collect(),
+                };
+                self.scroll = 0;
+            }
+            Err(e) => error!("Failed to read {}: {}", target, e),
+        }
+    }
+
+    fn summary(&self) -> Line<'static> {
+        let count = |f: fn(&Status) -> bool| self.patches.iter().filter(|(_, s)| f(s)).count();
+        let unmatched = count(|s| matches!(s, Status::NoMatch));
+        let ambiguous = count(|s| matches!(s, Status::Ambiguous(_) | Status::WrongCount { .. }));
+        let other = count(Status::is_problem) - unmatched - ambiguous;
+        Line::from(vec![
+            Span::from(format!("{} patches: ", self.patches.len())),
+            Span::styled(
+                format!("{unmatched} without a match"),
+                Style::default().fg(if unmatched > 0 {
+                    Color::Red
+                } else {
+                    Color::Green
+                }),
+            ),
+            Span::from(", "),
+            Span::styled(
+                format!("{ambiguous} with unexpected matches"),
+                Style::default().fg(if ambiguous > 0 {
+                    Color::Yellow
+                } else {
+                    Color::Green
+                }),
+            ),
+            Span::from(", "),
+            Span::styled(
+                format!("{other} other problems"),
+                Style::default().fg(if other > 0 { Color::Red } else { Color::Green }),
+            ),
+        ])
+    }
+
+    fn list_lines(&self) -> Vec<Line<'static>> {
+        match &self.game {
+            Some(Err(e)) => return vec![Line::styled(e.clone(), Style::default().fg(Color::Red))],
+            None if self.extraction.is_some() => {
+                return vec![Line::from("Extracting the game from Balatro.exe...")];
+            }
+            _ => {}
+        }
+        let mut lines = vec![];
+        for (path, e) in &self.broken_files {
+            lines.push(Line::styled(
+                format!("{}: {}", self.relative(path).display(), e),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        if self.patches.is_empty() {
+            lines.push(Line::from(format!(
+                "No Lovely patches found in {}.",
+                self.mod_path.display()
+            )));
+            return lines;
+        }
+        lines.push(self.summary());
+        lines.push(Line::from(""));
+        // keep the selected patch in view below the summary
+        let rows = (self.view_height as usize).saturating_sub(lines.len());
+        let start = (self.selected + 1).saturating_sub(rows);
+        for (i, (p, status)) in self.patches.iter().enumerate().skip(start) {
+            let name_style = if i == self.selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{} #{} ", self.relative(&p.file).display(), p.index),
+                    name_style,
+                ),
+                Span::styled(
+                    format!("{} {} {} ", p.kind_name(), p.position, p.target),
+                    Style::default().fg(Color::LightBlue),
+                ),
+                Span::styled(
+                    status.to_string(),
+                    Style::default().fg(status_color(status)),
+                ),
+            ]));
+        }
+        lines
+    }
+}
+
+impl Component for PatchChecker {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let View::Diff { .. } = self.view {
+            match key.code {
+                KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+                KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+                KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.view_height),
+                KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.view_height),
+                KeyCode::Esc => self.view = View::List,
+                _ => {}
+            }
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.patches.len().saturating_sub(1))
+            }
+            KeyCode::Enter => self.show_diff(),
+            KeyCode::Char('r') => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::Tick = action
+            && let Some(game) = self.extraction.as_ref().and_then(Extraction::finished)
+        {
+            self.extraction = None;
+            self.game = Some(game);
+            self.check_patches();
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.view_height = area.height.saturating_sub(2);
+        let (title, hints, lines, scroll) = match &self.view {
+            View::List => (
+                "Lovely patches checked against the vanilla game".to_string(),
+                " enter diff of the target, r recheck ",
+                self.list_lines(),
+                0,
+            ),
+            View::Diff { target, lines } => {
+                let max_scroll = (lines.len() as u16).saturating_sub(self.view_height);
+                self.scroll = self.scroll.min(max_scroll);
+                (
+                    format!("{target} patched by this mod"),
+                    " esc back ",
+                    lines.iter().map(|l| diff_line(l)).collect(),
+                    self.scroll,
+                )
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(lines).scroll((scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title)
+                    .title_bottom(Line::from(hints).right_aligned())
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        if self.game.is_none() {
+            self.reload();
+        }
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
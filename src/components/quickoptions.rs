@@ -4,7 +4,8 @@ use crate::components::optionselector::{Actions, OptionSelector, OptionSelectorT
 use crate::components::{Component, Eventable};
 use crate::config::{Config, get_config_dir, get_data_dir};
 use crate::tui::Event;
-use balatro_tui::{get_balatro_appdata_dir, get_balatro_dir, install_lovely, launch_balatro, open};
+use balatro_tui::steam::{LaunchOptionsChange, plan_lovely_launch_options_checked};
+use balatro_tui::{get_balatro_appdata_dir, get_balatro_dir, install_lovely, locate_steam_appdata, open};
 use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
 use ratatui::Frame;
 use ratatui::layout::{Rect, Size};
@@ -19,13 +20,14 @@ use std::rc::Rc;
 use tokio::process::Child;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedSender;
-use tracing::error;
+use log::{error, info};
 
 pub struct QuickOptions {
     pub options: OptionSelector,
     pub has_focus: bool,
     action_tx: Option<UnboundedSender<Action>>,
     pub launching_balatro: bool,
+    launch_options_change: Option<LaunchOptionsChange>,
     local_action_tx: mpsc::UnboundedSender<Actions>,
     local_action_rx: mpsc::UnboundedReceiver<Actions>,
 }
@@ -48,6 +50,14 @@ impl QuickOptions {
                 "Launch Balatro With Console".to_string(),
                 Style::default(),
             )],
+            vec![OptionSelectorText::new(
+                "Kill game".to_string(),
+                Style::default(),
+            )],
+            vec![OptionSelectorText::new(
+                "Restart game".to_string(),
+                Style::default(),
+            )],
             vec![OptionSelectorText::new(
                 "Open Balatro data folder".to_string(),
                 Style::default(),
@@ -64,6 +74,10 @@ impl QuickOptions {
                 "Install/Update Lovely".to_string(),
                 Style::default(),
             )],
+            vec![OptionSelectorText::new(
+                "Set Steam launch options for Lovely".to_string(),
+                Style::default(),
+            )],
         ]);
 
         options.title = "Quick Options".to_string();
@@ -75,12 +89,22 @@ impl QuickOptions {
             has_focus: false,
             action_tx: None,
             launching_balatro: false,
+            launch_options_change: None,
             local_action_tx: local_tx,
             local_action_rx: local_rx,
         }
     }
 }
 
+impl QuickOptions {
+    fn send(&self, action: Action) -> color_eyre::Result<()> {
+        if let Some(tx) = &self.action_tx {
+            tx.send(action)?;
+        }
+        Ok(())
+    }
+}
+
 impl Component for QuickOptions {
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
         self.action_tx = Some(tx.clone());
@@ -94,6 +118,18 @@ impl Component for QuickOptions {
             _ => {
                 if self.launching_balatro {
                     self.launching_balatro = false;
+                } else if let Some(change) = self.launch_options_change.take() {
+                    if key.code == KeyCode::Char('y') {
+                        match change.apply() {
+                            Ok(backup) => info!(
+                                "Updated launch options, the old config was backed up to {}",
+                                backup.display()
+                            ),
+                            Err(e) => error!("Failed to update launch options: {}", e),
+                        }
+                    } else {
+                        info!("Left the Steam launch options unchanged.");
+                    }
                 } else {
                     self.options.handle_key_event(key)?;
                 }
@@ -111,21 +147,34 @@ impl Component for QuickOptions {
                     match a {
                         Actions::Selected(c) => match c {
                             0 => {
-                                launch_balatro(true).expect("Balatro failed to launch!");
+                                self.send(Action::LaunchGame(true))?;
                                 self.launching_balatro = true;
                             }
                             1 => {
-                                launch_balatro(false).expect("Balatro failed to launch!");
+                                self.send(Action::LaunchGame(false))?;
                                 self.launching_balatro = true;
                             }
-                            2 => open(get_balatro_dir().to_str().unwrap()),
-                            3 => open(get_balatro_appdata_dir().to_str().unwrap()),
-                            4 => open(get_data_dir().to_str().unwrap()),
-                            5 => {
+                            2 => self.send(Action::KillGame)?,
+                            3 => self.send(Action::RestartGame)?,
+                            4 => open(get_balatro_dir().to_str().unwrap()),
+                            5 => open(get_balatro_appdata_dir().to_str().unwrap()),
+                            6 => open(get_data_dir().to_str().unwrap()),
+                            7 => {
                                 tokio::spawn(async move {
                                     install_lovely().await;
                                 });
                             }
+                            8 => {
+                                let Some(steam) = locate_steam_appdata() else {
+                                    error!("Failed to locate Steam!");
+                                    return Ok(None);
+                                };
+                                match plan_lovely_launch_options_checked(&steam.data_dir) {
+                                    Ok(Some(change)) => self.launch_options_change = Some(change),
+                                    Ok(None) => info!("The Lovely launch options are already set."),
+                                    Err(e) => error!("Failed to read launch options: {}", e),
+                                }
+                            }
                             _ => {}
                         },
                     }
@@ -137,6 +186,34 @@ impl Component for QuickOptions {
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        if let Some(change) = &self.launch_options_change {
+            frame.render_widget(
+                Paragraph::new(vec![
+                    Line::from(format!("This will edit {}", change.path.display())),
+                    Line::from(""),
+                    Line::from(format!("- {}", change.old)).style(Style::default().fg(Color::Red)),
+                    Line::from(format!("+ {}", change.new)).style(Style::default().fg(Color::Green)),
+                    Line::from(""),
+                    Line::from(format!("A backup will be saved to {}", change.backup_path.display()))
+                        .style(Style::default().fg(Color::Gray)),
+                    Line::from("Press y to apply, or any other key to cancel")
+                        .style(Style::default().fg(Color::Gray)),
+                ])
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title("Steam launch options")
+                        .border_style(if self.has_focus {
+                            Style::default().fg(Color::LightCyan)
+                        } else {
+                            Style::default().fg(Color::White)
+                        }),
+                ),
+                area,
+            );
+            return Ok(());
+        }
         if !self.launching_balatro {
             self.options.draw(frame, area)
         } else {
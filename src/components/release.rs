@@ -0,0 +1,180 @@
+use std::path::PathBuf;
+
+use balatro_tui::release::{
+    Release, ReleaseError, build_release, id_and_version, latest_tag, release_files,
+};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::info;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+
+/// Builds a release zip of the mod being edited, showing what will go in it first.
+pub struct ReleaseBuilder {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    version: Result<(String, String), String>,
+    tag: Option<String>,
+    files: Vec<PathBuf>,
+    built: Option<Result<Release, ReleaseError>>,
+    scroll: u16,
+    view_height: u16,
+}
+
+impl ReleaseBuilder {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            version: Ok(Default::default()),
+            tag: None,
+            files: vec![],
+            built: None,
+            scroll: 0,
+            view_height: 0,
+        }
+    }
+
+    fn reload(&mut self) {
+        self.version = id_and_version(&self.mod_path).map_err(|e| e.to_string());
+        self.tag = latest_tag(&self.mod_path);
+        self.files = release_files(&self.mod_path);
+    }
+
+    fn build(&mut self, ignore_tag: bool) {
+        self.reload();
+        let built = build_release(&self.mod_path, ignore_tag);
+        if let Ok(release) = &built {
+            info!(
+                "Built {} with SHA-256 {}",
+                release.path.display(),
+                release.sha256
+            );
+        }
+        self.built = Some(built);
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![];
+        match &self.built {
+            None => {}
+            Some(Ok(release)) => {
+                lines.push(Line::styled(
+                    format!(
+                        "Built {} with {} files",
+                        release.path.display(),
+                        release.files
+                    ),
+                    Style::default().fg(Color::Green),
+                ));
+                lines.push(Line::from(vec![
+                    Span::from("SHA-256 "),
+                    Span::styled(release.sha256.clone(), bold),
+                ]));
+                lines.push(Line::from(""));
+            }
+            Some(Err(e @ ReleaseError::VersionMismatch { .. })) => {
+                lines.push(Line::styled(
+                    format!("Not built: {e}. Tag the release, or press B to build anyway."),
+                    Style::default().fg(Color::Red),
+                ));
+                lines.push(Line::from(""));
+            }
+            Some(Err(e)) => {
+                lines.push(Line::styled(
+                    format!("Not built: {e}"),
+                    Style::default().fg(Color::Red),
+                ));
+                lines.push(Line::from(""));
+            }
+        }
+        match &self.version {
+            Ok((id, version)) => lines.push(Line::from(vec![
+                Span::from("Will build "),
+                Span::styled(format!("releases/{id}-{version}.zip"), bold),
+                Span::from(match &self.tag {
+                    Some(tag) => format!(", the latest git tag is {tag}"),
+                    None => ", there are no git tags to check the version against".to_string(),
+                }),
+            ])),
+            Err(e) => lines.push(Line::styled(e.clone(), Style::default().fg(Color::Red))),
+        }
+        lines.push(Line::from(format!(
+            "{} files, leaving out .git, editor files and what .gitignore and .modignore ignore:",
+            self.files.len()
+        )));
+        lines.extend(self.files.iter().map(|f| {
+            Line::styled(
+                format!("    {}", f.display()),
+                Style::default().fg(Color::Gray),
+            )
+        }));
+        lines
+    }
+}
+
+impl Component for ReleaseBuilder {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.view_height),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.view_height),
+            KeyCode::Char('b') => self.build(false),
+            KeyCode::Char('B') => self.build(true),
+            KeyCode::Char('r') => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines = self.lines();
+        self.view_height = area.height.saturating_sub(2);
+        let max_scroll = (lines.len() as u16).saturating_sub(self.view_height);
+        self.scroll = self.scroll.min(max_scroll);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Build release")
+                    .title_bottom(
+                        Line::from(" b build, B build without checking the tag, r refresh ")
+                            .right_aligned(),
+                    )
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
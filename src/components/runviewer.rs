@@ -0,0 +1,231 @@
+use balatro_tui::get_balatro_appdata_dir;
+use balatro_tui::run::{CardSummary, RunSummary, save_path};
+use balatro_tui::saves::PROFILE_SLOTS;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+
+pub struct RunViewer {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    slot: &'static str,
+    /// The run, or why it couldn't be read.
+    run: Result<Option<RunSummary>, String>,
+    scroll: u16,
+    view_height: u16,
+    loaded: bool,
+}
+
+fn heading(text: String) -> Line<'static> {
+    Line::from(Span::styled(
+        text,
+        Style::default()
+            .fg(Color::LightCyan)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+fn card_line(card: &CardSummary) -> Line<'static> {
+    let mut spans = vec![Span::from(format!("  {}", card.name))];
+    if let Some(edition) = &card.edition {
+        spans.push(Span::styled(
+            format!(" [{edition}]"),
+            Style::default().fg(Color::LightMagenta),
+        ));
+    }
+    if let Some(seal) = &card.seal {
+        spans.push(Span::styled(
+            format!(" [{seal} seal]"),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    for sticker in &card.stickers {
+        spans.push(Span::styled(
+            format!(" [{sticker}]"),
+            Style::default().fg(Color::LightRed),
+        ));
+    }
+    spans.push(Span::styled(
+        format!(" {}", card.key),
+        Style::default().fg(Color::DarkGray),
+    ));
+    Line::from(spans)
+}
+
+impl RunViewer {
+    pub fn new() -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            slot: PROFILE_SLOTS[0],
+            run: Ok(None),
+            scroll: 0,
+            view_height: 0,
+            loaded: false,
+        }
+    }
+
+    fn reload(&mut self) {
+        self.loaded = true;
+        self.run = RunSummary::read(&get_balatro_appdata_dir(), self.slot).map_err(|e| {
+            format!(
+                "Failed to read {}: {}",
+                save_path(&get_balatro_appdata_dir(), self.slot).display(),
+                e
+            )
+        });
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let run = match &self.run {
+            Ok(Some(run)) => run,
+            Ok(None) => {
+                return vec![Line::from(format!(
+                    "No run in progress in profile {}.",
+                    self.slot
+                ))];
+            }
+            Err(e) => {
+                return vec![Line::from(Span::styled(
+                    e.clone(),
+                    Style::default().fg(Color::Red),
+                ))];
+            }
+        };
+
+        let value = Style::default().fg(Color::Yellow);
+        let mut lines = vec![
+            Line::from(vec![
+                Span::from("Seed "),
+                Span::styled(run.seed.clone(), value),
+                Span::from("  Deck "),
+                Span::styled(run.deck.clone(), value),
+                Span::from("  Stake "),
+                Span::styled(run.stake.to_string(), value),
+            ]),
+            Line::from(vec![
+                Span::from("Ante "),
+                Span::styled(run.ante.to_string(), value),
+                Span::from("  Round "),
+                Span::styled(run.round.to_string(), value),
+                Span::from("  Money "),
+                Span::styled(format!("${}", run.money), value),
+            ]),
+        ];
+        if let Some(version) = &run.game_version {
+            lines.push(Line::from(Span::styled(
+                format!("Saved by Balatro {version}"),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(heading(format!("Jokers ({})", run.jokers.len())));
+        lines.extend(run.jokers.iter().map(card_line));
+
+        lines.push(Line::from(""));
+        lines.push(heading(format!("Consumables ({})", run.consumables.len())));
+        lines.extend(run.consumables.iter().map(card_line));
+
+        lines.push(Line::from(""));
+        lines.push(heading(format!("Vouchers ({})", run.vouchers.len())));
+        lines.extend(run.vouchers.iter().map(|v| Line::from(format!("  {v}"))));
+
+        // group identical cards, a deck is mostly duplicates of the same few kinds
+        let mut cards: Vec<(&CardSummary, usize)> = vec![];
+        for card in &run.cards {
+            match cards.iter_mut().find(|(c, _)| *c == card) {
+                Some((_, count)) => *count += 1,
+                None => cards.push((card, 1)),
+            }
+        }
+        lines.push(Line::from(""));
+        lines.push(heading(format!("Deck ({} cards)", run.cards.len())));
+        for (card, count) in cards {
+            let mut line = card_line(card);
+            if count > 1 {
+                line.spans.insert(
+                    0,
+                    Span::styled(format!("  {count}x"), Style::default().fg(Color::Gray)),
+                );
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+impl Component for RunViewer {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char(c) if PROFILE_SLOTS.iter().any(|s| s.starts_with(c)) => {
+                self.slot = PROFILE_SLOTS.iter().find(|s| s.starts_with(c)).unwrap();
+                self.scroll = 0;
+                self.reload();
+            }
+            KeyCode::Char('r') => self.reload(),
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.view_height),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.view_height),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick if !self.loaded => self.reload(),
+            // the game saves the run when it closes
+            Action::GameExited => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines = self.lines();
+        self.view_height = area.height.saturating_sub(2);
+        let max_scroll = (lines.len() as u16).saturating_sub(self.view_height);
+        self.scroll = self.scroll.min(max_scroll);
+
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!("Current run in profile {}", self.slot))
+                    .title_bottom(Line::from(" 1/2/3 profile, r reload ").right_aligned())
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
@@ -0,0 +1,342 @@
+use std::path::PathBuf;
+
+use balatro_tui::saves::{
+    PROFILE_SLOTS, Snapshot, existing_slots, format_size, list_snapshots, restore, take_snapshot,
+};
+use balatro_tui::{get_balatro_appdata_dir, open};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::{error, info, warn};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Eventable};
+use crate::action::Action;
+use crate::components::optionselector::{Actions, OptionSelector, OptionSelectorText};
+use crate::config::get_data_dir;
+
+pub fn backup_dir() -> PathBuf {
+    get_data_dir().join("backups")
+}
+
+/// Snapshots every profile that exists, logging the result.
+pub fn backup_profiles() {
+    let appdata = get_balatro_appdata_dir();
+    let slots = existing_slots(&appdata);
+    if slots.is_empty() {
+        warn!("No Balatro profiles found to back up.");
+        return;
+    }
+    match take_snapshot(&appdata, &backup_dir(), &slots) {
+        Ok(snapshot) => info!(
+            "Backed up profiles {} to {}",
+            slots.join(", "),
+            snapshot.path.display()
+        ),
+        Err(e) => error!("Failed to back up profiles: {}", e),
+    }
+}
+
+/// The steps of restoring a snapshot, each holding the index of the snapshot being restored.
+enum Prompt {
+    None,
+    FromSlot(usize),
+    ToSlot(usize, String),
+    Confirm(usize, String, String),
+}
+
+pub struct SaveBackups {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    /// Mirrors the `backup_saves_on_launch` config option, for display.
+    pub backup_on_launch: bool,
+    snapshots: Vec<Snapshot>,
+    options: OptionSelector,
+    prompt: Prompt,
+    game_running: bool,
+    local_action_tx: mpsc::UnboundedSender<Actions>,
+    local_action_rx: mpsc::UnboundedReceiver<Actions>,
+    loaded: bool,
+}
+
+impl SaveBackups {
+    pub fn new() -> Self {
+        let mut options = OptionSelector::new(vec![]);
+        options.title = "Snapshots (enter to restore)".to_string();
+
+        let (local_tx, local_rx) = mpsc::unbounded_channel();
+
+        Self {
+            action_tx: None,
+            has_focus: false,
+            backup_on_launch: false,
+            snapshots: vec![],
+            options,
+            prompt: Prompt::None,
+            game_running: false,
+            local_action_tx: local_tx,
+            local_action_rx: local_rx,
+            loaded: false,
+        }
+    }
+
+    fn reload(&mut self) {
+        self.loaded = true;
+        self.snapshots = list_snapshots(&backup_dir());
+        self.options.options = self
+            .snapshots
+            .iter()
+            .map(|s| {
+                vec![
+                    OptionSelectorText::new(
+                        s.created.format("%Y-%m-%d %H:%M:%S").to_string(),
+                        Style::default(),
+                    ),
+                    OptionSelectorText::new(
+                        format!("  {:>10}", format_size(s.size)),
+                        Style::default().fg(Color::LightBlue),
+                    ),
+                    OptionSelectorText::new(
+                        format!("  profiles {}", s.slots.join(", ")),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]
+            })
+            .collect();
+        self.options.selected = self
+            .options
+            .selected
+            .min(self.snapshots.len().saturating_sub(1));
+    }
+
+    fn start_restore(&mut self, index: usize) {
+        let Some(snapshot) = self.snapshots.get(index) else {
+            return;
+        };
+        if self.game_running {
+            error!("Close Balatro before restoring a snapshot, it would overwrite it on exit.");
+            return;
+        }
+        self.prompt = match snapshot.slots.as_slice() {
+            [] => {
+                error!("This snapshot has no profiles in it.");
+                Prompt::None
+            }
+            [slot] => Prompt::ToSlot(index, slot.clone()),
+            _ => Prompt::FromSlot(index),
+        };
+    }
+
+    fn answer_prompt(&mut self, key: KeyEvent) {
+        let slot = match key.code {
+            KeyCode::Char(c) => PROFILE_SLOTS.iter().find(|s| s.starts_with(c)),
+            _ => None,
+        };
+        self.prompt = match std::mem::replace(&mut self.prompt, Prompt::None) {
+            Prompt::FromSlot(index) => match slot {
+                Some(slot) if self.snapshots[index].slots.iter().any(|s| s == slot) => {
+                    Prompt::ToSlot(index, slot.to_string())
+                }
+                _ => Prompt::FromSlot(index),
+            },
+            Prompt::ToSlot(index, from) => match slot {
+                Some(slot) => Prompt::Confirm(index, from, slot.to_string()),
+                None => Prompt::ToSlot(index, from),
+            },
+            Prompt::Confirm(index, from, to) => {
+                if key.code == KeyCode::Char('y') {
+                    self.restore(index, &from, &to);
+                }
+                Prompt::None
+            }
+            Prompt::None => Prompt::None,
+        };
+    }
+
+    fn restore(&mut self, index: usize, from: &str, to: &str) {
+        let snapshot = &self.snapshots[index];
+        match restore(
+            snapshot,
+            from,
+            &get_balatro_appdata_dir(),
+            to,
+            &backup_dir(),
+        ) {
+            Ok(backup) => {
+                if let Some(backup) = backup {
+                    info!(
+                        "Backed up the old profile {} to {}",
+                        to,
+                        backup.path.display()
+                    );
+                }
+                info!(
+                    "Restored profile {} from {} into slot {}",
+                    from,
+                    snapshot.created.format("%Y-%m-%d %H:%M:%S"),
+                    to
+                );
+            }
+            Err(e) => error!("Failed to restore the snapshot: {}", e),
+        }
+        self.reload();
+    }
+
+    fn prompt_line(&self) -> Line<'static> {
+        let highlight = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        match &self.prompt {
+            Prompt::None => Line::from(Span::styled(
+                "s: snapshot all profiles now, o: open the backups folder, r: reload",
+                Style::default().fg(Color::Gray),
+            )),
+            Prompt::FromSlot(index) => Line::from(format!(
+                "Restore which profile? ({}, esc to cancel)",
+                self.snapshots[*index].slots.join("/")
+            )),
+            Prompt::ToSlot(_, from) => Line::from(format!(
+                "Restore profile {from} into which slot? (1/2/3, esc to cancel)"
+            )),
+            Prompt::Confirm(_, from, to) => Line::from(vec![
+                Span::from(format!(
+                    "Replace slot {to} with profile {from} from this snapshot? "
+                )),
+                Span::styled("The current slot is backed up first. (y/n)", highlight),
+            ]),
+        }
+    }
+}
+
+impl Component for SaveBackups {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx.clone());
+        self.options.register_action_handler(tx)?;
+        self.options
+            .register_local_action_handler(self.local_action_tx.clone())?;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if !matches!(self.prompt, Prompt::None) {
+            if key.code == KeyCode::Esc {
+                self.prompt = Prompt::None;
+            } else {
+                self.answer_prompt(key);
+            }
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Char('s') => {
+                backup_profiles();
+                self.reload();
+            }
+            KeyCode::Char('o') => {
+                let dir = backup_dir();
+                if let Err(e) = std::fs::create_dir_all(&dir) {
+                    error!("Failed to create {}: {}", dir.display(), e);
+                }
+                open(dir.to_str().unwrap());
+            }
+            KeyCode::Char('r') => self.reload(),
+            _ => {
+                self.options.handle_key_event(key)?;
+            }
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick => {
+                if !self.loaded {
+                    self.reload();
+                }
+                if let Ok(Actions::Selected(c)) = self.local_action_rx.try_recv() {
+                    self.start_restore(c);
+                }
+            }
+            Action::GameStarted => {
+                self.game_running = true;
+                // pick up the snapshot taken on launch
+                self.reload();
+            }
+            Action::GameExited => self.game_running = false,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(3)])
+            .split(area);
+
+        let info = vec![
+            Line::from(vec![
+                Span::from("Profiles are backed up to "),
+                Span::styled(
+                    backup_dir().display().to_string(),
+                    Style::default().fg(Color::LightBlue),
+                ),
+                Span::styled(
+                    if self.backup_on_launch {
+                        " on every launch"
+                    } else {
+                        " (set backup_saves_on_launch to back up on every launch)"
+                    },
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]),
+            self.prompt_line(),
+        ];
+        frame.render_widget(
+            Paragraph::new(info).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Save Backups")
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            chunks[0],
+        );
+
+        if self.snapshots.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No snapshots yet, press s to take one.").block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title("Snapshots"),
+                ),
+                chunks[1],
+            );
+        } else {
+            self.options.draw(frame, chunks[1])?;
+        }
+
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.options.focus();
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+        self.options.unfocus();
+        self.prompt = Prompt::None;
+    }
+}
@@ -0,0 +1,289 @@
+use balatro_tui::get_balatro_appdata_dir;
+use balatro_tui::jkr::Value;
+use balatro_tui::settings::{FIELDS, FieldKind, Settings, settings_path};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::{error, info};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+use crate::components::textinput::TextInput;
+
+fn format_value(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::Bool(b)) => if *b { "on" } else { "off" }.to_string(),
+        Some(Value::Number(n)) => n.to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Nil) | None => "(not set)".to_string(),
+        Some(Value::Table(_)) => "(table)".to_string(),
+    }
+}
+
+pub struct SettingsEditor {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    /// The settings being edited, `None` if the file doesn't exist, or why it couldn't be read.
+    settings: Result<Option<Settings>, String>,
+    /// The settings as loaded, to mark what was changed.
+    original: Option<Settings>,
+    selected: usize,
+    input: TextInput,
+    editing: bool,
+    game_running: bool,
+    loaded: bool,
+}
+
+impl SettingsEditor {
+    pub fn new() -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            settings: Ok(None),
+            original: None,
+            selected: 0,
+            input: TextInput::new(),
+            editing: false,
+            game_running: false,
+            loaded: false,
+        }
+    }
+
+    fn reload(&mut self) {
+        self.loaded = true;
+        self.editing = false;
+        let path = settings_path(&get_balatro_appdata_dir());
+        self.settings = if path.exists() {
+            Settings::load(path.clone())
+                .map(Some)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        } else {
+            Ok(None)
+        };
+        self.original = self.settings.as_ref().ok().cloned().flatten();
+    }
+
+    fn is_modified(&self, index: usize) -> bool {
+        let field = &FIELDS[index];
+        match (&self.settings, &self.original) {
+            (Ok(Some(settings)), Some(original)) => settings.get(field) != original.get(field),
+            _ => false,
+        }
+    }
+
+    fn is_dirty(&self) -> bool {
+        (0..FIELDS.len()).any(|i| self.is_modified(i))
+    }
+
+    /// Cycles bool and choice fields, or starts typing a number.
+    fn edit_selected(&mut self) {
+        let Ok(Some(settings)) = &mut self.settings else {
+            return;
+        };
+        let field = &FIELDS[self.selected];
+        match field.next(settings.get(field)) {
+            Some(value) => {
+                if let Err(e) = settings.set(field, value) {
+                    error!("{}", e);
+                }
+            }
+            None => {
+                self.input.text = format_value(settings.get(field));
+                self.input.title = match field.kind {
+                    FieldKind::Number { min, max } => {
+                        format!("{} ({} to {}, enter to confirm)", field.label, min, max)
+                    }
+                    _ => format!("{} (enter to confirm)", field.label),
+                };
+                self.input.focus();
+                self.editing = true;
+            }
+        }
+    }
+
+    fn finish_editing(&mut self) {
+        self.editing = false;
+        self.input.unfocus();
+        let Ok(Some(settings)) = &mut self.settings else {
+            return;
+        };
+        let field = &FIELDS[self.selected];
+        match field.parse(&self.input.text) {
+            Ok(value) => {
+                if let Err(e) = settings.set(field, value) {
+                    error!("{}", e);
+                }
+            }
+            Err(e) => error!("{}", e),
+        }
+    }
+
+    fn save(&mut self) {
+        let Ok(Some(settings)) = &self.settings else {
+            return;
+        };
+        if self.game_running {
+            error!("Close Balatro before saving settings, it writes its own on exit.");
+            return;
+        }
+        match settings.save() {
+            Ok(backup) => {
+                info!(
+                    "Saved {}, the old settings are in {}",
+                    settings.path.display(),
+                    backup.display()
+                );
+                self.original = Some(settings.clone());
+            }
+            Err(e) => error!("Failed to save settings: {}", e),
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let settings = match &self.settings {
+            Ok(Some(settings)) => settings,
+            Ok(None) => {
+                return vec![Line::from(
+                    "No settings.jkr found, the game creates it on first launch.",
+                )];
+            }
+            Err(e) => {
+                return vec![Line::from(Span::styled(
+                    e.clone(),
+                    Style::default().fg(Color::Red),
+                ))];
+            }
+        };
+        FIELDS
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let style = if i == self.selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let mut spans = vec![
+                    Span::styled(format!("{:<22}", field.label), style),
+                    Span::styled(
+                        format_value(settings.get(field)),
+                        Style::default().fg(Color::LightBlue),
+                    ),
+                ];
+                if self.is_modified(i) {
+                    spans.push(Span::styled(" *", Style::default().fg(Color::LightRed)));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+}
+
+impl Component for SettingsEditor {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.editing {
+            match key.code {
+                KeyCode::Enter => self.finish_editing(),
+                KeyCode::Esc => {
+                    self.editing = false;
+                    self.input.unfocus();
+                }
+                _ => {
+                    self.input.handle_key_event(key)?;
+                }
+            }
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(FIELDS.len() - 1),
+            KeyCode::Enter | KeyCode::Char(' ') => self.edit_selected(),
+            KeyCode::Char('s') => self.save(),
+            KeyCode::Char('r') => {
+                self.reload();
+                info!("Reloaded settings, unsaved changes were discarded.");
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick if !self.loaded => self.reload(),
+            Action::GameStarted => self.game_running = true,
+            Action::GameExited => {
+                self.game_running = false;
+                // the game saved its own settings, pick them up unless we have changes
+                if !self.is_dirty() {
+                    self.reload();
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(if self.editing {
+                vec![Constraint::Min(3), Constraint::Length(3)]
+            } else {
+                vec![Constraint::Min(3)]
+            })
+            .split(area);
+
+        let title = if self.is_dirty() {
+            "Game settings (unsaved changes)"
+        } else {
+            "Game settings"
+        };
+        frame.render_widget(
+            Paragraph::new(self.lines()).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title)
+                    .title_bottom(
+                        Line::from(" enter change, s save, r discard changes ").right_aligned(),
+                    )
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            chunks[0],
+        );
+        if self.editing {
+            self.input.draw(frame, chunks[1])?;
+        }
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        if !self.is_dirty() {
+            self.reload();
+        }
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+        self.editing = false;
+        self.input.unfocus();
+    }
+}
@@ -0,0 +1,411 @@
+use balatro_tui::get_balatro_appdata_dir;
+use balatro_tui::profile::{
+    ProfileStats, STAKES, VANILLA_DECKS, deck_name, profile_path, stake_name, stat_label,
+};
+use balatro_tui::saves::PROFILE_SLOTS;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Career,
+    Decks,
+    Jokers,
+    Compare,
+}
+
+impl Section {
+    const ALL: [Section; 4] = [
+        Section::Career,
+        Section::Decks,
+        Section::Jokers,
+        Section::Compare,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Section::Career => "Career",
+            Section::Decks => "Decks",
+            Section::Jokers => "Jokers",
+            Section::Compare => "Compare slots",
+        }
+    }
+}
+
+/// Sticker colours, following the stake chips in game.
+const STAKE_COLORS: [Color; 8] = [
+    Color::White,
+    Color::Red,
+    Color::Green,
+    Color::DarkGray,
+    Color::Blue,
+    Color::Magenta,
+    Color::LightRed,
+    Color::Yellow,
+];
+
+fn stake_span(stake: usize) -> Span<'static> {
+    Span::styled(
+        stake_name(stake),
+        Style::default().fg(STAKE_COLORS[stake.saturating_sub(1) % STAKE_COLORS.len()]),
+    )
+}
+
+fn heading(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        text.to_string(),
+        Style::default()
+            .fg(Color::LightCyan)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+fn format_stat(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+pub struct StatsView {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    /// Each slot's stats, or why they couldn't be read.
+    profiles: Vec<Result<Option<ProfileStats>, String>>,
+    slot: usize,
+    section: Section,
+    scroll: u16,
+    view_height: u16,
+    loaded: bool,
+}
+
+impl StatsView {
+    pub fn new() -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            profiles: vec![],
+            slot: 0,
+            section: Section::Career,
+            scroll: 0,
+            view_height: 0,
+            loaded: false,
+        }
+    }
+
+    fn reload(&mut self) {
+        self.loaded = true;
+        let appdata = get_balatro_appdata_dir();
+        self.profiles = PROFILE_SLOTS
+            .iter()
+            .map(|slot| {
+                ProfileStats::read(&appdata, slot).map_err(|e| {
+                    format!(
+                        "Failed to read {}: {}",
+                        profile_path(&appdata, slot).display(),
+                        e
+                    )
+                })
+            })
+            .collect();
+    }
+
+    fn career_lines(stats: &ProfileStats) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        if !stats.progress.is_empty() {
+            lines.push(heading("Progress"));
+            for (key, tally, of) in &stats.progress {
+                let percent = if *of > 0.0 { tally / of * 100.0 } else { 0.0 };
+                lines.push(Line::from(format!(
+                    "  {:<24} {:>5}/{:<5} {:>5.1}%",
+                    stat_label(key),
+                    format_stat(*tally),
+                    format_stat(*of),
+                    percent
+                )));
+            }
+            lines.push(Line::from(""));
+        }
+        lines.push(heading("High scores"));
+        for (key, value) in &stats.high_scores {
+            lines.push(Line::from(format!(
+                "  {:<24} {:>12}",
+                stat_label(key),
+                format_stat(*value)
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(heading("Career totals"));
+        for (key, value) in &stats.career {
+            lines.push(Line::from(format!(
+                "  {:<24} {:>12}",
+                stat_label(key),
+                format_stat(*value)
+            )));
+        }
+        lines
+    }
+
+    fn deck_lines(stats: &ProfileStats) -> Vec<Line<'static>> {
+        let mut header = vec![Span::from(format!("{:<20}", "Deck"))];
+        for stake in 1..=STAKES.len() {
+            let mut span = stake_span(stake);
+            span.content = format!("{:<7}", span.content).into();
+            header.push(span);
+        }
+        let mut lines = vec![
+            Line::from(format!(
+                "{} of {} vanilla deck and stake combinations won",
+                stats.deck_stakes_won(),
+                VANILLA_DECKS.len() * STAKES.len()
+            )),
+            Line::from(""),
+            Line::from(header),
+        ];
+        for deck in &stats.decks {
+            let mut spans = vec![Span::from(format!("{:<20}", deck_name(&deck.key)))];
+            for stake in 1..=STAKES.len() {
+                spans.push(if deck.won_at(stake) {
+                    Span::styled(
+                        format!("{:<7}", "●"),
+                        Style::default().fg(STAKE_COLORS[stake - 1]),
+                    )
+                } else {
+                    Span::styled(format!("{:<7}", "·"), Style::default().fg(Color::DarkGray))
+                });
+            }
+            lines.push(Line::from(spans));
+        }
+        lines
+    }
+
+    fn joker_lines(stats: &ProfileStats) -> Vec<Line<'static>> {
+        let mut lines = vec![heading("Sticker coverage")];
+        for stake in 1..=STAKES.len() {
+            lines.push(Line::from(vec![
+                Span::from("  "),
+                stake_span(stake),
+                Span::from(format!(
+                    " or higher: {} of {} used jokers",
+                    stats.jokers_with_sticker(stake),
+                    stats.jokers.len()
+                )),
+            ]));
+        }
+        lines.push(Line::from(""));
+        lines.push(heading("Jokers"));
+        for joker in &stats.jokers {
+            let sticker = match joker.highest() {
+                Some(stake) => stake_span(stake),
+                None => Span::styled("no win", Style::default().fg(Color::DarkGray)),
+            };
+            lines.push(Line::from(vec![
+                Span::from(format!("  {:<28}", joker.key)),
+                sticker,
+            ]));
+        }
+        lines
+    }
+
+    fn compare_lines(&self) -> Vec<Line<'static>> {
+        let slots: Vec<Option<&ProfileStats>> = self
+            .profiles
+            .iter()
+            .map(|p| p.as_ref().ok().and_then(|p| p.as_ref()))
+            .collect();
+
+        let row = |label: &str, value: &dyn Fn(&ProfileStats) -> String| {
+            let mut text = format!("{label:<24}");
+            for stats in &slots {
+                let cell = stats.map(value).unwrap_or_else(|| "-".to_string());
+                text.push_str(&format!("{cell:>14}"));
+            }
+            Line::from(text)
+        };
+
+        let mut header = format!("{:<24}", "");
+        for (slot, stats) in PROFILE_SLOTS.iter().zip(&slots) {
+            let name = stats
+                .map(|s| format!("{} ({})", slot, s.name))
+                .unwrap_or_else(|| slot.to_string());
+            header.push_str(&format!("{name:>14}"));
+        }
+
+        let stat = |key: &'static str| {
+            move |s: &ProfileStats| s.career_stat(key).map(format_stat).unwrap_or_default()
+        };
+        let high_score = |key: &'static str| {
+            move |s: &ProfileStats| {
+                s.high_scores
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, v)| format_stat(*v))
+                    .unwrap_or_default()
+            }
+        };
+
+        vec![
+            heading(&header),
+            row("Wins", &stat("c_wins")),
+            row("Losses", &stat("c_losses")),
+            row("Rounds", &stat("c_rounds")),
+            row("Dollars earned", &stat("c_dollars_earned")),
+            row("Best hand", &high_score("hand")),
+            row("Furthest ante", &high_score("furthest_ante")),
+            row("Win streak", &high_score("win_streak")),
+            row("Deck stakes won", &|s| {
+                format!(
+                    "{}/{}",
+                    s.deck_stakes_won(),
+                    VANILLA_DECKS.len() * STAKES.len()
+                )
+            }),
+            row("Jokers with a sticker", &|s| {
+                s.jokers_with_sticker(1).to_string()
+            }),
+            row("Gold stickers", &|s| {
+                s.jokers_with_sticker(STAKES.len()).to_string()
+            }),
+        ]
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        if self.section == Section::Compare {
+            return self.compare_lines();
+        }
+        let slot = PROFILE_SLOTS[self.slot];
+        let stats = match self.profiles.get(self.slot) {
+            Some(Ok(Some(stats))) => stats,
+            Some(Err(e)) => {
+                return vec![Line::from(Span::styled(
+                    e.clone(),
+                    Style::default().fg(Color::Red),
+                ))];
+            }
+            _ => return vec![Line::from(format!("Profile {slot} is empty."))],
+        };
+        match self.section {
+            Section::Career => Self::career_lines(stats),
+            Section::Decks => Self::deck_lines(stats),
+            Section::Jokers => Self::joker_lines(stats),
+            Section::Compare => unreachable!(),
+        }
+    }
+}
+
+impl Component for StatsView {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char(c) if PROFILE_SLOTS.iter().any(|s| s.starts_with(c)) => {
+                self.slot = PROFILE_SLOTS.iter().position(|s| s.starts_with(c)).unwrap();
+                self.scroll = 0;
+            }
+            KeyCode::Tab => {
+                let i = Section::ALL
+                    .iter()
+                    .position(|s| *s == self.section)
+                    .unwrap();
+                self.section = Section::ALL[(i + 1) % Section::ALL.len()];
+                self.scroll = 0;
+            }
+            KeyCode::BackTab => {
+                let i = Section::ALL
+                    .iter()
+                    .position(|s| *s == self.section)
+                    .unwrap();
+                self.section = Section::ALL[(i + Section::ALL.len() - 1) % Section::ALL.len()];
+                self.scroll = 0;
+            }
+            KeyCode::Char('r') => self.reload(),
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.view_height),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.view_height),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Tick if !self.loaded => self.reload(),
+            Action::GameExited => self.reload(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines = self.lines();
+        self.view_height = area.height.saturating_sub(2);
+        let max_scroll = (lines.len() as u16).saturating_sub(self.view_height);
+        self.scroll = self.scroll.min(max_scroll);
+
+        let mut tabs = vec![Span::from(" ")];
+        for section in Section::ALL {
+            tabs.push(if section == self.section {
+                Span::styled(
+                    section.title(),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Span::from(section.title())
+            });
+            tabs.push(Span::from(" | "));
+        }
+        tabs.pop();
+        if self.section != Section::Compare {
+            tabs.push(Span::from(format!(
+                "  (profile {}) ",
+                PROFILE_SLOTS[self.slot]
+            )));
+        } else {
+            tabs.push(Span::from(" "));
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(Line::from(tabs))
+                    .title_bottom(
+                        Line::from(" tab section, 1/2/3 profile, r reload ").right_aligned(),
+                    )
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        self.reload();
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
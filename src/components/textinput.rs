@@ -1,4 +1,4 @@
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::Frame;
 use ratatui::layout::{Rect, Size};
 use ratatui::style::{Color, Style};
@@ -37,6 +37,12 @@ impl Component for TextInput {
     // }
 
     fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        // leave shortcuts like ctrl-r to the app, but AltGr arrives as ctrl+alt on Windows and
+        // some terminals and is how characters like @ { \ and € are typed
+        let alt_gr = key.modifiers.contains(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if !alt_gr && key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+            return Ok(None);
+        }
         match key.code {
             KeyCode::Char(c) => {
                 self.text.push(c);
@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+
+use balatro_tui::textures::{TextureProblem, Upscaled, one_x_dir, upscale_all, verify};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::info;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+
+/// Makes the 2x textures of the mod being edited and checks them against the 1x ones.
+pub struct TextureTools {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    /// What the last upscale did, if there was one.
+    upscaled: Option<Vec<(PathBuf, Upscaled)>>,
+    problems: Option<Vec<TextureProblem>>,
+    scroll: u16,
+    view_height: u16,
+}
+
+impl TextureTools {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            upscaled: None,
+            problems: None,
+            scroll: 0,
+            view_height: 0,
+        }
+    }
+
+    fn upscale(&mut self, force: bool) {
+        let upscaled = upscale_all(&self.mod_path, force);
+        info!(
+            "Upscaled {} textures of {}",
+            upscaled
+                .iter()
+                .filter(|(_, u)| *u == Upscaled::Written)
+                .count(),
+            self.mod_path.display()
+        );
+        self.upscaled = Some(upscaled);
+        self.problems = Some(verify(&self.mod_path));
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![];
+        if let Some(upscaled) = &self.upscaled {
+            let count = |u: &Upscaled| upscaled.iter().filter(|(_, r)| r == u).count();
+            lines.push(Line::styled(
+                format!(
+                    "Made {} 2x textures, {} were already newer than their 1x version",
+                    count(&Upscaled::Written),
+                    count(&Upscaled::UpToDate)
+                ),
+                bold,
+            ));
+            for (file, result) in upscaled {
+                if let Upscaled::Failed(e) = result {
+                    lines.push(Line::styled(
+                        format!("    {}: {}", file.display(), e),
+                        Style::default().fg(Color::Red),
+                    ));
+                }
+            }
+            lines.push(Line::from(""));
+        }
+        match &self.problems {
+            None => {}
+            Some(_) if !one_x_dir(&self.mod_path).is_dir() => lines.push(Line::from(format!(
+                "No textures in {}.",
+                one_x_dir(&self.mod_path).display()
+            ))),
+            Some(problems) if problems.is_empty() => lines.push(Line::styled(
+                "All 1x and 2x textures and atlases check out.",
+                Style::default().fg(Color::Green),
+            )),
+            Some(problems) => {
+                lines.push(Line::styled(
+                    format!("{} problems with the textures", problems.len()),
+                    bold,
+                ));
+                lines.extend(
+                    problems
+                        .iter()
+                        .map(|p| Line::styled(format!("    {p}"), Style::default().fg(Color::Red))),
+                );
+            }
+        }
+        lines
+    }
+}
+
+impl Component for TextureTools {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.view_height),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(self.view_height),
+            KeyCode::Char('u') => self.upscale(false),
+            KeyCode::Char('U') => self.upscale(true),
+            KeyCode::Char('r') => self.problems = Some(verify(&self.mod_path)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let lines = self.lines();
+        self.view_height = area.height.saturating_sub(2);
+        let max_scroll = (lines.len() as u16).saturating_sub(self.view_height);
+        self.scroll = self.scroll.min(max_scroll);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title("Textures in assets/1x and assets/2x")
+                    .title_bottom(
+                        Line::from(" u make 2x textures, U remake all of them, r recheck ")
+                            .right_aligned(),
+                    )
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+        if self.problems.is_none() {
+            self.problems = Some(verify(&self.mod_path));
+        }
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
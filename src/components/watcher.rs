@@ -0,0 +1,277 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, channel};
+use std::time::{Duration, Instant};
+
+use balatro_tui::devlink::mod_id;
+use balatro_tui::get_balatro_appdata_dir;
+use balatro_tui::lovely::patch::mod_patches;
+use balatro_tui::metadata::{check_metadata, find_metadata_file};
+use balatro_tui::watch::{Debouncer, is_watched, needs_sync, other_copies, sync_mod};
+use chrono::Local;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use log::{error, info};
+use notify::event::EventKind;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::Action;
+
+/// How long changes have to stop coming in before the game is relaunched.
+const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+/// Watches the mod being edited and relaunches the game with it whenever it changes.
+pub struct DevWatcher {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub has_focus: bool,
+    mod_path: PathBuf,
+    /// The watcher and where its events arrive, while watching.
+    watcher: Option<(RecommendedWatcher, Receiver<notify::Result<notify::Event>>)>,
+    debouncer: Debouncer,
+    validate: bool,
+    game_running: bool,
+    log: Vec<Line<'static>>,
+    scroll: u16,
+    view_height: u16,
+}
+
+impl DevWatcher {
+    pub fn new(mod_path: PathBuf) -> Self {
+        Self {
+            action_tx: None,
+            has_focus: false,
+            mod_path,
+            watcher: None,
+            debouncer: Debouncer::new(SETTLE_TIME),
+            validate: true,
+            game_running: false,
+            log: vec![],
+            scroll: 0,
+            view_height: 0,
+        }
+    }
+
+    fn log(&mut self, text: String, color: Color) {
+        self.log.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", Local::now().format("%H:%M:%S")),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(text, Style::default().fg(color)),
+        ]));
+        // follow the end of the log
+        self.scroll = (self.log.len() as u16).saturating_sub(self.view_height);
+    }
+
+    fn toggle_watching(&mut self) {
+        if self.watcher.take().is_some() {
+            self.log("Stopped watching".to_string(), Color::Gray);
+            return;
+        }
+        let (tx, rx) = channel();
+        let watcher = notify::recommended_watcher(tx).and_then(|mut watcher| {
+            watcher.watch(&self.mod_path, RecursiveMode::Recursive)?;
+            Ok(watcher)
+        });
+        match watcher {
+            Ok(watcher) => {
+                self.watcher = Some((watcher, rx));
+                self.log(
+                    format!("Watching {}", self.mod_path.display()),
+                    Color::Green,
+                );
+            }
+            Err(e) => {
+                error!("Failed to watch {}: {}", self.mod_path.display(), e);
+                self.log(format!("Failed to watch: {e}"), Color::Red);
+            }
+        }
+    }
+
+    /// Problems that would stop the mod loading, as messages.
+    fn validation_errors(&self) -> Vec<String> {
+        let mut errors = vec![];
+        match find_metadata_file(&self.mod_path) {
+            Some(file) => {
+                let text = std::fs::read_to_string(&file).unwrap_or_default();
+                for check in check_metadata(&text, &self.mod_path) {
+                    errors.extend(check.problems.iter().map(|p| format!("metadata {p}")));
+                }
+            }
+            None => errors.push("the mod has no metadata JSON".to_string()),
+        }
+        let (_, broken) = mod_patches(&self.mod_path);
+        errors.extend(
+            broken
+                .into_iter()
+                .map(|(file, e)| format!("{}: {}", file.display(), e)),
+        );
+        errors
+    }
+
+    fn on_change(&mut self, changed: Vec<PathBuf>) -> Result<()> {
+        let names: Vec<String> = changed
+            .iter()
+            .map(|p| {
+                p.strip_prefix(&self.mod_path)
+                    .unwrap_or(p)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        self.log(format!("Changed: {}", names.join(", ")), Color::White);
+
+        if self.validate {
+            let errors = self.validation_errors();
+            if !errors.is_empty() {
+                for e in errors {
+                    self.log(e, Color::Red);
+                }
+                self.log(
+                    "Not relaunching until the mod validates".to_string(),
+                    Color::Yellow,
+                );
+                return Ok(());
+            }
+        }
+
+        let mods_dir = get_balatro_appdata_dir().join("Mods");
+        if let Some(id) = mod_id(&self.mod_path)
+            && needs_sync(&mods_dir, &id, &self.mod_path)
+        {
+            match sync_mod(&mods_dir, &id, &self.mod_path) {
+                Ok(count) => self.log(format!("Synced {count} files into Mods/{id}"), Color::White),
+                Err(e) => {
+                    self.log(format!("Failed to sync into Mods/{id}: {e}"), Color::Red);
+                    return Ok(());
+                }
+            }
+            for copy in other_copies(&mods_dir, &id, &self.mod_path) {
+                self.log(
+                    format!(
+                        "{} also has the id {id}, the game will load it too",
+                        copy.display()
+                    ),
+                    Color::Yellow,
+                );
+            }
+        }
+
+        info!("{} changed, relaunching Balatro", self.mod_path.display());
+        self.log("Relaunching the game".to_string(), Color::Green);
+        if let Some(tx) = &self.action_tx {
+            tx.send(if self.game_running {
+                Action::RestartGame
+            } else {
+                Action::LaunchGame(true)
+            })?;
+        }
+        Ok(())
+    }
+
+    fn poll(&mut self) -> Result<()> {
+        let Some((_, rx)) = &self.watcher else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        for event in rx.try_iter().filter_map(|e| e.ok()) {
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            for path in event.paths {
+                if is_watched(&path, &self.mod_path) {
+                    self.debouncer.record(path, now);
+                }
+            }
+        }
+        if let Some(changed) = self.debouncer.take_ready(now) {
+            self.on_change(changed)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for DevWatcher {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Char('w') => self.toggle_watching(),
+            KeyCode::Char('v') => self.validate = !self.validate,
+            KeyCode::Char('c') => self.log.clear(),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            // keeps watching while other tools are open
+            Action::Tick => self.poll()?,
+            Action::GameStarted => self.game_running = true,
+            Action::GameExited => self.game_running = false,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.view_height = area.height.saturating_sub(2);
+        let max_scroll = (self.log.len() as u16).saturating_sub(self.view_height);
+        self.scroll = self.scroll.min(max_scroll);
+        let lines = if self.log.is_empty() {
+            vec![Line::from(
+                "Press w to relaunch the game whenever the mod's Lua, TOML, JSON or assets change.",
+            )]
+        } else {
+            self.log.clone()
+        };
+        let title = format!(
+            "Dev loop: {}, validation {}",
+            if self.watcher.is_some() {
+                "watching"
+            } else {
+                "not watching"
+            },
+            if self.validate { "on" } else { "off" }
+        );
+        frame.render_widget(
+            Paragraph::new(lines).scroll((self.scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title)
+                    .title_bottom(
+                        Line::from(" w start/stop watching, v toggle validation, c clear ")
+                            .right_aligned(),
+                    )
+                    .border_style(if self.has_focus {
+                        Style::default().fg(Color::LightCyan)
+                    } else {
+                        Style::default().fg(Color::White)
+                    }),
+            ),
+            area,
+        );
+        Ok(())
+    }
+
+    fn focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.has_focus = false;
+    }
+}
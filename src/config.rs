@@ -1,5 +1,6 @@
 use std::{collections::HashMap, env, path::PathBuf};
 
+use balatro_tui::launcher::LauncherConfig;
 use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use derive_deref::{Deref, DerefMut};
@@ -19,6 +20,14 @@ pub struct AppConfig {
     pub data_dir: PathBuf,
     #[serde(default)]
     pub config_dir: PathBuf,
+    #[serde(default)]
+    pub launcher: LauncherConfig,
+    /// Snapshot the save profiles every time the game is launched.
+    #[serde(default)]
+    pub backup_saves_on_launch: bool,
+    /// A clone of a fork of the Balatro Mod Index, where mod index submissions are written.
+    #[serde(default)]
+    pub index_fork: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -0,0 +1,320 @@
+//! Finding crashes in Lovely logs and working out which mod caused them.
+
+use std::path::PathBuf;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::mods::Mod;
+
+lazy_static! {
+    static ref LEVEL_PREFIX: Regex = Regex::new(r"^(TRACE|DEBUG|INFO|WARN|ERROR) - (\[[^\]]*\] )?").unwrap();
+    /// `[SMODS SomeMod "file.lua"]:123`, how Steamodded names chunks loaded from mods.
+    static ref SMODS_CHUNK: Regex = Regex::new(r#"\[SMODS (\S+) "([^"]+)"\]:(\d+)"#).unwrap();
+    /// `Mods/SomeMod/main.lua:123`, possibly as part of an absolute or windows path.
+    static ref MODS_PATH: Regex = Regex::new(r"Mods[/\\]([^/\\]+)[/\\]([^:'\x22\s]+\.lua):(\d+)").unwrap();
+    static ref LUA_FILE: Regex = Regex::new(r"([A-Za-z_][\w/.\-]*\.lua):(\d+)").unwrap();
+    static ref ERROR_LINE: Regex = Regex::new(r"\.lua\x22?\]?:\d+: \S").unwrap();
+}
+
+const CRASH_MARKERS: [&str; 3] = ["Oops! The game crashed", "stack traceback", "Traceback"];
+
+/// How far to look past the crash marker for stack frames.
+const MAX_TRACE_LINES: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameSource {
+    /// A frame in a mod, known by its id (from Steamodded's chunk names) or folder.
+    Mod {
+        id: Option<String>,
+        folder: Option<String>,
+    },
+    /// A frame in the vanilla game, or somewhere we can't attribute.
+    Game,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    pub source: FrameSource,
+    pub file: String,
+    pub line: u32,
+}
+
+impl StackFrame {
+    fn parse(line: &str) -> Option<Self> {
+        if let Some(c) = SMODS_CHUNK.captures(line) {
+            return Some(Self {
+                source: FrameSource::Mod {
+                    id: Some(c[1].to_string()),
+                    folder: None,
+                },
+                file: c[2].to_string(),
+                line: c[3].parse().ok()?,
+            });
+        }
+        if let Some(c) = MODS_PATH.captures(line) {
+            return Some(Self {
+                source: FrameSource::Mod {
+                    id: None,
+                    folder: Some(c[1].to_string()),
+                },
+                file: c[2].to_string(),
+                line: c[3].parse().ok()?,
+            });
+        }
+        let c = LUA_FILE.captures(line)?;
+        Some(Self {
+            source: FrameSource::Game,
+            file: c[1].to_string(),
+            line: c[2].parse().ok()?,
+        })
+    }
+
+    /// Whether this frame belongs to `m`, matching by id or by the mod's folder name.
+    pub fn is_from(&self, m: &Mod) -> bool {
+        let FrameSource::Mod { id, folder } = &self.source else {
+            return false;
+        };
+        let folder_name = m.folder.file_name().and_then(|f| f.to_str());
+        id.as_ref()
+            .is_some_and(|id| !m.id.is_empty() && id.eq_ignore_ascii_case(&m.id))
+            || folder.as_deref().is_some_and(|f| Some(f) == folder_name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    pub log: PathBuf,
+    pub message: String,
+    pub frames: Vec<StackFrame>,
+    /// Index of the line in the log where the crash starts.
+    pub line_index: usize,
+}
+
+fn strip_prefix(line: &str) -> &str {
+    match LEVEL_PREFIX.find(line) {
+        Some(m) => &line[m.end()..],
+        None => line,
+    }
+}
+
+impl CrashReport {
+    /// Finds the last crash in a log.
+    pub fn find(log: PathBuf, lines: &[String]) -> Option<Self> {
+        let marker = lines
+            .iter()
+            .rposition(|l| CRASH_MARKERS.iter().any(|m| l.contains(m)))?;
+
+        // step back to the start of the block the marker is part of, however much context the
+        // game wrote between the crash message and the traceback
+        let block_start = (0..=marker)
+            .rev()
+            .find(|i| LEVEL_PREFIX.is_match(&lines[*i]));
+        let block = block_start.unwrap_or(0)..=marker;
+        let start = block
+            .clone()
+            .rev()
+            .find(|i| lines[*i].contains("Oops! The game crashed"))
+            .or(block_start)
+            .or_else(|| {
+                block
+                    .clone()
+                    .find(|i| CRASH_MARKERS.iter().any(|m| lines[*i].contains(m)))
+            })
+            .unwrap_or(marker);
+
+        let mut end = marker + 1;
+        while end < lines.len()
+            && end - marker < MAX_TRACE_LINES
+            && !LEVEL_PREFIX.is_match(&lines[end])
+        {
+            end += 1;
+        }
+        let crash = &lines[start..end];
+
+        let message = Self::find_message(crash)
+            .unwrap_or_else(|| strip_prefix(&lines[marker]).trim().to_string());
+
+        let frame_lines = crash
+            .iter()
+            .map(|l| strip_prefix(l))
+            .filter(|l| !CRASH_MARKERS.iter().any(|m| l.contains(m)));
+        let mut frames: Vec<StackFrame> = frame_lines
+            .clone()
+            .filter(|l| l.trim() != message)
+            .filter_map(StackFrame::parse)
+            .collect();
+        // the error line repeats the top frame, so it only counts without a traceback
+        if frames.is_empty() {
+            frames = frame_lines.filter_map(StackFrame::parse).collect();
+        }
+
+        Some(Self {
+            log,
+            message,
+            frames,
+            line_index: start,
+        })
+    }
+
+    fn find_message(crash: &[String]) -> Option<String> {
+        let oops = crash
+            .iter()
+            .position(|l| l.contains("Oops! The game crashed"));
+        if let Some(i) = oops {
+            let after = crash[i]
+                .split_once("Oops! The game crashed")
+                .map(|(_, r)| r.trim_start_matches(':').trim());
+            if let Some(after) = after.filter(|a| !a.is_empty()) {
+                return Some(after.to_string());
+            }
+            return crash[i + 1..]
+                .iter()
+                .map(|l| strip_prefix(l).trim())
+                .find(|l| !l.is_empty())
+                .map(|l| l.to_string());
+        }
+        crash
+            .iter()
+            .map(|l| strip_prefix(l).trim())
+            .find(|l| ERROR_LINE.is_match(l))
+            .map(|l| l.to_string())
+    }
+
+    /// Picks the installed mod most likely to have caused the crash: the one owning the top-most
+    /// frame, preferring other mods over Steamodded itself since it calls into everything.
+    pub fn suspect<'a>(&self, mods: &'a [Mod]) -> Option<&'a Mod> {
+        let owners: Vec<&Mod> = self
+            .frames
+            .iter()
+            .filter_map(|f| mods.iter().find(|m| f.is_from(m)))
+            .collect();
+        owners
+            .iter()
+            .find(|m| m.id != "steamodded")
+            .or(owners.first())
+            .copied()
+    }
+
+    /// Mods with frames in the trace whose mod is not installed (anymore), by id or folder.
+    pub fn unknown_sources(&self, mods: &[Mod]) -> Vec<String> {
+        let mut unknown = vec![];
+        for frame in &self.frames {
+            if let FrameSource::Mod { id, folder } = &frame.source
+                && !mods.iter().any(|m| frame.is_from(m))
+            {
+                let name = id.clone().or(folder.clone()).unwrap_or_default();
+                if !unknown.contains(&name) {
+                    unknown.push(name);
+                }
+            }
+        }
+        unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const LOG: &str = r#"INFO - [♥] Lovely 0.7.1
+INFO - [G] 2025-01-01 12:00:00 :: INFO :: Loader :: Loading mods
+ERROR - [G] Oops! The game crashed:
+[SMODS JokerPack "jokers/cursed.lua"]:42: attempt to index local 'card' (a nil value)
+
+Additional Context:
+Balatro Version: 1.0.1o-FULL
+
+Stack Traceback
+===============
+(3) Lua local 'calc' at file '[SMODS JokerPack "jokers/cursed.lua"]:42'
+(4) Lua method 'calculate_joker' at file 'card.lua:2345'
+(5) Lua upvalue 'eval' at file 'Mods/smods/src/utils.lua:1500'
+(6) Lua function at file 'C:\Users\steamuser\AppData\Roaming\Balatro\Mods\Other Mod\main.lua:10'
+INFO - [G] after crash"#;
+
+    fn lines() -> Vec<String> {
+        LOG.lines().map(|l| l.to_string()).collect()
+    }
+
+    fn installed(id: &str, folder: &str) -> Mod {
+        Mod {
+            id: id.to_string(),
+            folder: PathBuf::from("/mods").join(folder),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_crash() {
+        let report = CrashReport::find(PathBuf::from("x.log"), &lines()).unwrap();
+        assert_eq!(
+            report.message,
+            "[SMODS JokerPack \"jokers/cursed.lua\"]:42: attempt to index local 'card' (a nil value)"
+        );
+        assert_eq!(report.line_index, 2);
+        let files: Vec<(&str, u32)> = report
+            .frames
+            .iter()
+            .map(|f| (f.file.as_str(), f.line))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ("jokers/cursed.lua", 42),
+                ("card.lua", 2345),
+                ("src/utils.lua", 1500),
+                ("main.lua", 10),
+            ]
+        );
+        assert_eq!(report.frames[1].source, FrameSource::Game);
+    }
+
+    #[test]
+    fn test_find_crash_after_long_context() {
+        let mut lines = lines();
+        let context: Vec<String> = (0..30).map(|i| format!("Mod {i}: enabled")).collect();
+        lines.splice(6..6, context);
+        let report = CrashReport::find(PathBuf::from("x.log"), &lines).unwrap();
+        assert_eq!(report.line_index, 2);
+        assert!(report.message.contains("attempt to index local 'card'"));
+        assert_eq!(report.frames.len(), 4);
+    }
+
+    #[test]
+    fn test_crash_without_traceback() {
+        let lines: Vec<String> = vec![
+            "INFO - [G] loading".to_string(),
+            "ERROR - [G] Oops! The game crashed:".to_string(),
+            "card.lua:12: attempt to call a nil value".to_string(),
+        ];
+        let report = CrashReport::find(PathBuf::from("x.log"), &lines).unwrap();
+        assert_eq!(report.line_index, 1);
+        assert_eq!(report.frames.len(), 1);
+        assert_eq!(report.frames[0].file, "card.lua");
+    }
+
+    #[test]
+    fn test_suspect() {
+        let report = CrashReport::find(PathBuf::from("x.log"), &lines()).unwrap();
+        let mods = vec![
+            installed("steamodded", "smods"),
+            installed("jokerpack", "JokerPack-main"),
+            installed("other", "Other Mod"),
+        ];
+        assert_eq!(report.suspect(&mods).unwrap().id, "jokerpack");
+        assert_eq!(report.suspect(&mods[..1]).unwrap().id, "steamodded");
+        assert_eq!(
+            report.unknown_sources(&mods[..1]),
+            vec!["JokerPack", "Other Mod"]
+        );
+    }
+
+    #[test]
+    fn test_no_crash() {
+        let lines: Vec<String> = vec!["INFO - [♥] Lovely 0.7.1".to_string()];
+        assert!(CrashReport::find(PathBuf::from("x.log"), &lines).is_none());
+    }
+}
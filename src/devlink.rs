@@ -0,0 +1,179 @@
+//! Symlinking a mod in development into the game's `Mods` folder, so the game loads it straight
+//! from where it is being worked on.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::metadata::find_metadata_file;
+
+/// Whether `path` is a symlink, which is how dev mods are installed.
+pub fn is_dev_link(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink())
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// The id in the metadata of the mod in `dir`.
+pub fn mod_id(dir: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(find_metadata_file(dir)?).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&text).ok()?;
+    Some(json.get("id")?.as_str()?.to_string())
+}
+
+/// Folders in `mods_dir` with a mod of the given id that aren't `mod_dir` or a link to it.
+pub fn installed_copies(mods_dir: &Path, id: &str, mod_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(mods_dir) else {
+        return vec![];
+    };
+    let mut copies: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_dir() && !same_dir(p, mod_dir))
+        .filter(|p| mod_id(p).as_deref() == Some(id))
+        .collect();
+    copies.sort();
+    copies
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkState {
+    /// `Mods/<id>` is a link to the mod being developed.
+    Linked,
+    /// `Mods/<id>` is something else, so it can't be linked without removing that first.
+    Occupied,
+    NotLinked,
+}
+
+pub fn link_path(mods_dir: &Path, id: &str) -> PathBuf {
+    mods_dir.join(id)
+}
+
+pub fn link_state(mods_dir: &Path, id: &str, mod_dir: &Path) -> LinkState {
+    let link = link_path(mods_dir, id);
+    if is_dev_link(&link) && same_dir(&link, mod_dir) {
+        LinkState::Linked
+    } else if std::fs::symlink_metadata(&link).is_ok() {
+        LinkState::Occupied
+    } else {
+        LinkState::NotLinked
+    }
+}
+
+#[derive(Debug)]
+pub enum LinkError {
+    Io(PathBuf, io::Error),
+    /// Something other than a link to the mod is in the way.
+    Occupied(PathBuf),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            LinkError::Occupied(path) => write!(
+                f,
+                "{} already exists and isn't a link to this mod, remove it first",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+#[cfg(unix)]
+fn symlink_dir(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(original, link)
+}
+
+/// Links `mod_dir` into `mods_dir` as `<id>`, doing nothing if it already is.
+pub fn link(mods_dir: &Path, id: &str, mod_dir: &Path) -> Result<PathBuf, LinkError> {
+    let link = link_path(mods_dir, id);
+    match link_state(mods_dir, id, mod_dir) {
+        LinkState::Linked => Ok(link),
+        LinkState::Occupied => Err(LinkError::Occupied(link)),
+        LinkState::NotLinked => {
+            let original = mod_dir
+                .canonicalize()
+                .map_err(|e| LinkError::Io(mod_dir.to_path_buf(), e))?;
+            std::fs::create_dir_all(mods_dir)
+                .map_err(|e| LinkError::Io(mods_dir.to_path_buf(), e))?;
+            symlink_dir(&original, &link).map_err(|e| LinkError::Io(link.clone(), e))?;
+            Ok(link)
+        }
+    }
+}
+
+/// Removes the link to `mod_dir`, leaving anything else at `Mods/<id>` alone.
+pub fn unlink(mods_dir: &Path, id: &str, mod_dir: &Path) -> Result<(), LinkError> {
+    let link = link_path(mods_dir, id);
+    match link_state(mods_dir, id, mod_dir) {
+        LinkState::NotLinked => Ok(()),
+        LinkState::Occupied => Err(LinkError::Occupied(link)),
+        // a directory symlink is removed like a file on unix, and like a directory on windows
+        LinkState::Linked => std::fs::remove_file(&link)
+            .or_else(|_| std::fs::remove_dir(&link))
+            .map_err(|e| LinkError::Io(link, e)),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn write_mod(dir: &Path, id: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join(format!("{id}.json")),
+            format!(r#"{{"id": "{id}"}}"#),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_link_and_unlink() {
+        let root = tempdir().unwrap();
+        let (mods, dev) = (root.path().join("Mods"), root.path().join("dev"));
+        write_mod(&dev, "SpeedMod");
+        write_mod(&mods.join("SpeedMod-main"), "SpeedMod");
+        write_mod(&mods.join("Other"), "Other");
+
+        assert_eq!(link_state(&mods, "SpeedMod", &dev), LinkState::NotLinked);
+        let linked = link(&mods, "SpeedMod", &dev).unwrap();
+        assert!(is_dev_link(&linked));
+        assert_eq!(link_state(&mods, "SpeedMod", &dev), LinkState::Linked);
+        assert_eq!(
+            installed_copies(&mods, "SpeedMod", &dev),
+            vec![mods.join("SpeedMod-main")]
+        );
+
+        unlink(&mods, "SpeedMod", &dev).unwrap();
+        assert!(!linked.exists());
+        assert!(dev.join("SpeedMod.json").exists());
+
+        // a real folder in the way is never replaced or removed
+        write_mod(&mods.join("SpeedMod"), "SpeedMod");
+        assert!(matches!(
+            link(&mods, "SpeedMod", &dev),
+            Err(LinkError::Occupied(_))
+        ));
+        assert!(matches!(
+            unlink(&mods, "SpeedMod", &dev),
+            Err(LinkError::Occupied(_))
+        ));
+        assert!(mods.join("SpeedMod").is_dir());
+    }
+}
@@ -0,0 +1,296 @@
+//! Keeping track of the running game process.
+
+use std::io;
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+use crate::launcher::{LauncherConfig, LauncherKind, launch};
+use crate::process::find_processes;
+
+/// The name of the game's process, both under Proton/Wine and on Windows.
+pub const GAME_PROCESS_NAME: &str = "Balatro.exe";
+
+/// How long to wait for the game to show up after launching it. Steam can take a while to
+/// start the game when the client was not running yet.
+const STARTUP_GRACE: Duration = Duration::from_secs(45);
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Stopped,
+    Starting,
+    Running { pid: u32, since: Instant },
+}
+
+/// What changed in the last call to [`GameSupervisor::poll`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    Started,
+    Exited,
+}
+
+#[derive(Debug)]
+pub struct GameSupervisor {
+    /// The process we spawned. For the Steam launcher this is only the steam client.
+    child: Option<Child>,
+    /// False when `child` is a launcher that outlives the game, like the steam client.
+    child_is_game: bool,
+    launched_at: Option<Instant>,
+    last_poll: Option<Instant>,
+    status: GameStatus,
+    /// Set while restarting, holds the `disable_console` flag to relaunch with.
+    pending_restart: Option<bool>,
+    last_disable_console: bool,
+}
+
+impl Default for GameSupervisor {
+    fn default() -> Self {
+        Self {
+            child: None,
+            child_is_game: false,
+            launched_at: None,
+            last_poll: None,
+            status: GameStatus::Stopped,
+            pending_restart: None,
+            last_disable_console: true,
+        }
+    }
+}
+
+impl GameSupervisor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status != GameStatus::Stopped
+    }
+
+    pub fn uptime(&self) -> Option<Duration> {
+        match self.status {
+            GameStatus::Running { since, .. } => Some(since.elapsed()),
+            _ => None,
+        }
+    }
+
+    pub fn launch(&mut self, config: &LauncherConfig, disable_console: bool) -> io::Result<()> {
+        let child = launch(config, disable_console)?;
+        self.track(child, config.kind != LauncherKind::Steam, disable_console);
+        Ok(())
+    }
+
+    /// Starts watching a process that was just launched.
+    fn track(&mut self, child: Child, child_is_game: bool, disable_console: bool) {
+        self.child = Some(child);
+        self.child_is_game = child_is_game;
+        self.launched_at = Some(Instant::now());
+        self.last_disable_console = disable_console;
+        if self.status == GameStatus::Stopped {
+            self.status = GameStatus::Starting;
+        }
+    }
+
+    /// Returns the pid of the actual game, preferring a `Balatro.exe` found in `/proc` over the
+    /// process we spawned (which may just be `steam` or a wrapper script).
+    fn find_game_pid(&mut self) -> Option<u32> {
+        // always reap the child, so it does not linger as a zombie
+        let child_pid = match self.child.as_mut().map(|c| c.try_wait()) {
+            Some(Ok(None)) => self.child.as_ref().map(|c| c.id()),
+            Some(_) => {
+                self.child = None;
+                None
+            }
+            None => None,
+        };
+        if let Some(pid) = find_processes(GAME_PROCESS_NAME).first() {
+            return Some(*pid);
+        }
+        child_pid.filter(|_| self.child_is_game)
+    }
+
+    /// Refreshes the status, at most once per second unless `force` is set.
+    pub fn poll(&mut self, force: bool) -> Option<GameEvent> {
+        if !force && self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return None;
+        }
+        self.last_poll = Some(Instant::now());
+
+        let previous = self.status;
+        self.status = match self.find_game_pid() {
+            Some(pid) => match previous {
+                GameStatus::Running { pid: old, since } if old == pid => {
+                    GameStatus::Running { pid, since }
+                }
+                GameStatus::Running { since, .. } => GameStatus::Running { pid, since },
+                _ => GameStatus::Running {
+                    pid,
+                    since: Instant::now(),
+                },
+            },
+            None if previous != GameStatus::Stopped
+                && !matches!(previous, GameStatus::Running { .. })
+                && self
+                    .launched_at
+                    .is_some_and(|t| t.elapsed() < STARTUP_GRACE) =>
+            {
+                GameStatus::Starting
+            }
+            None => GameStatus::Stopped,
+        };
+
+        match (previous, self.status) {
+            (GameStatus::Running { .. }, GameStatus::Running { .. }) => None,
+            (_, GameStatus::Running { .. }) => Some(GameEvent::Started),
+            (GameStatus::Stopped, _) => None,
+            (_, GameStatus::Stopped) => {
+                self.launched_at = None;
+                Some(GameEvent::Exited)
+            }
+            _ => None,
+        }
+    }
+
+    /// Relaunches the game if a restart is waiting for the old process to exit.
+    pub fn continue_restart(&mut self, config: &LauncherConfig) -> io::Result<bool> {
+        let Some(disable_console) = self.take_restart() else {
+            return Ok(false);
+        };
+        self.launch(config, disable_console)?;
+        Ok(true)
+    }
+
+    /// Whether [`Self::continue_restart`] would relaunch the game now.
+    pub fn restart_due(&self) -> bool {
+        self.status == GameStatus::Stopped && self.pending_restart.is_some()
+    }
+
+    /// The `disable_console` flag to relaunch with, once the game being restarted has exited.
+    fn take_restart(&mut self) -> Option<bool> {
+        if self.status != GameStatus::Stopped {
+            return None;
+        }
+        self.pending_restart.take()
+    }
+
+    /// Kills the game, including any `Balatro.exe` started outside of balatro-tui.
+    pub fn kill(&mut self) -> io::Result<()> {
+        // a launcher is left for `poll` to reap once it exits by itself
+        if self.child_is_game
+            && let Some(mut child) = self.child.take()
+        {
+            // the process may have exited on its own in the meantime, which is fine
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        self.launched_at = None;
+
+        for pid in find_processes(GAME_PROCESS_NAME) {
+            kill_pid(pid)?;
+        }
+        Ok(())
+    }
+
+    /// Kills the game and relaunches it once it has exited, see [`Self::continue_restart`].
+    pub fn restart(&mut self) -> io::Result<()> {
+        self.pending_restart = Some(self.last_disable_console);
+        self.kill()
+    }
+}
+
+#[cfg(unix)]
+fn kill_pid(pid: u32) -> io::Result<()> {
+    // SAFETY: kill has no memory safety requirements
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_pid(pid: u32) -> io::Result<()> {
+    std::process::Command::new("taskkill")
+        .args(["/F", "/PID", &pid.to_string()])
+        .status()
+        .map(|_| ())
+}
+
+/// Formats a duration as `h:mm:ss`.
+pub fn format_uptime(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::process::Command;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn sleeper() -> Child {
+        Command::new("sleep").arg("30").spawn().unwrap()
+    }
+
+    #[test]
+    fn test_starting_running_exited() {
+        let mut game = GameSupervisor::new();
+        assert_eq!(game.status(), GameStatus::Stopped);
+        assert_eq!(game.poll(true), None);
+
+        let child = sleeper();
+        let pid = child.id();
+        game.track(child, true, false);
+        assert_eq!(game.status(), GameStatus::Starting);
+        assert!(game.is_running());
+
+        assert_eq!(game.poll(true), Some(GameEvent::Started));
+        assert!(matches!(game.status(), GameStatus::Running { pid: p, .. } if p == pid));
+        assert!(game.uptime().is_some());
+        // polls are throttled unless forced
+        assert_eq!(game.poll(false), None);
+        assert_eq!(game.poll(true), None);
+
+        game.kill().unwrap();
+        assert_eq!(game.poll(true), Some(GameEvent::Exited));
+        assert_eq!(game.status(), GameStatus::Stopped);
+        assert_eq!(game.poll(true), None);
+    }
+
+    #[test]
+    fn test_launcher_is_not_the_game() {
+        // like the steam client, which stays around without the game running
+        let mut game = GameSupervisor::new();
+        game.track(sleeper(), false, false);
+        assert_eq!(game.poll(true), None);
+        assert_eq!(game.status(), GameStatus::Starting);
+        game.kill().unwrap();
+        let mut launcher = game
+            .child
+            .take()
+            .expect("kill leaves the launcher to be reaped");
+        launcher.kill().unwrap();
+        launcher.wait().unwrap();
+    }
+
+    #[test]
+    fn test_restart_waits_for_exit() {
+        let mut game = GameSupervisor::new();
+        game.track(sleeper(), true, false);
+        assert_eq!(game.poll(true), Some(GameEvent::Started));
+
+        game.restart().unwrap();
+        // the old process is only noticed to be gone on the next poll
+        assert_eq!(game.take_restart(), None);
+        assert_eq!(game.poll(true), Some(GameEvent::Exited));
+        assert!(game.restart_due());
+        assert_eq!(game.take_restart(), Some(false));
+        assert_eq!(game.take_restart(), None);
+    }
+}
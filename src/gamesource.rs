@@ -0,0 +1,261 @@
+//! The game's Lua source, extracted from the zip that LÖVE appends to `Balatro.exe`.
+
+use std::io::{self, Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use zip::ZipArchive;
+
+const EOCD_SIGNATURE: &[u8] = b"PK\x05\x06";
+/// The end of central directory record is 22 bytes plus a comment of up to 64KiB.
+const EOCD_SEARCH: usize = 22 + u16::MAX as usize;
+
+/// Where the appended zip starts in a fused LÖVE executable.
+///
+/// Offsets in the zip are relative to its own start, so it is found by walking back from the
+/// end of central directory record by the size and offset of the central directory.
+pub fn embedded_zip_offset(data: &[u8]) -> Option<usize> {
+    let search_from = data.len().saturating_sub(EOCD_SEARCH);
+    let eocd = search_from
+        + data[search_from..]
+            .windows(EOCD_SIGNATURE.len())
+            .rposition(|w| w == EOCD_SIGNATURE)?;
+    let record = data.get(eocd..eocd + 22)?;
+    let cd_size = u32::from_le_bytes(record[12..16].try_into().unwrap()) as usize;
+    let cd_offset = u32::from_le_bytes(record[16..20].try_into().unwrap()) as usize;
+    eocd.checked_sub(cd_size)?.checked_sub(cd_offset)
+}
+
+/// The version the game shows in its corner, read from `version.jkr` in the archive.
+fn read_version(archive: &mut ZipArchive<Cursor<&[u8]>>) -> io::Result<String> {
+    let mut version = String::new();
+    archive
+        .by_name("version.jkr")
+        .map_err(io::Error::other)?
+        .read_to_string(&mut version)?;
+    let version = version
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if version.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "version.jkr is empty",
+        ));
+    }
+    Ok(version)
+}
+
+/// The cache folder for a version, keeping only characters that are safe in a file name.
+/// `None` for names made only of dots, which would be the cache or its parent.
+fn version_dir_name(version: &str) -> Option<String> {
+    let name: String = version
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    (!name.chars().all(|c| c == '.')).then_some(name)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameSource {
+    pub version: String,
+    pub dir: PathBuf,
+}
+
+impl GameSource {
+    /// Extracts the game from `exe` into `cache/<version>`, unless that version is already there.
+    pub fn extract(exe: &Path, cache: &Path) -> io::Result<Self> {
+        let data = std::fs::read(exe)?;
+        let offset = embedded_zip_offset(&data).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("no zip found in {}", exe.display()),
+            )
+        })?;
+        let mut archive =
+            ZipArchive::new(Cursor::new(&data[offset..])).map_err(io::Error::other)?;
+        let version = read_version(&mut archive)?;
+        let dir = cache.join(version_dir_name(&version).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("version.jkr has an unusable version '{version}'"),
+            )
+        })?);
+        if !dir.exists() {
+            // extract next to the final location so an interrupted extraction isn't used, in a
+            // folder of its own as the tools extract on their own threads
+            std::fs::create_dir_all(cache)?;
+            let partial = tempfile::Builder::new()
+                .prefix(".partial-")
+                .tempdir_in(cache)?;
+            archive.extract(partial.path()).map_err(io::Error::other)?;
+            if let Err(e) = std::fs::rename(partial.path(), &dir) {
+                // another extraction of the same version finished first
+                if !dir.exists() {
+                    return Err(e);
+                }
+            }
+        }
+        Ok(Self { version, dir })
+    }
+
+    /// Every file in the source, relative to its root and sorted.
+    pub fn files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files = vec![];
+        let mut dirs = vec![self.dir.clone()];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if let Ok(relative) = path.strip_prefix(&self.dir) {
+                    files.push(relative.to_path_buf());
+                }
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    pub fn read(&self, file: &Path) -> io::Result<String> {
+        let data = std::fs::read(self.dir.join(file))?;
+        Ok(String::from_utf8_lossy(&data).into_owned())
+    }
+
+    /// Case-insensitive search through the Lua files, stopping after `limit` hits.
+    pub fn search(&self, query: &str, limit: usize) -> io::Result<Vec<Hit>> {
+        let query = query.to_lowercase();
+        let mut hits = vec![];
+        if query.is_empty() {
+            return Ok(hits);
+        }
+        for file in self.files()? {
+            if file.extension().is_none_or(|ext| ext != "lua") {
+                continue;
+            }
+            let source = self.read(&file)?;
+            for (i, line) in source.lines().enumerate() {
+                if line.to_lowercase().contains(&query) {
+                    hits.push(Hit {
+                        file: file.clone(),
+                        line: i + 1,
+                        text: line.trim().to_string(),
+                    });
+                    if hits.len() >= limit {
+                        return Ok(hits);
+                    }
+                }
+            }
+        }
+        Ok(hits)
+    }
+}
+
+/// A line matching a full-text search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    pub file: PathBuf,
+    /// Starting at 1.
+    pub line: usize,
+    pub text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    fn fused_exe(path: &Path, version: &str) {
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        for (name, contents) in [
+            ("version.jkr", version),
+            ("main.lua", "function love.load()\n  G:start_up()\nend\n"),
+            ("engine/ui.lua", "-- UIBox\nfunction UIBox:init()\nend\n"),
+            ("resources/fonts/m6x11plus.ttf", "not a font"),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        let mut exe = b"MZ pretend this is love.exe".repeat(100);
+        exe.extend(zip.finish().unwrap().into_inner());
+        std::fs::write(path, exe).unwrap();
+    }
+
+    #[test]
+    fn test_extract_and_search() {
+        let dir = tempdir().unwrap();
+        let exe = dir.path().join("Balatro.exe");
+        fused_exe(&exe, "1.0.1o-FULL\n");
+
+        let cache = dir.path().join("cache");
+        let source = GameSource::extract(&exe, &cache).unwrap();
+        assert_eq!(source.version, "1.0.1o-FULL");
+        assert_eq!(source.dir, cache.join("1.0.1o-FULL"));
+        assert_eq!(
+            source.files().unwrap(),
+            vec![
+                PathBuf::from("engine/ui.lua"),
+                PathBuf::from("main.lua"),
+                PathBuf::from("resources/fonts/m6x11plus.ttf"),
+                PathBuf::from("version.jkr"),
+            ]
+        );
+
+        let hits = source.search("init", 10).unwrap();
+        assert_eq!(
+            hits,
+            vec![Hit {
+                file: PathBuf::from("engine/ui.lua"),
+                line: 2,
+                text: "function UIBox:init()".to_string(),
+            }]
+        );
+        assert_eq!(source.search("uibox", 1).unwrap().len(), 1);
+        assert!(source.search("", 10).unwrap().is_empty());
+
+        // a second extraction reuses the cache
+        std::fs::write(source.dir.join("main.lua"), "edited").unwrap();
+        let again = GameSource::extract(&exe, &cache).unwrap();
+        assert_eq!(again.read(Path::new("main.lua")).unwrap(), "edited");
+    }
+
+    #[test]
+    fn test_version_stays_in_the_cache() {
+        let dir = tempdir().unwrap();
+        let exe = dir.path().join("Balatro.exe");
+        let cache = dir.path().join("cache");
+
+        fused_exe(&exe, "../1.0 beta\n");
+        let source = GameSource::extract(&exe, &cache).unwrap();
+        assert_eq!(source.dir, cache.join(".._1.0_beta"));
+
+        for version in ["..", "."] {
+            fused_exe(&exe, version);
+            assert!(GameSource::extract(&exe, &cache).is_err());
+        }
+        // nothing is left behind by the refused extractions
+        assert_eq!(std::fs::read_dir(&cache).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_not_fused() {
+        assert_eq!(embedded_zip_offset(b"MZ just an exe"), None);
+        let dir = tempdir().unwrap();
+        let exe = dir.path().join("Balatro.exe");
+        std::fs::write(&exe, "MZ").unwrap();
+        assert!(GameSource::extract(&exe, &dir.path().join("cache")).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! Launching the game with only the mod being made, its dependencies and force enabled mods,
+//! and putting the other mods back afterwards. What was enabled before is written to a journal
+//! first, so a crash in the middle can be undone the next time the TUI starts.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::config::get_data_dir;
+use crate::mods::{Mod, ModList};
+
+pub fn journal_path() -> PathBuf {
+    get_data_dir().join("isolation.json")
+}
+
+/// The journal of a bisect in progress, which changes the enabled mods the same way.
+pub fn bisect_journal_path() -> PathBuf {
+    get_data_dir().join("bisect.json")
+}
+
+/// The mods to keep enabled: the mod with `id`, force enabled mods, and what they depend on.
+pub fn isolated_set(mods: &[Mod], id: &str) -> Vec<usize> {
+    let roots: Vec<usize> = (0..mods.len())
+        .filter(|i| mods[*i].force_enable || mods[*i].id.eq_ignore_ascii_case(id))
+        .collect();
+    ModList::with_dependencies(mods, &roots)
+}
+
+/// The mods a bisect splits into halves: the enabled ones, except what force enabled mods need,
+/// since that stays on throughout.
+pub fn bisect_candidates(mods: &[Mod]) -> Vec<usize> {
+    let forced: Vec<usize> = (0..mods.len()).filter(|i| mods[*i].force_enable).collect();
+    let always = ModList::with_dependencies(mods, &forced);
+    (0..mods.len())
+        .filter(|i| mods[*i].enabled.unwrap_or(true) && !always.contains(i))
+        .collect()
+}
+
+/// The mods to enable for one step of a bisect: `test_set`, force enabled mods and what they
+/// depend on. A mod needing a candidate that is not in `test_set` is left out as well, so a
+/// library is only ever enabled when it is being tested and can be found like any other mod.
+pub fn bisect_step(mods: &[Mod], candidates: &[usize], test_set: &[usize]) -> Vec<usize> {
+    let mut roots: Vec<usize> = test_set
+        .iter()
+        .copied()
+        .filter(|i| {
+            ModList::with_dependencies(mods, &[*i])
+                .iter()
+                .all(|d| !candidates.contains(d) || test_set.contains(d))
+        })
+        .collect();
+    roots.extend((0..mods.len()).filter(|i| mods[*i].force_enable));
+    ModList::with_dependencies(mods, &roots)
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ModState {
+    folder: PathBuf,
+    enabled: bool,
+}
+
+/// An isolated launch in progress, holding what each mod's enabled state was before it.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Isolation {
+    previous: Vec<ModState>,
+}
+
+fn set_enabled(folder: &Path, enabled: bool) -> io::Result<()> {
+    let ignore = folder.join(".lovelyignore");
+    if enabled && ignore.exists() {
+        std::fs::remove_file(ignore)
+    } else if !enabled && !ignore.exists() && folder.is_dir() {
+        std::fs::File::create(ignore).map(|_| ())
+    } else {
+        Ok(())
+    }
+}
+
+impl Isolation {
+    /// Disables every mod not in `keep`, writing the journal before anything is changed.
+    ///
+    /// Refuses to start while a journal is left over, since overwriting it would lose the state
+    /// from before that launch. [`Isolation::recover`] puts that back first.
+    pub fn start(mods: &mut [Mod], keep: &[usize], journal: &Path) -> io::Result<Self> {
+        if journal.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} is left over from a launch that didn't finish",
+                    journal.display()
+                ),
+            ));
+        }
+        let isolation = Self {
+            previous: mods
+                .iter()
+                .map(|m| ModState {
+                    folder: m.folder.clone(),
+                    enabled: m.enabled.unwrap_or(true),
+                })
+                .collect(),
+        };
+        if let Some(parent) = journal.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(journal, serde_json::to_string_pretty(&isolation)?)?;
+        for (i, m) in mods.iter_mut().enumerate() {
+            m.set_enabled(keep.contains(&i))?;
+        }
+        Ok(isolation)
+    }
+
+    /// Puts every mod back how it was and removes the journal. Mods that have since been
+    /// uninstalled are skipped.
+    pub fn restore(&self, journal: &Path) -> io::Result<()> {
+        for state in &self.previous {
+            if let Err(e) = set_enabled(&state.folder, state.enabled) {
+                error!("Failed to restore {}: {}", state.folder.display(), e);
+            }
+        }
+        match std::fs::remove_file(journal) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Restores the state left in the journal by an isolated launch that never finished.
+    /// Returns whether there was one.
+    pub fn recover(journal: &Path) -> io::Result<bool> {
+        let text = match std::fs::read_to_string(journal) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let isolation: Self = serde_json::from_str(&text)?;
+        isolation.restore(journal)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn installed(root: &Path, id: &str, dependencies: &[&str], enabled: bool) -> Mod {
+        let folder = root.join(id);
+        std::fs::create_dir_all(&folder).unwrap();
+        if !enabled {
+            std::fs::File::create(folder.join(".lovelyignore")).unwrap();
+        }
+        Mod {
+            id: id.to_string(),
+            folder,
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            enabled: Some(enabled),
+            force_enable: id == "steamodded",
+            ..Mod::default()
+        }
+    }
+
+    fn enabled(mods: &[Mod]) -> Vec<bool> {
+        mods.iter()
+            .map(|m| !m.folder.join(".lovelyignore").exists())
+            .collect()
+    }
+
+    #[test]
+    fn test_isolate_and_restore() {
+        let root = tempdir().unwrap();
+        let journal = root.path().join("data").join("isolation.json");
+        let mut mods = vec![
+            installed(root.path(), "steamodded", &[], true),
+            installed(root.path(), "Speed", &["Talisman (>=2.0)"], true),
+            installed(root.path(), "Talisman", &[], false),
+            installed(root.path(), "Cryptid", &["Talisman"], true),
+            installed(root.path(), "Other", &[], false),
+        ];
+        let keep = isolated_set(&mods, "speed");
+        assert_eq!(keep, vec![0, 1, 2]);
+
+        let isolation = Isolation::start(&mut mods, &keep, &journal).unwrap();
+        assert!(journal.exists());
+        assert_eq!(enabled(&mods), vec![true, true, true, false, false]);
+
+        // a second start would overwrite the journal with the isolated state
+        let error = Isolation::start(&mut mods, &keep, &journal).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+
+        // as if the TUI crashed and was started again
+        drop(isolation);
+        assert!(Isolation::recover(&journal).unwrap());
+        assert_eq!(enabled(&mods), vec![true, true, false, true, false]);
+        assert!(!journal.exists());
+        assert!(!Isolation::recover(&journal).unwrap());
+    }
+
+    #[test]
+    fn test_bisect_step() {
+        let root = tempdir().unwrap();
+        let mods = vec![
+            installed(root.path(), "steamodded", &["Lovely"], true),
+            installed(root.path(), "Lovely", &[], true),
+            installed(root.path(), "Speed", &["Talisman (>=2.0)"], true),
+            installed(root.path(), "Talisman", &[], true),
+            installed(root.path(), "Cryptid", &["Lib"], true),
+            installed(root.path(), "Lib", &[], false),
+        ];
+        let candidates = bisect_candidates(&mods);
+        assert_eq!(candidates, vec![2, 3, 4]);
+
+        // Speed is left out without the Talisman it needs, rather than enabling Talisman
+        assert_eq!(bisect_step(&mods, &candidates, &[2]), vec![0, 1]);
+        assert_eq!(bisect_step(&mods, &candidates, &[3]), vec![0, 1, 3]);
+        assert_eq!(bisect_step(&mods, &candidates, &[2, 3]), vec![0, 1, 2, 3]);
+        // Lib was disabled, so it isn't a candidate and comes along with Cryptid
+        assert_eq!(bisect_step(&mods, &candidates, &[4]), vec![0, 1, 4, 5]);
+    }
+}
@@ -0,0 +1,687 @@
+//! Reading and writing `.jkr` files, the game's save format.
+//!
+//! A `.jkr` file is a Lua table literal (`return {["key"]=value,...}`, as written by the game's
+//! `STR_PACK`) compressed with raw deflate.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use flate2::Compression;
+use flate2::read::{DeflateDecoder, ZlibDecoder};
+use flate2::write::DeflateEncoder;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Key {
+    Number(f64),
+    String(String),
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Number(n) => write!(f, "{n}"),
+            Key::String(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Table(Vec<(Key, Value)>),
+}
+
+impl Value {
+    /// Looks up a string key in a table.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Table(entries) => entries
+                .iter()
+                .find(|(k, _)| matches!(k, Key::String(s) if s == key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        match self {
+            Value::Table(entries) => entries
+                .iter_mut()
+                .find(|(k, _)| matches!(k, Key::String(s) if s == key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Follows a path of string keys through nested tables.
+    pub fn get_path(&self, path: &[&str]) -> Option<&Value> {
+        path.iter().try_fold(self, |v, key| v.get(key))
+    }
+
+    /// Follows a path of keys of any kind through nested tables.
+    pub fn at(&self, path: &[Key]) -> Option<&Value> {
+        path.iter().try_fold(self, |v, key| {
+            v.entries().iter().find(|(k, _)| k == key).map(|(_, v)| v)
+        })
+    }
+
+    pub fn at_mut(&mut self, path: &[Key]) -> Option<&mut Value> {
+        path.iter().try_fold(self, |v, key| match v {
+            Value::Table(entries) => entries.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        })
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[(Key, Value)] {
+        match self {
+            Value::Table(entries) => entries,
+            _ => &[],
+        }
+    }
+
+    /// The values stored under numeric keys, in key order, like `ipairs` but tolerant of gaps.
+    pub fn array(&self) -> Vec<&Value> {
+        let mut items: Vec<(f64, &Value)> = self
+            .entries()
+            .iter()
+            .filter_map(|(k, v)| match k {
+                Key::Number(n) => Some((*n, v)),
+                Key::String(_) => None,
+            })
+            .collect();
+        items.sort_by(|a, b| a.0.total_cmp(&b.0));
+        items.into_iter().map(|(_, v)| v).collect()
+    }
+
+    /// Writes the value as a Lua expression, the way the game's `STR_PACK` does.
+    pub fn to_lua(&self) -> String {
+        let mut out = String::new();
+        write_lua(self, &mut out);
+        out
+    }
+
+    /// Converts to JSON. Tables with keys `1..n` become arrays, other tables become objects.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Nil => serde_json::Value::Null,
+            Value::Bool(b) => serde_json::Value::Bool(*b),
+            Value::Number(n) => number_to_json(*n),
+            Value::String(s) => serde_json::Value::String(s.clone()),
+            Value::Table(entries) => {
+                let is_array = !entries.is_empty()
+                    && (1..=entries.len()).all(|i| {
+                        entries
+                            .iter()
+                            .any(|(k, _)| matches!(k, Key::Number(n) if *n == i as f64))
+                    });
+                if is_array {
+                    serde_json::Value::Array(self.array().iter().map(|v| v.to_json()).collect())
+                } else {
+                    serde_json::Value::Object(
+                        entries
+                            .iter()
+                            .map(|(k, v)| (k.to_string(), v.to_json()))
+                            .collect(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+fn number_to_json(n: f64) -> serde_json::Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        serde_json::Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null)
+    }
+}
+
+fn write_number(n: f64, out: &mut String) {
+    if n.is_nan() {
+        out.push_str("0/0");
+    } else if n.is_infinite() {
+        out.push_str(if n > 0.0 { "math.huge" } else { "-math.huge" });
+    } else {
+        out.push_str(&n.to_string());
+    }
+}
+
+/// Quotes a string like Lua's `%q`.
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 || c == '\x7f' => out.push_str(&format!("\\{:03}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_lua(value: &Value, out: &mut String) {
+    match value {
+        Value::Nil => out.push_str("nil"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(*n, out),
+        Value::String(s) => write_string(s, out),
+        Value::Table(entries) => {
+            out.push('{');
+            for (k, v) in entries {
+                out.push('[');
+                match k {
+                    Key::Number(n) => write_number(*n, out),
+                    Key::String(s) => write_string(s, out),
+                }
+                out.push_str("]=");
+                write_lua(v, out);
+                out.push(',');
+            }
+            out.push('}');
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum JkrError {
+    Io(io::Error),
+    /// The file couldn't be inflated, so it's likely not a `.jkr` file.
+    Decompress(io::Error),
+    Parse {
+        offset: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for JkrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JkrError::Io(e) => write!(f, "{e}"),
+            JkrError::Decompress(e) => write!(f, "failed to decompress: {e}"),
+            JkrError::Parse { offset, message } => {
+                write!(f, "parse error at byte {offset}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for JkrError {}
+
+impl From<io::Error> for JkrError {
+    fn from(e: io::Error) -> Self {
+        JkrError::Io(e)
+    }
+}
+
+/// Inflates the contents of a `.jkr` file into Lua source.
+///
+/// Besides raw deflate this accepts zlib streams and plain text, which is what you get when a
+/// save was edited by hand.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, JkrError> {
+    if data.trim_ascii_start().starts_with(b"return") {
+        return Ok(data.to_vec());
+    }
+    let mut out = vec![];
+    match DeflateDecoder::new(data).read_to_end(&mut out) {
+        Ok(_) => Ok(out),
+        Err(e) => {
+            out.clear();
+            ZlibDecoder::new(data)
+                .read_to_end(&mut out)
+                .map_err(|_| JkrError::Decompress(e))?;
+            Ok(out)
+        }
+    }
+}
+
+pub fn decode(data: &[u8]) -> Result<Value, JkrError> {
+    parse(&decompress(data)?)
+}
+
+pub fn read(path: &Path) -> Result<Value, JkrError> {
+    decode(&std::fs::read(path)?)
+}
+
+/// Serializes and compresses a value the way the game writes `.jkr` files.
+pub fn encode(value: &Value) -> io::Result<Vec<u8>> {
+    let lua = format!("return {}", value.to_lua());
+    let mut encoder = DeflateEncoder::new(vec![], Compression::new(1));
+    encoder.write_all(lua.as_bytes())?;
+    encoder.finish()
+}
+
+/// Serializes a value without compressing it, which is how Steamodded writes mod configs.
+pub fn encode_text(value: &Value) -> Vec<u8> {
+    format!("return {}", value.to_lua()).into_bytes()
+}
+
+pub fn write(path: &Path, value: &Value) -> io::Result<()> {
+    std::fs::write(path, encode(value)?)
+}
+
+/// Parses Lua source of the form `return <expression>`.
+pub fn parse(source: &[u8]) -> Result<Value, JkrError> {
+    let mut parser = Parser {
+        src: source,
+        pos: 0,
+    };
+    parser.skip_whitespace();
+    if parser.src[parser.pos..].starts_with(b"return") {
+        parser.pos += b"return".len();
+    }
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.src.len() {
+        return Err(parser.error("unexpected data after the value"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> JkrError {
+        JkrError::Parse {
+            offset: self.pos,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_whitespace() {
+                self.pos += 1;
+            } else if self.src[self.pos..].starts_with(b"--") {
+                self.pos += 2;
+                match self.long_bracket() {
+                    Some(level) => {
+                        // an unterminated long comment runs to the end
+                        if self.long_string(level).is_err() {
+                            self.pos = self.src.len();
+                        }
+                    }
+                    None => {
+                        while self.peek().is_some_and(|c| c != b'\n') {
+                            self.pos += 1;
+                        }
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), JkrError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c as char)))
+        }
+    }
+
+    /// The level of a long bracket like `[[` or `[==[` at the current position.
+    fn long_bracket(&self) -> Option<usize> {
+        let rest = self.src[self.pos..].strip_prefix(b"[")?;
+        let level = rest.iter().take_while(|c| **c == b'=').count();
+        (rest.get(level) == Some(&b'[')).then_some(level)
+    }
+
+    /// Reads a long string or comment starting at its opening bracket of the given level.
+    fn long_string(&mut self, level: usize) -> Result<String, JkrError> {
+        self.pos += level + 2;
+        // a newline straight after the opening bracket isn't part of the string
+        if self.src[self.pos..].starts_with(b"\r\n") {
+            self.pos += 2;
+        } else if self.peek() == Some(b'\n') {
+            self.pos += 1;
+        }
+        let close = [b"]".as_slice(), &vec![b'='; level], b"]"].concat();
+        let Some(len) = self.src[self.pos..]
+            .windows(close.len())
+            .position(|w| w == close)
+        else {
+            return Err(self.error("unterminated long string"));
+        };
+        let text = String::from_utf8_lossy(&self.src[self.pos..self.pos + len]).to_string();
+        self.pos += len + close.len();
+        Ok(text)
+    }
+
+    /// Joins `first` with what follows any `..`, for strings split over several lines.
+    fn concatenation(&mut self, first: String) -> Result<Value, JkrError> {
+        let before = self.pos;
+        self.skip_whitespace();
+        if !self.src[self.pos..].starts_with(b"..") || self.src[self.pos..].starts_with(b"...") {
+            self.pos = before;
+            return Ok(Value::String(first));
+        }
+        self.pos += 2;
+        // `..` is right associative, so the rest of the chain comes back as one value
+        match self.value()? {
+            Value::String(rest) => Ok(Value::String(first + &rest)),
+            Value::Number(n) => Ok(Value::String(format!("{first}{n}"))),
+            _ => Err(self.error("only strings and numbers can be concatenated")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, JkrError> {
+        self.skip_whitespace();
+        if let Some(level) = self.long_bracket() {
+            let string = self.long_string(level)?;
+            return self.concatenation(string);
+        }
+        match self.peek() {
+            Some(b'{') => self.table(),
+            Some(b'"' | b'\'') => {
+                let string = self.string()?;
+                self.concatenation(string)
+            }
+            Some(c) if c == b'-' || c == b'.' || c.is_ascii_digit() => self.number(),
+            Some(c) if c.is_ascii_alphabetic() || c == b'_' => {
+                let name = self.identifier();
+                Ok(match name.as_str() {
+                    "true" => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    "math.huge" => Value::Number(f64::INFINITY),
+                    // anything else would be a global, which is nil when the game loads it
+                    _ => Value::Nil,
+                })
+            }
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of data")),
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'.')
+        {
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.src[start..self.pos]).to_string()
+    }
+
+    fn number(&mut self) -> Result<Value, JkrError> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+            self.skip_whitespace();
+            if self.src[self.pos..].starts_with(b"math.huge") {
+                self.pos += b"math.huge".len();
+                return Ok(Value::Number(f64::NEG_INFINITY));
+            }
+        }
+        while self.peek().is_some_and(|c| {
+            c.is_ascii_alphanumeric() || c == b'.' || c == b'+' || c == b'-' || c == b'/'
+        }) {
+            // only allow a sign straight after an exponent
+            if matches!(self.peek(), Some(b'+' | b'-'))
+                && !matches!(self.src[self.pos - 1], b'e' | b'E')
+            {
+                break;
+            }
+            self.pos += 1;
+        }
+        let text = String::from_utf8_lossy(&self.src[start..self.pos]).replace(' ', "");
+        match text.as_str() {
+            "0/0" | "-0/0" => return Ok(Value::Number(f64::NAN)),
+            "inf" => return Ok(Value::Number(f64::INFINITY)),
+            "-inf" => return Ok(Value::Number(f64::NEG_INFINITY)),
+            _ => {}
+        }
+        let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+            Some(hex) => i64::from_str_radix(hex, 16).ok().map(|n| n as f64),
+            None => text.parse::<f64>().ok(),
+        };
+        parsed.map(Value::Number).ok_or_else(|| JkrError::Parse {
+            offset: start,
+            message: format!("invalid number '{text}'"),
+        })
+    }
+
+    fn string(&mut self) -> Result<String, JkrError> {
+        let quote = self.src[self.pos];
+        self.pos += 1;
+        let mut bytes = vec![];
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += 1;
+            if c == quote {
+                break;
+            }
+            if c != b'\\' {
+                bytes.push(c);
+                continue;
+            }
+            let Some(escaped) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += 1;
+            match escaped {
+                b'n' | b'\n' => bytes.push(b'\n'),
+                b'r' => bytes.push(b'\r'),
+                b't' => bytes.push(b'\t'),
+                b'a' => bytes.push(0x07),
+                b'b' => bytes.push(0x08),
+                b'f' => bytes.push(0x0c),
+                b'v' => bytes.push(0x0b),
+                b'0'..=b'9' => {
+                    let start = self.pos - 1;
+                    while self.pos - start < 3 && self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        self.pos += 1;
+                    }
+                    let code: u32 = std::str::from_utf8(&self.src[start..self.pos])
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    let byte = u8::try_from(code).map_err(|_| self.error("escape too large"))?;
+                    bytes.push(byte);
+                }
+                other => bytes.push(other),
+            }
+        }
+        Ok(String::from_utf8_lossy(&bytes).to_string())
+    }
+
+    fn table(&mut self) -> Result<Value, JkrError> {
+        self.expect(b'{')?;
+        let mut entries = vec![];
+        let mut next_index = 1.0;
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                break;
+            }
+
+            let key = if self.peek() == Some(b'[') && self.long_bracket().is_none() {
+                self.pos += 1;
+                let key = match self.value()? {
+                    Value::String(s) => Key::String(s),
+                    Value::Number(n) => Key::Number(n),
+                    _ => return Err(self.error("table keys must be strings or numbers")),
+                };
+                self.expect(b']')?;
+                self.expect(b'=')?;
+                Some(key)
+            } else if self
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == b'_')
+            {
+                // `name = value`, unless it's a bare value like `true`
+                let start = self.pos;
+                let name = self.identifier();
+                self.skip_whitespace();
+                if self.peek() == Some(b'=') && !name.contains('.') {
+                    self.pos += 1;
+                    Some(Key::String(name))
+                } else {
+                    self.pos = start;
+                    None
+                }
+            } else {
+                None
+            };
+
+            let value = self.value()?;
+            let key = key.unwrap_or_else(|| {
+                let key = Key::Number(next_index);
+                next_index += 1.0;
+                key
+            });
+            entries.push((key, value));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',' | b';') => self.pos += 1,
+                Some(b'}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+        Ok(Value::Table(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const SOURCE: &str = r#"return {["GAME"]={["round"]=3,["dollars"]=-2.5,["seed"]="ABC\"D\\",["won"]=false,},[1]="one",[2]={},["note"]="line\
+two\000",}"#;
+
+    #[test]
+    fn test_parse() {
+        let value = parse(SOURCE.as_bytes()).unwrap();
+        assert_eq!(
+            value.get_path(&["GAME", "round"]).and_then(|v| v.as_f64()),
+            Some(3.0)
+        );
+        assert_eq!(
+            value
+                .get_path(&["GAME", "dollars"])
+                .and_then(|v| v.as_f64()),
+            Some(-2.5)
+        );
+        assert_eq!(
+            value.get_path(&["GAME", "seed"]).and_then(|v| v.as_str()),
+            Some("ABC\"D\\")
+        );
+        assert_eq!(
+            value.get("note").and_then(|v| v.as_str()),
+            Some("line\ntwo\0")
+        );
+        assert_eq!(value.array().len(), 2);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = parse(SOURCE.as_bytes()).unwrap();
+        let encoded = encode(&value).unwrap();
+        assert_eq!(decode(&encoded).unwrap(), value);
+        assert_eq!(parse(value.to_lua().as_bytes()).unwrap(), value);
+    }
+
+    #[test]
+    fn test_plain_lua_syntax() {
+        let value = parse(b"return { a = 1, 'x', \"y\"; b = { true, nil } }").unwrap();
+        assert_eq!(value.get("a"), Some(&Value::Number(1.0)));
+        assert_eq!(
+            value.array(),
+            vec![&Value::String("x".into()), &Value::String("y".into())]
+        );
+        assert_eq!(value.get("b").unwrap().array().len(), 2);
+    }
+
+    #[test]
+    fn test_long_strings_and_comments() {
+        let value = parse(
+            b"--[[ a long\ncomment ]] return {\n\
+              --[==[ another ]] one ]==]\n\
+              a = [[\nfirst\nsecond]], [=[x]]y]=],\n\
+              b = 'one ' .. \"two \" .. [[three]] .. 4,\n\
+              }",
+        )
+        .unwrap();
+        assert_eq!(value.get("a"), Some(&Value::String("first\nsecond".into())));
+        assert_eq!(value.array(), vec![&Value::String("x]]y".into())]);
+        assert_eq!(
+            value.get("b"),
+            Some(&Value::String("one two three4".into()))
+        );
+        assert!(matches!(
+            parse(b"return { [==[ open ]] }"),
+            Err(JkrError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let value = parse(SOURCE.as_bytes()).unwrap();
+        let json = value.to_json();
+        assert_eq!(json["GAME"]["round"], serde_json::json!(3));
+        assert_eq!(json["GAME"]["won"], serde_json::json!(false));
+        assert_eq!(json["1"], serde_json::json!("one"));
+
+        let list = parse(b"return {[1]=\"a\",[2]=\"b\"}").unwrap();
+        assert_eq!(list.to_json(), serde_json::json!(["a", "b"]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse(b"return {[\"a\"]=}"),
+            Err(JkrError::Parse { .. })
+        ));
+        assert!(matches!(
+            decode(&[0xff, 0x00, 0x12]),
+            Err(JkrError::Decompress(_))
+        ));
+    }
+}
@@ -0,0 +1,253 @@
+//! Launching Balatro without going through the Steam client.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+use serde::Deserialize;
+
+use crate::steam::{BALATRO_APP_ID, LOVELY_DLL_OVERRIDE};
+use crate::{get_balatro_dir, launch_balatro, locate_steam_appdata};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum LauncherKind {
+    /// `steam -applaunch`, the default.
+    #[default]
+    Steam,
+    /// Runs `Balatro.exe` through a Proton install.
+    Proton,
+    /// Runs `Balatro.exe` through a plain Wine binary.
+    Wine,
+    /// Runs the extracted game with a native LÖVE binary.
+    Love,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LauncherConfig {
+    pub kind: LauncherKind,
+    /// Path to the `proton` script. Found in `steamapps/common` when left empty.
+    pub proton_path: PathBuf,
+    /// Path to the `wine` binary. Uses `wine` from `PATH` when left empty.
+    pub wine_path: PathBuf,
+    /// Wine prefix to use. Defaults to the prefix Steam made for Balatro, so saves are shared.
+    pub wine_prefix: PathBuf,
+    /// Path to the `love` binary. Uses `love` from `PATH` when left empty.
+    pub love_path: PathBuf,
+    /// Folder with the game's extracted source, for the LÖVE launcher.
+    pub love_game_dir: PathBuf,
+}
+
+fn steam_root() -> io::Result<PathBuf> {
+    locate_steam_appdata()
+        .map(|s| s.data_dir)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "failed to locate steam"))
+}
+
+/// The folder Steam uses as `STEAM_COMPAT_DATA_PATH` for Balatro. Its `pfx` subfolder is the
+/// wine prefix.
+pub fn compat_data_dir(steam_root: &Path) -> PathBuf {
+    let mut path = steam_root.to_path_buf();
+    path.extend(["steamapps", "compatdata", BALATRO_APP_ID]);
+    path
+}
+
+/// Picks a Proton install from `steamapps/common`, preferring Proton Experimental and then the
+/// newest numbered version.
+pub fn find_proton(steam_root: &Path) -> Option<PathBuf> {
+    let common = steam_root.join("steamapps").join("common");
+    let mut installs: Vec<(String, PathBuf)> = std::fs::read_dir(common)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_str()?.to_string();
+            let script = e.path().join("proton");
+            (name.starts_with("Proton") && script.is_file()).then_some((name, script))
+        })
+        .collect();
+
+    if let Some((_, script)) = installs
+        .iter()
+        .find(|(name, _)| name.contains("Experimental"))
+    {
+        return Some(script.clone());
+    }
+    installs.sort_by_key(|(name, _)| {
+        name.trim_start_matches("Proton")
+            .trim()
+            .split('.')
+            .map(|part| part.parse::<u32>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    });
+    installs.pop().map(|(_, script)| script)
+}
+
+fn or_default(path: &Path, default: &str) -> PathBuf {
+    if path.as_os_str().is_empty() {
+        PathBuf::from(default)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Builds the command that runs Balatro with the given launcher, without spawning it.
+/// Returns `None` for [`LauncherKind::Steam`], which can only be spawned through Steam.
+pub fn build_command(
+    config: &LauncherConfig,
+    steam_root: &Path,
+    game_dir: &Path,
+    disable_console: bool,
+) -> io::Result<Option<Command>> {
+    let exe = game_dir.join("Balatro.exe");
+    let mut command = match config.kind {
+        LauncherKind::Steam => return Ok(None),
+        LauncherKind::Proton => {
+            let proton = if config.proton_path.as_os_str().is_empty() {
+                find_proton(steam_root).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        "no Proton install found in steamapps/common",
+                    )
+                })?
+            } else {
+                config.proton_path.clone()
+            };
+            let compat_data = compat_data_dir(steam_root);
+            let mut command = Command::new(proton);
+            command
+                .arg("run")
+                .arg(&exe)
+                .env("STEAM_COMPAT_DATA_PATH", &compat_data)
+                .env("STEAM_COMPAT_CLIENT_INSTALL_PATH", steam_root)
+                .env("WINEPREFIX", compat_data.join("pfx"))
+                .env("SteamAppId", BALATRO_APP_ID)
+                .env("SteamGameId", BALATRO_APP_ID);
+            command
+        }
+        LauncherKind::Wine => {
+            let prefix = if config.wine_prefix.as_os_str().is_empty() {
+                compat_data_dir(steam_root).join("pfx")
+            } else {
+                config.wine_prefix.clone()
+            };
+            let mut command = Command::new(or_default(&config.wine_path, "wine"));
+            command.arg(&exe).env("WINEPREFIX", prefix);
+            command
+        }
+        LauncherKind::Love => {
+            let mut command = Command::new(or_default(&config.love_path, "love"));
+            command
+                .arg(&config.love_game_dir)
+                .current_dir(&config.love_game_dir);
+            return Ok(Some(command));
+        }
+    };
+
+    command
+        .env("WINEDLLOVERRIDES", LOVELY_DLL_OVERRIDE)
+        .current_dir(game_dir);
+    if disable_console {
+        command.arg("--disable-console");
+    }
+    Ok(Some(command))
+}
+
+/// Launches Balatro with the configured launcher and returns the spawned process.
+///
+/// With the Steam launcher this is the `steam` client process rather than the game itself.
+pub fn launch(config: &LauncherConfig, disable_console: bool) -> io::Result<Child> {
+    if config.kind == LauncherKind::Steam {
+        return launch_balatro(disable_console);
+    }
+    if config.kind == LauncherKind::Love && config.love_game_dir.as_os_str().is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no love_game_dir is configured for the LÖVE launcher",
+        ));
+    }
+    let mut command = build_command(config, &steam_root()?, &get_balatro_dir(), disable_console)?
+        .expect("only the steam launcher has no command");
+    command.spawn()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn env_of(command: &Command, key: &str) -> Option<String> {
+        command
+            .get_envs()
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v.map(|v| v.to_string_lossy().to_string()))
+    }
+
+    #[test]
+    fn test_find_proton() {
+        let root = tempfile::tempdir().unwrap();
+        for name in ["Proton 8.0", "Proton 9.0", "Proton 10.0", "Balatro"] {
+            let dir = root.path().join("steamapps/common").join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            if name != "Balatro" {
+                std::fs::write(dir.join("proton"), "").unwrap();
+            }
+        }
+        assert_eq!(
+            find_proton(root.path()).unwrap(),
+            root.path().join("steamapps/common/Proton 10.0/proton")
+        );
+    }
+
+    #[test]
+    fn test_proton_command_env() {
+        let config = LauncherConfig {
+            kind: LauncherKind::Proton,
+            proton_path: PathBuf::from("/opt/proton/proton"),
+            ..Default::default()
+        };
+        let root = Path::new("/steam");
+        let command = build_command(
+            &config,
+            root,
+            Path::new("/steam/steamapps/common/Balatro"),
+            true,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(command.get_program(), "/opt/proton/proton");
+        assert_eq!(
+            env_of(&command, "STEAM_COMPAT_DATA_PATH").unwrap(),
+            "/steam/steamapps/compatdata/2379780"
+        );
+        assert_eq!(env_of(&command, "WINEDLLOVERRIDES").unwrap(), "version=n,b");
+        assert_eq!(
+            command
+                .get_args()
+                .map(|a| a.to_string_lossy().to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "run",
+                "/steam/steamapps/common/Balatro/Balatro.exe",
+                "--disable-console"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wine_command_uses_steam_prefix() {
+        let config = LauncherConfig {
+            kind: LauncherKind::Wine,
+            ..Default::default()
+        };
+        let command = build_command(&config, Path::new("/steam"), Path::new("/game"), false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(command.get_program(), "wine");
+        assert_eq!(
+            env_of(&command, "WINEPREFIX").unwrap(),
+            "/steam/steamapps/compatdata/2379780/pfx"
+        );
+    }
+}
@@ -1,4 +1,25 @@
+pub mod devlink;
+pub mod game;
+pub mod gamesource;
+pub mod jkr;
+pub mod launcher;
+pub mod localization;
+pub mod lovely;
+pub mod metadata;
+pub mod modindex;
+pub mod modconfig;
 pub mod motd;
+pub mod process;
+pub mod profile;
+pub mod release;
+pub mod run;
+pub mod saves;
+pub mod scaffold;
+pub mod settings;
+pub mod steam;
+pub mod textures;
+pub mod vdf;
+pub mod watch;
 
 use git2::build::CheckoutBuilder;
 use git2::{FetchOptions, RemoteCallbacks, Repository};
@@ -10,9 +31,10 @@ use std::error::Error;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::io::{Read, Seek, SeekFrom, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::process::{Child, Command};
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{fs, thread};
 use tempfile::NamedTempFile;
 
@@ -52,6 +74,23 @@ pub fn open(path: &str) {
     }
 }
 
+/// A free `<file>.<unix secs>.bak` path next to `path`, with a `-n` suffix when a backup was
+/// already made in the same second.
+pub fn backup_path(path: &Path) -> PathBuf {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut backup = path.with_file_name(format!("{file_name}.{secs}.bak"));
+    let mut n = 1;
+    while backup.exists() {
+        n += 1;
+        backup = path.with_file_name(format!("{file_name}.{secs}-{n}.bak"));
+    }
+    backup
+}
+
 pub fn locate_steam_appdata() -> Option<AppDirs> {
     AppDirs::new(Some("Steam"), false)
 }
@@ -221,7 +260,7 @@ pub async fn install_lovely() {
     #[cfg(target_os = "linux")]
     {
         info!(
-            "Successfully Installed Lovely! You may need to set the launch options in Steam to \"WINEDLLOVERRIDES=\"version=n,b\" %command%\""
+            "Successfully Installed Lovely! You may need to set the launch options in Steam to \"WINEDLLOVERRIDES=\"version=n,b\" %command%\", which \"Set Steam launch options for Lovely\" can do for you."
         );
     }
 }
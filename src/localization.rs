@@ -0,0 +1,360 @@
+//! Checking a mod's `localization/<lang>.lua` files against each other and against the keys its
+//! code uses.
+//!
+//! Each file returns nested tables (`descriptions`, `misc`) of strings. They are flattened into
+//! dotted paths like `descriptions.Joker.j_abc_example.text`, with a list of strings becoming a
+//! single entry, so languages can be compared key by key.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::jkr::{self, Key, Value};
+use crate::metadata::find_metadata_file;
+use crate::release::release_files;
+
+lazy_static! {
+    /// `{C:attention}`, `{X:mult,C:white}`, `{}` and the like.
+    static ref FORMATTING_TAG: Regex = Regex::new(r"\{[^{}]*\}").unwrap();
+}
+
+/// The language most mods are written in, and so the default to compare against.
+pub const DEFAULT_BASE: &str = "en-us";
+
+pub fn localization_dir(mod_dir: &Path) -> PathBuf {
+    mod_dir.join("localization")
+}
+
+/// Flattened entries, from dotted path to the strings there.
+pub type Entries = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Language {
+    /// The file name without `.lua`, like `en-us`.
+    pub name: String,
+    pub path: PathBuf,
+    /// The entries, or why the file couldn't be read.
+    pub entries: Result<Entries, String>,
+}
+
+fn is_string_list(entries: &[(Key, Value)]) -> bool {
+    !entries.is_empty()
+        && entries
+            .iter()
+            .all(|(k, v)| matches!(k, Key::Number(_)) && matches!(v, Value::String(_)))
+}
+
+fn flatten(value: &Value, path: &str, out: &mut Entries) {
+    match value {
+        Value::Table(entries) if is_string_list(entries) => {
+            let lines = entries
+                .iter()
+                .filter_map(|(_, v)| match v {
+                    Value::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect();
+            out.insert(path.to_string(), lines);
+        }
+        Value::Table(entries) => {
+            for (key, value) in entries {
+                let path = if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{path}.{key}")
+                };
+                flatten(value, &path, out);
+            }
+        }
+        Value::String(s) => {
+            out.insert(path.to_string(), vec![s.clone()]);
+        }
+        Value::Number(n) => {
+            out.insert(path.to_string(), vec![n.to_string()]);
+        }
+        Value::Bool(b) => {
+            out.insert(path.to_string(), vec![b.to_string()]);
+        }
+        Value::Nil => {}
+    }
+}
+
+/// Parses the source of a localization file into its entries.
+///
+/// The file has to return a plain table literal. Comments, long strings and `..` between strings
+/// are fine, but locals, function calls and other code are not evaluated.
+pub fn parse_entries(source: &[u8]) -> Result<Entries, String> {
+    let value = jkr::parse(source).map_err(|e| {
+        format!("{e} (only files that return a plain table literal can be checked, without locals or function calls)")
+    })?;
+    let mut entries = Entries::new();
+    flatten(&value, "", &mut entries);
+    Ok(entries)
+}
+
+/// The mod's localization files, sorted by language.
+pub fn load_languages(mod_dir: &Path) -> Vec<Language> {
+    let Ok(dir) = std::fs::read_dir(localization_dir(mod_dir)) else {
+        return vec![];
+    };
+    let mut languages: Vec<Language> = dir
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "lua"))
+        .map(|path| Language {
+            name: path.file_stem().unwrap().to_string_lossy().to_string(),
+            entries: std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|source| parse_entries(&source)),
+            path,
+        })
+        .collect();
+    languages.sort_by(|a, b| a.name.cmp(&b.name));
+    languages
+}
+
+/// The formatting tags in `lines`, sorted so only which tags are used matters, not where.
+pub fn formatting_tags(lines: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = lines
+        .iter()
+        .flat_map(|l| FORMATTING_TAG.find_iter(l).map(|m| m.as_str().to_string()))
+        .collect();
+    tags.sort();
+    tags
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagMismatch {
+    pub key: String,
+    pub base: Vec<String>,
+    pub translated: Vec<String>,
+}
+
+/// How one language differs from the base language.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comparison {
+    /// In the base language but not this one.
+    pub missing: Vec<String>,
+    /// In this language but not the base one.
+    pub extra: Vec<String>,
+    pub mismatched_tags: Vec<TagMismatch>,
+}
+
+impl Comparison {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.mismatched_tags.is_empty()
+    }
+}
+
+pub fn compare(base: &Entries, translated: &Entries) -> Comparison {
+    let mut comparison = Comparison::default();
+    for (key, lines) in base {
+        match translated.get(key) {
+            None => comparison.missing.push(key.clone()),
+            Some(translated_lines) => {
+                let (base_tags, tags) = (formatting_tags(lines), formatting_tags(translated_lines));
+                if base_tags != tags {
+                    comparison.mismatched_tags.push(TagMismatch {
+                        key: key.clone(),
+                        base: base_tags,
+                        translated: tags,
+                    });
+                }
+            }
+        }
+    }
+    comparison.extra = translated
+        .keys()
+        .filter(|k| !base.contains_key(*k))
+        .cloned()
+        .collect();
+    comparison
+}
+
+/// The prefix in the metadata of the mod in `dir`.
+pub fn mod_prefix(dir: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(find_metadata_file(dir)?).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&text).ok()?;
+    Some(json.get("prefix")?.as_str()?.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyUse {
+    pub key: String,
+    /// Relative to the mod.
+    pub file: PathBuf,
+    /// From 1.
+    pub line: usize,
+}
+
+/// Full keys like `key = 'j_<prefix>_name'` in the mod's Lua code, outside its localization.
+pub fn referenced_keys(mod_dir: &Path, prefix: &str) -> Vec<KeyUse> {
+    let pattern = Regex::new(&format!(
+        r#"\bkey\s*=\s*["']([a-z]+_{}_\w+)["']"#,
+        regex::escape(prefix)
+    ))
+    .unwrap();
+    let mut uses = vec![];
+    for file in release_files(mod_dir) {
+        if file.extension().is_none_or(|e| e != "lua") || file.starts_with("localization") {
+            continue;
+        }
+        let Ok(source) = std::fs::read_to_string(mod_dir.join(&file)) else {
+            continue;
+        };
+        for (i, line) in source.lines().enumerate() {
+            uses.extend(pattern.captures_iter(line).map(|c| KeyUse {
+                key: c[1].to_string(),
+                file: file.clone(),
+                line: i + 1,
+            }));
+        }
+    }
+    uses
+}
+
+/// The keys used in the code that no language has an entry for.
+pub fn unlocalized_keys(mod_dir: &Path, prefix: &str, languages: &[Language]) -> Vec<KeyUse> {
+    let localized = |key: &str| {
+        languages.iter().any(|l| {
+            l.entries.as_ref().is_ok_and(|entries| {
+                entries
+                    .keys()
+                    .any(|path| path.split('.').any(|part| part == key))
+            })
+        })
+    };
+    referenced_keys(mod_dir, prefix)
+        .into_iter()
+        .filter(|u| !localized(&u.key))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    const EN: &str = r#"return {
+    descriptions = {
+        Joker = {
+            j_abc_speed = {
+                name = "Speed",
+                text = { "{C:mult}+#1#{} Mult", "when {C:attention}played{}" },
+            },
+            j_abc_slow = { name = "Slow", text = { "Does nothing" } },
+        },
+    },
+    misc = { dictionary = { k_abc_fast = "Fast!" } },
+}"#;
+
+    const FR: &str = r#"return {
+    descriptions = {
+        Joker = {
+            j_abc_speed = {
+                name = "Vitesse",
+                text = { "{C:mult}+#1#{} Multi", "quand jouée" },
+            },
+        },
+    },
+    misc = { dictionary = { k_abc_fast = "Vite !", k_abc_old = "Vieux" } },
+}"#;
+
+    #[test]
+    fn test_parse_entries() {
+        let entries = parse_entries(EN.as_bytes()).unwrap();
+        assert_eq!(
+            entries.keys().collect::<Vec<_>>(),
+            vec![
+                "descriptions.Joker.j_abc_slow.name",
+                "descriptions.Joker.j_abc_slow.text",
+                "descriptions.Joker.j_abc_speed.name",
+                "descriptions.Joker.j_abc_speed.text",
+                "misc.dictionary.k_abc_fast",
+            ]
+        );
+        assert_eq!(
+            formatting_tags(&entries["descriptions.Joker.j_abc_speed.text"]),
+            vec!["{C:attention}", "{C:mult}", "{}", "{}"]
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let en = parse_entries(EN.as_bytes()).unwrap();
+        let fr = parse_entries(FR.as_bytes()).unwrap();
+        let comparison = compare(&en, &fr);
+        assert_eq!(
+            comparison.missing,
+            vec![
+                "descriptions.Joker.j_abc_slow.name",
+                "descriptions.Joker.j_abc_slow.text",
+            ]
+        );
+        assert_eq!(comparison.extra, vec!["misc.dictionary.k_abc_old"]);
+        assert_eq!(
+            comparison.mismatched_tags,
+            vec![TagMismatch {
+                key: "descriptions.Joker.j_abc_speed.text".to_string(),
+                base: vec!["{C:attention}", "{C:mult}", "{}", "{}"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                translated: vec!["{C:mult}", "{}"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            }]
+        );
+        assert!(compare(&en, &en).is_complete());
+    }
+
+    #[test]
+    fn test_parse_lua_syntax() {
+        let source = "--[[ generated, do not edit ]]\n\
+            return { misc = { dictionary = {\n\
+                k_abc_long = [[Line]] .. ' two',\n\
+                --[==[ k_abc_old = 'Old', ]==]\n\
+            } } }";
+        let entries = parse_entries(source.as_bytes()).unwrap();
+        assert_eq!(
+            entries.into_iter().collect::<Vec<_>>(),
+            vec![(
+                "misc.dictionary.k_abc_long".to_string(),
+                vec!["Line two".to_string()]
+            )]
+        );
+
+        let error = parse_entries(b"local t = {}\nreturn t").unwrap_err();
+        assert!(error.contains("plain table literal"), "{error}");
+    }
+
+    #[test]
+    fn test_unlocalized_keys() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(localization_dir(dir.path())).unwrap();
+        std::fs::write(localization_dir(dir.path()).join("en-us.lua"), EN).unwrap();
+        std::fs::write(
+            dir.path().join("main.lua"),
+            "SMODS.Joker { key = 'j_abc_speed' }\n\
+             local other = { key = \"j_abc_missing\" }\n\
+             local elsewhere = { key = 'j_xyz_theirs' }\n",
+        )
+        .unwrap();
+
+        let languages = load_languages(dir.path());
+        assert_eq!(languages.len(), 1);
+        assert_eq!(languages[0].name, "en-us");
+        assert_eq!(
+            unlocalized_keys(dir.path(), "abc", &languages),
+            vec![KeyUse {
+                key: "j_abc_missing".to_string(),
+                file: PathBuf::from("main.lua"),
+                line: 2,
+            }]
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Working with Lovely, the injector that loads mods into the game.
+
+use std::path::PathBuf;
+
+use crate::get_balatro_appdata_dir;
+
+pub mod collisions;
+pub mod dump;
+pub mod log;
+pub mod patch;
+
+/// Lovely's own folder inside `Mods`, holding its logs and dumps.
+pub fn lovely_dir() -> PathBuf {
+    get_balatro_appdata_dir().join("Mods").join("lovely")
+}
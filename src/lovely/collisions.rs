@@ -0,0 +1,243 @@
+//! Finding Lovely patches of different mods that touch the same lines of the game.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+
+use super::patch::{Patch, PatchKind, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionKind {
+    /// The patches match overlapping lines and at least one replaces them, so whichever is
+    /// applied second may no longer find its pattern.
+    Conflict,
+    /// One patch inserts into the middle of lines the other matches as a whole, which breaks
+    /// the other if it is applied first.
+    Splits,
+    /// Both insert at the same place. Both apply, but the order of what they insert depends on
+    /// which is applied first.
+    SameAnchor,
+}
+
+impl fmt::Display for CollisionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            CollisionKind::Conflict => "conflict",
+            CollisionKind::Splits => "splits a match",
+            CollisionKind::SameAnchor => "order dependent",
+        };
+        write!(f, "{text}")
+    }
+}
+
+/// A patch of an installed mod, with the vanilla lines it matches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModPatch {
+    pub mod_name: String,
+    pub patch: Patch,
+    pub lines: Vec<Range<usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collision {
+    pub kind: CollisionKind,
+    pub target: String,
+    /// The lines involved, from 0.
+    pub lines: Range<usize>,
+    /// The patch Lovely applies first, lower priority first.
+    pub first: ModPatch,
+    pub second: ModPatch,
+}
+
+impl Collision {
+    /// Equal priorities leave the order to the order mods are loaded in.
+    pub fn order_is_fixed(&self) -> bool {
+        self.first.patch.priority != self.second.patch.priority
+    }
+}
+
+/// Where a patch puts its payload, as the line it is inserted before, or the lines it replaces.
+fn insertion(position: Position, lines: &Range<usize>) -> Range<usize> {
+    match position {
+        Position::Before => lines.start..lines.start,
+        Position::At => lines.clone(),
+        _ => lines.end..lines.end,
+    }
+}
+
+fn collide(a: &ModPatch, b: &ModPatch) -> Option<(CollisionKind, Range<usize>)> {
+    for ra in &a.lines {
+        for rb in &b.lines {
+            let overlap = ra.start.max(rb.start)..ra.end.min(rb.end);
+            if (a.patch.position == Position::At || b.patch.position == Position::At)
+                && overlap.start < overlap.end
+            {
+                return Some((CollisionKind::Conflict, overlap));
+            }
+            let (ia, ib) = (
+                insertion(a.patch.position, ra),
+                insertion(b.patch.position, rb),
+            );
+            let inside =
+                |point: usize, lines: &Range<usize>| lines.start < point && point < lines.end;
+            if inside(ia.start, rb) || inside(ib.start, ra) {
+                return Some((
+                    CollisionKind::Splits,
+                    ra.start.min(rb.start)..ra.end.max(rb.end),
+                ));
+            }
+            if ia == ib {
+                return Some((
+                    CollisionKind::SameAnchor,
+                    ra.start.min(rb.start)..ra.end.max(rb.end),
+                ));
+            }
+        }
+    }
+    None
+}
+
+/// Finds collisions between the pattern and regex patches of different mods.
+///
+/// `mods` holds each mod's name and patches, and `game_dir` the extracted vanilla game.
+pub fn find_collisions(mods: &[(String, Vec<Patch>)], game_dir: &Path) -> Vec<Collision> {
+    let mut sources: HashMap<&str, Option<String>> = HashMap::new();
+    let mut by_target: HashMap<&str, Vec<ModPatch>> = HashMap::new();
+    for (mod_name, patches) in mods {
+        for patch in patches {
+            if !patch.targets_vanilla()
+                || !matches!(
+                    patch.kind,
+                    PatchKind::Pattern { .. } | PatchKind::Regex { .. }
+                )
+            {
+                continue;
+            }
+            let source = sources
+                .entry(&patch.target)
+                .or_insert_with(|| std::fs::read_to_string(game_dir.join(&patch.target)).ok());
+            let Some(source) = source else {
+                continue;
+            };
+            let Ok(mut lines) = patch.matches(source) else {
+                continue;
+            };
+            if let Some(times) = patch.times {
+                lines.truncate(times);
+            }
+            if lines.is_empty() {
+                continue;
+            }
+            by_target.entry(&patch.target).or_default().push(ModPatch {
+                mod_name: mod_name.clone(),
+                patch: patch.clone(),
+                lines,
+            });
+        }
+    }
+
+    let mut collisions = vec![];
+    for (target, patches) in by_target {
+        for (i, a) in patches.iter().enumerate() {
+            for b in patches
+                .iter()
+                .skip(i + 1)
+                .filter(|b| b.mod_name != a.mod_name)
+            {
+                let Some((kind, lines)) = collide(a, b) else {
+                    continue;
+                };
+                let (first, second) = if b.patch.priority < a.patch.priority {
+                    (b, a)
+                } else {
+                    (a, b)
+                };
+                collisions.push(Collision {
+                    kind,
+                    target: target.to_string(),
+                    lines,
+                    first: first.clone(),
+                    second: second.clone(),
+                });
+            }
+        }
+    }
+    let key = |c: &Collision| {
+        (
+            c.target.clone(),
+            c.lines.start,
+            c.first.mod_name.clone(),
+            c.second.mod_name.clone(),
+        )
+    };
+    collisions.sort_by_key(key);
+    collisions
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::lovely::patch::read_patch_file;
+
+    const GAME: &str = "function Game:update(dt)\n    self.SPEEDFACTOR = 1\n    self.TIMERS = {}\n    self.E_MANAGER:update(dt)\nend\n";
+
+    fn patches(
+        dir: &Path,
+        name: &str,
+        priority: i64,
+        entries: &[(&str, &str)],
+    ) -> (String, Vec<Patch>) {
+        let mut toml = format!("[manifest]\nversion = \"1.0.0\"\npriority = {priority}\n");
+        for (pattern, position) in entries {
+            toml.push_str(&format!(
+                "[[patches]]\n[patches.pattern]\ntarget = \"game.lua\"\npattern = '''{pattern}'''\nposition = \"{position}\"\npayload = \"-- {name}\"\n"
+            ));
+        }
+        let path = dir.join(format!("{name}.toml"));
+        std::fs::write(&path, toml).unwrap();
+        (name.to_string(), read_patch_file(&path).unwrap())
+    }
+
+    #[test]
+    fn test_find_collisions() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("game.lua"), GAME).unwrap();
+        let mods = vec![
+            patches(dir.path(), "Speed", 0, &[("self.SPEEDFACTOR = 1", "at")]),
+            patches(
+                dir.path(),
+                "Timers",
+                -1,
+                &[("self.SPEEDFACTOR = 1\nself.TIMERS = {}", "after")],
+            ),
+            patches(dir.path(), "Events", 0, &[("self.TIMERS = {}", "before")]),
+            patches(
+                dir.path(),
+                "Events2",
+                0,
+                &[("self.E_MANAGER:update(dt)", "before")],
+            ),
+            patches(dir.path(), "Alone", 0, &[("end", "after")]),
+        ];
+
+        let found: Vec<(CollisionKind, String, String, usize)> = find_collisions(&mods, dir.path())
+            .into_iter()
+            .map(|c| (c.kind, c.first.mod_name, c.second.mod_name, c.lines.start))
+            .collect();
+        let expected = [
+            (CollisionKind::Splits, "Timers", "Events", 1),
+            (CollisionKind::SameAnchor, "Timers", "Events2", 1),
+            (CollisionKind::Conflict, "Timers", "Speed", 1),
+        ];
+        assert_eq!(
+            found,
+            expected
+                .map(|(k, a, b, l)| (k, a.to_string(), b.to_string(), l))
+                .to_vec()
+        );
+    }
+}
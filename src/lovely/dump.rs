@@ -0,0 +1,169 @@
+//! Lovely's dump of the patched game files in `Mods/lovely/dump`, compared with vanilla.
+//!
+//! The dump holds only the patched source, so hunks are attributed to mods by finding the
+//! lines they add in the payloads of the installed mods' patches.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use similar::{ChangeTag, TextDiff};
+
+use super::lovely_dir;
+use super::patch::{Patch, PatchKind};
+
+pub fn dump_dir() -> PathBuf {
+    lovely_dir().join("dump")
+}
+
+/// The dumped Lua files, relative to `dir` and sorted.
+pub fn list_dumps(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        for entry in std::fs::read_dir(current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|e| e == "lua")
+                && let Ok(relative) = path.strip_prefix(dir)
+            {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// A hunk of the diff between a vanilla file and its dump.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    /// The `@@ -a,b +c,d @@` line.
+    pub header: String,
+    /// The lines of the hunk, each starting with ` `, `-` or `+`.
+    pub lines: Vec<String>,
+    /// Mods whose patches add the lines this hunk adds, most lines first.
+    pub mods: Vec<String>,
+}
+
+impl Hunk {
+    fn added(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().filter_map(|l| l.strip_prefix('+'))
+    }
+}
+
+/// Lines of a patch's payload that are distinctive enough to recognise in a dump.
+pub fn payload_lines(patch: &Patch, mod_dir: &Path) -> Vec<String> {
+    let text = match &patch.kind {
+        PatchKind::Pattern { payload, .. } | PatchKind::Regex { payload, .. } => payload.clone(),
+        PatchKind::Copy { sources } => sources
+            .iter()
+            .filter_map(|s| std::fs::read_to_string(mod_dir.join(s)).ok())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        PatchKind::Other(_) => String::new(),
+    };
+    text.lines()
+        .map(str::trim)
+        // lines like `end` or `}` are in every payload
+        .filter(|l| l.len() >= 8 && !l.starts_with("--"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Diffs a vanilla file against its dump, attributing each hunk using `payloads`, which holds
+/// each mod's name and the [`payload_lines`] of its patches.
+pub fn diff_hunks(vanilla: &str, dumped: &str, payloads: &[(String, Vec<String>)]) -> Vec<Hunk> {
+    let diff = TextDiff::from_lines(vanilla, dumped);
+    diff.unified_diff()
+        .context_radius(3)
+        .iter_hunks()
+        .map(|hunk| {
+            let lines: Vec<String> = hunk
+                .iter_changes()
+                .map(|change| {
+                    let sign = match change.tag() {
+                        ChangeTag::Equal => ' ',
+                        ChangeTag::Delete => '-',
+                        ChangeTag::Insert => '+',
+                    };
+                    format!("{sign}{}", change.value().trim_end_matches(['\r', '\n']))
+                })
+                .collect();
+            let mut hunk = Hunk {
+                header: hunk.header().to_string(),
+                lines,
+                mods: vec![],
+            };
+            let added: Vec<&str> = hunk.added().map(str::trim).collect();
+            let mut scores: Vec<(usize, &String)> = payloads
+                .iter()
+                .map(|(name, lines)| {
+                    let hits = added
+                        .iter()
+                        .filter(|a| lines.iter().any(|l| l == *a))
+                        .count();
+                    (hits, name)
+                })
+                .filter(|(hits, _)| *hits > 0)
+                .collect();
+            scores.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+            hunk.mods = scores.into_iter().map(|(_, name)| name.clone()).collect();
+            hunk
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_diff_hunks() {
+        let vanilla = (1..=20).map(|i| format!("line {i}\n")).collect::<String>();
+        let dumped = vanilla
+            .replace("line 2\n", "line 2\nprint('from the speed mod')\n")
+            .replace("line 18\n", "line 18 changed by nobody we know\n");
+        let payloads = vec![
+            (
+                "Speed".to_string(),
+                vec!["print('from the speed mod')".to_string()],
+            ),
+            (
+                "Other".to_string(),
+                vec!["something else entirely".to_string()],
+            ),
+        ];
+
+        let hunks = diff_hunks(&vanilla, &dumped, &payloads);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].header, "@@ -1,5 +1,6 @@");
+        assert!(
+            hunks[0]
+                .lines
+                .contains(&"+print('from the speed mod')".to_string())
+        );
+        assert_eq!(hunks[0].mods, vec!["Speed"]);
+        assert!(hunks[1].lines.contains(&"-line 18".to_string()));
+        assert!(hunks[1].mods.is_empty());
+    }
+
+    #[test]
+    fn test_list_dumps() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("functions")).unwrap();
+        std::fs::write(dir.path().join("game.lua"), "").unwrap();
+        std::fs::write(dir.path().join("functions").join("misc_functions.lua"), "").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        assert_eq!(
+            list_dumps(dir.path()).unwrap(),
+            vec![
+                PathBuf::from("functions/misc_functions.lua"),
+                PathBuf::from("game.lua")
+            ]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Finding and tailing the logs Lovely writes to `Mods/lovely/log`.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::lovely_dir;
+
+pub fn log_dir() -> PathBuf {
+    lovely_dir().join("log")
+}
+
+/// Lists the `.log` files in `dir`, newest first.
+pub fn list_logs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut logs: Vec<(SystemTime, PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "log"))
+        .map(|p| {
+            let modified = p
+                .metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            (modified, p)
+        })
+        .collect();
+    // file names are timestamps, so they break ties between files written in the same second
+    logs.sort_by(|a, b| b.cmp(a));
+    logs.into_iter().map(|(_, p)| p).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn from_str(s: &str) -> Option<Self> {
+        match s.trim() {
+            "TRACE" => Some(LogLevel::Trace),
+            "DEBUG" => Some(LogLevel::Debug),
+            "INFO" => Some(LogLevel::Info),
+            "WARN" | "WARNING" => Some(LogLevel::Warn),
+            "ERROR" | "FATAL" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Works out the level of a log line.
+///
+/// Lovely prefixes every line with its own level (`INFO - [G] ...`), but everything the game
+/// prints comes through as `INFO`, so Steamodded's `:: WARN ::` style levels take precedence.
+pub fn level_of(line: &str) -> Option<LogLevel> {
+    if let Some(level) = line.split(" :: ").skip(1).find_map(LogLevel::from_str) {
+        return Some(level);
+    }
+    line.split_once(" - ")
+        .and_then(|(level, _)| LogLevel::from_str(level))
+}
+
+/// Reads a log file incrementally, picking up lines as they are appended.
+#[derive(Debug)]
+pub struct LogTail {
+    pub path: PathBuf,
+    pub lines: Vec<String>,
+    offset: u64,
+    partial: Vec<u8>,
+}
+
+impl LogTail {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lines: vec![],
+            offset: 0,
+            partial: vec![],
+        }
+    }
+
+    /// Reads anything appended since the last call. Returns whether new lines were added.
+    pub fn refresh(&mut self) -> io::Result<bool> {
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            // truncated or replaced, start over
+            self.lines.clear();
+            self.partial.clear();
+            self.offset = 0;
+        }
+        if len == self.offset {
+            return Ok(false);
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = vec![];
+        file.read_to_end(&mut buf)?;
+        self.offset += buf.len() as u64;
+
+        self.partial.extend_from_slice(&buf);
+        let Some(last_newline) = self.partial.iter().rposition(|b| *b == b'\n') else {
+            return Ok(false);
+        };
+        let complete: Vec<u8> = self.partial.drain(..=last_newline).collect();
+        self.lines.extend(
+            String::from_utf8_lossy(&complete)
+                .lines()
+                .map(|l| l.trim_end_matches('\r').to_string()),
+        );
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_level_of() {
+        assert_eq!(level_of("INFO - [♥] Lovely 0.7.1"), Some(LogLevel::Info));
+        assert_eq!(level_of("WARN - [♥] Unknown target"), Some(LogLevel::Warn));
+        assert_eq!(
+            level_of("INFO - [G] 2025-01-01 12:00:00 :: ERROR :: Loader :: failed"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(level_of("  stack traceback:"), None);
+    }
+
+    #[test]
+    fn test_tail_partial_lines() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let mut tail = LogTail::new(file.path().to_path_buf());
+
+        write!(file, "INFO - first\nINFO - sec").unwrap();
+        assert!(tail.refresh().unwrap());
+        assert_eq!(tail.lines, vec!["INFO - first"]);
+
+        writeln!(file, "ond").unwrap();
+        assert!(tail.refresh().unwrap());
+        assert_eq!(tail.lines, vec!["INFO - first", "INFO - second"]);
+        assert!(!tail.refresh().unwrap());
+    }
+}
@@ -0,0 +1,687 @@
+//! Reading a mod's Lovely patches and applying them to the vanilla game source.
+//!
+//! Lovely reads `lovely.toml` and `lovely/*.toml` from each mod. Every `[[patches]]` entry holds
+//! one `pattern`, `regex`, `copy` or `module` table, and the `[manifest]` priority decides the
+//! order mods are applied in, lowest first.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::Deserialize;
+use similar::TextDiff;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    Before,
+    After,
+    At,
+    Prepend,
+    Append,
+}
+
+impl Position {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "before" => Some(Position::Before),
+            "after" => Some(Position::After),
+            "at" => Some(Position::At),
+            "prepend" => Some(Position::Prepend),
+            "append" => Some(Position::Append),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Position::Before => "before",
+            Position::After => "after",
+            Position::At => "at",
+            Position::Prepend => "prepend",
+            Position::Append => "append",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchKind {
+    Pattern {
+        pattern: String,
+        payload: String,
+        match_indent: bool,
+    },
+    Regex {
+        pattern: String,
+        payload: String,
+        line_prepend: String,
+    },
+    /// Copies the contents of `sources`, relative to the mod, into the target.
+    Copy { sources: Vec<PathBuf> },
+    /// Kinds that don't change a game file, like `module`.
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Patch {
+    /// The toml file the patch is in.
+    pub file: PathBuf,
+    /// Which `[[patches]]` entry of the file this is, from 1.
+    pub index: usize,
+    pub priority: i64,
+    pub target: String,
+    pub position: Position,
+    /// How many matches the patch expects, all of them when not set.
+    pub times: Option<usize>,
+    pub kind: PatchKind,
+}
+
+#[derive(Debug)]
+pub enum PatchError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::Io(e) => write!(f, "{e}"),
+            PatchError::Toml(e) => write!(f, "{e}"),
+            PatchError::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+impl From<io::Error> for PatchError {
+    fn from(e: io::Error) -> Self {
+        PatchError::Io(e)
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct RawManifest {
+    #[serde(default)]
+    priority: i64,
+}
+
+#[derive(Deserialize)]
+struct RawFile {
+    #[serde(default)]
+    manifest: RawManifest,
+    #[serde(default)]
+    patches: Vec<HashMap<String, toml::Table>>,
+}
+
+fn field<'a>(table: &'a toml::Table, name: &str) -> Option<&'a str> {
+    table.get(name).and_then(|v| v.as_str())
+}
+
+fn required<'a>(table: &'a toml::Table, name: &str, index: usize) -> Result<&'a str, PatchError> {
+    field(table, name).ok_or_else(|| PatchError::Invalid(format!("patch {index} has no '{name}'")))
+}
+
+impl Patch {
+    fn from_table(
+        file: &Path,
+        index: usize,
+        priority: i64,
+        kind: &str,
+        table: &toml::Table,
+    ) -> Result<Self, PatchError> {
+        let position = match field(table, "position") {
+            Some(p) => Position::parse(p).ok_or_else(|| {
+                PatchError::Invalid(format!("patch {index} has an unknown position '{p}'"))
+            })?,
+            None if kind == "copy" => Position::Append,
+            None => Position::At,
+        };
+        let kind = match kind {
+            "pattern" => PatchKind::Pattern {
+                pattern: required(table, "pattern", index)?.to_string(),
+                payload: field(table, "payload").unwrap_or_default().to_string(),
+                match_indent: table
+                    .get("match_indent")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            },
+            "regex" => PatchKind::Regex {
+                pattern: required(table, "pattern", index)?.to_string(),
+                payload: field(table, "payload").unwrap_or_default().to_string(),
+                line_prepend: field(table, "line_prepend").unwrap_or_default().to_string(),
+            },
+            "copy" => PatchKind::Copy {
+                sources: table
+                    .get("sources")
+                    .and_then(|v| v.as_array())
+                    .map(|a| {
+                        a.iter()
+                            .filter_map(|v| v.as_str())
+                            .map(PathBuf::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+            other => PatchKind::Other(other.to_string()),
+        };
+        Ok(Self {
+            file: file.to_path_buf(),
+            index,
+            priority,
+            target: field(table, "target").unwrap_or_default().to_string(),
+            position,
+            times: table
+                .get("times")
+                .and_then(|v| v.as_integer())
+                .map(|t| t.max(0) as usize),
+            kind,
+        })
+    }
+
+    pub fn kind_name(&self) -> &str {
+        match &self.kind {
+            PatchKind::Pattern { .. } => "pattern",
+            PatchKind::Regex { .. } => "regex",
+            PatchKind::Copy { .. } => "copy",
+            PatchKind::Other(name) => name,
+        }
+    }
+
+    /// Whether the patch targets a file of the vanilla game rather than one made by a mod,
+    /// like Steamodded's `=[SMODS _ "src/utils.lua"]`.
+    pub fn targets_vanilla(&self) -> bool {
+        !self.target.is_empty() && !self.target.starts_with('=')
+    }
+
+    /// The lines of `source` the patch matches, as half-open ranges of line numbers from 0.
+    /// Copy patches match the start or the end of the file.
+    pub fn matches(&self, source: &str) -> Result<Vec<Range<usize>>, String> {
+        match &self.kind {
+            PatchKind::Pattern { pattern, .. } => Ok(pattern_matches(pattern, source)),
+            PatchKind::Regex { pattern, .. } => {
+                let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+                Ok(regex
+                    .find_iter(source)
+                    .map(|m| line_range(source, m.range()))
+                    .collect())
+            }
+            PatchKind::Copy { .. } => {
+                let line = match self.position {
+                    Position::Prepend => 0,
+                    _ => source.lines().count(),
+                };
+                Ok(vec![Range {
+                    start: line,
+                    end: line,
+                }])
+            }
+            PatchKind::Other(_) => Ok(vec![]),
+        }
+    }
+
+    /// Applies the patch the way Lovely would, returning the patched source and how many
+    /// times it was applied.
+    pub fn apply(&self, source: &str, mod_dir: &Path) -> Result<(String, usize), String> {
+        match &self.kind {
+            PatchKind::Pattern {
+                pattern,
+                payload,
+                match_indent,
+            } => {
+                let mut found = pattern_matches(pattern, source);
+                if let Some(times) = self.times {
+                    found.truncate(times);
+                }
+                let lines: Vec<&str> = source.lines().collect();
+                let mut out: Vec<String> = vec![];
+                let mut next = 0;
+                for range in &found {
+                    out.extend(lines[next..range.start].iter().map(|l| l.to_string()));
+                    let indent = if *match_indent {
+                        let first = lines[range.start];
+                        &first[..first.len() - first.trim_start().len()]
+                    } else {
+                        ""
+                    };
+                    let payload = payload.lines().map(|l| format!("{indent}{l}"));
+                    let matched = lines[range.clone()].iter().map(|l| l.to_string());
+                    match self.position {
+                        Position::Before => {
+                            out.extend(payload);
+                            out.extend(matched);
+                        }
+                        Position::At => out.extend(payload),
+                        _ => {
+                            out.extend(matched);
+                            out.extend(payload);
+                        }
+                    }
+                    next = range.end;
+                }
+                out.extend(lines[next..].iter().map(|l| l.to_string()));
+                Ok((join_lines(out, source), found.len()))
+            }
+            PatchKind::Regex {
+                pattern,
+                payload,
+                line_prepend,
+            } => {
+                let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+                let mut out = String::new();
+                let mut next = 0;
+                let mut count = 0;
+                for captures in regex.captures_iter(source) {
+                    if self.times.is_some_and(|t| count >= t) {
+                        break;
+                    }
+                    count += 1;
+                    let whole = captures.get(0).unwrap();
+                    let mut prepend = String::new();
+                    captures.expand(line_prepend, &mut prepend);
+                    let mut expanded = String::new();
+                    captures.expand(payload, &mut expanded);
+                    let expanded: String = expanded
+                        .split_inclusive('\n')
+                        .map(|l| format!("{prepend}{l}"))
+                        .collect();
+                    out.push_str(&source[next..whole.start()]);
+                    match self.position {
+                        Position::Before => {
+                            out.push_str(&expanded);
+                            out.push_str(whole.as_str());
+                        }
+                        Position::At => out.push_str(&expanded),
+                        _ => {
+                            out.push_str(whole.as_str());
+                            out.push_str(&expanded);
+                        }
+                    }
+                    next = whole.end();
+                }
+                out.push_str(&source[next..]);
+                Ok((out, count))
+            }
+            PatchKind::Copy { sources } => {
+                let mut copied = String::new();
+                for path in sources {
+                    let contents = std::fs::read_to_string(mod_dir.join(path))
+                        .map_err(|e| format!("{}: {}", path.display(), e))?;
+                    copied.push_str(&contents);
+                    copied.push('\n');
+                }
+                let patched = match self.position {
+                    Position::Prepend => format!("{copied}{source}"),
+                    _ => format!("{source}\n{copied}"),
+                };
+                Ok((patched, 1))
+            }
+            PatchKind::Other(_) => Ok((source.to_string(), 0)),
+        }
+    }
+}
+
+/// Joins lines back up, keeping the trailing newline of `original` if it had one.
+fn join_lines(lines: Vec<String>, original: &str) -> String {
+    let mut joined = lines.join("\n");
+    if original.ends_with('\n') {
+        joined.push('\n');
+    }
+    joined
+}
+
+fn line_range(source: &str, bytes: Range<usize>) -> Range<usize> {
+    let start = source[..bytes.start].matches('\n').count();
+    let end = source[..bytes.end].matches('\n').count() + 1;
+    start..end.max(start + 1)
+}
+
+/// Matches `text` against a pattern where `*` is any run of characters and `?` is one.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // where the last `*` was, and how much of the text it has eaten
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Finds the lines a pattern patch matches. Each line of the pattern is matched against a
+/// line of the source with surrounding whitespace ignored, like Lovely does.
+fn pattern_matches(pattern: &str, source: &str) -> Vec<Range<usize>> {
+    let wanted: Vec<&str> = pattern.trim().lines().map(str::trim).collect();
+    let lines: Vec<&str> = source.lines().map(str::trim).collect();
+    let mut found = vec![];
+    let mut i = 0;
+    while !wanted.is_empty() && i + wanted.len() <= lines.len() {
+        if wanted
+            .iter()
+            .zip(&lines[i..])
+            .all(|(p, l)| wildcard_match(p, l))
+        {
+            found.push(i..i + wanted.len());
+            i += wanted.len();
+        } else {
+            i += 1;
+        }
+    }
+    found
+}
+
+/// The Lovely patch files of a mod, `lovely.toml` first.
+pub fn patch_files(mod_dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let root = mod_dir.join("lovely.toml");
+    if root.is_file() {
+        files.push(root);
+    }
+    if let Ok(entries) = std::fs::read_dir(mod_dir.join("lovely")) {
+        let mut nested: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "toml"))
+            .collect();
+        nested.sort();
+        files.extend(nested);
+    }
+    files
+}
+
+pub fn read_patch_file(path: &Path) -> Result<Vec<Patch>, PatchError> {
+    let text = std::fs::read_to_string(path)?;
+    let raw: RawFile = toml::from_str(&text).map_err(PatchError::Toml)?;
+    raw.patches
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            entry
+                .iter()
+                .next()
+                .map(|(kind, table)| (i + 1, kind, table))
+        })
+        .map(|(index, kind, table)| {
+            Patch::from_table(path, index, raw.manifest.priority, kind, table)
+        })
+        .collect()
+}
+
+/// Every patch of a mod, and the files that couldn't be read.
+pub fn mod_patches(mod_dir: &Path) -> (Vec<Patch>, Vec<(PathBuf, PatchError)>) {
+    let mut patches = vec![];
+    let mut errors = vec![];
+    for file in patch_files(mod_dir) {
+        match read_patch_file(&file) {
+            Ok(found) => patches.extend(found),
+            Err(e) => errors.push((file, e)),
+        }
+    }
+    (patches, errors)
+}
+
+/// How a patch fares against the vanilla source.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Matched(usize),
+    NoMatch,
+    /// More than one match without `times`, which may patch more than intended.
+    Ambiguous(usize),
+    /// `times` is set but the patch matched a different number of times.
+    WrongCount {
+        found: usize,
+        expected: usize,
+    },
+    /// The target isn't a vanilla file, so it can't be checked.
+    NotVanilla,
+    MissingTarget,
+    Error(String),
+    /// Kinds like `module` that don't patch a file.
+    Skipped,
+}
+
+impl Status {
+    pub fn is_problem(&self) -> bool {
+        !matches!(
+            self,
+            Status::Matched(_) | Status::NotVanilla | Status::Skipped
+        )
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Matched(1) => write!(f, "1 match"),
+            Status::Matched(n) => write!(f, "{n} matches"),
+            Status::NoMatch => write!(f, "no match"),
+            Status::Ambiguous(n) => write!(f, "{n} matches, set `times` if that is intended"),
+            Status::WrongCount { found, expected } => {
+                write!(f, "{found} matches, expected {expected}")
+            }
+            Status::NotVanilla => write!(f, "targets a file made by a mod, not checked"),
+            Status::MissingTarget => write!(f, "target is not in the game"),
+            Status::Error(e) => write!(f, "{e}"),
+            Status::Skipped => write!(f, "not checked"),
+        }
+    }
+}
+
+/// Checks a patch against the game source in `game_dir`.
+pub fn check(patch: &Patch, game_dir: &Path, mod_dir: &Path) -> Status {
+    if let PatchKind::Other(_) = patch.kind {
+        return Status::Skipped;
+    }
+    if !patch.targets_vanilla() {
+        return Status::NotVanilla;
+    }
+    let Ok(source) = std::fs::read_to_string(game_dir.join(&patch.target)) else {
+        return Status::MissingTarget;
+    };
+    if let PatchKind::Copy { .. } = patch.kind {
+        return match patch.apply(&source, mod_dir) {
+            Ok(_) => Status::Matched(1),
+            Err(e) => Status::Error(e),
+        };
+    }
+    match patch.matches(&source) {
+        Err(e) => Status::Error(e),
+        Ok(found) => match (found.len(), patch.times) {
+            (0, _) => Status::NoMatch,
+            (n, Some(expected)) if n != expected => Status::WrongCount { found: n, expected },
+            (n, None) if n > 1 => Status::Ambiguous(n),
+            (n, _) => Status::Matched(n),
+        },
+    }
+}
+
+/// Applies every patch for `target`, in priority order, to the vanilla file.
+pub fn patch_target(
+    patches: &[Patch],
+    target: &str,
+    game_dir: &Path,
+    mod_dir: &Path,
+) -> io::Result<(String, String)> {
+    let vanilla = std::fs::read_to_string(game_dir.join(target))?;
+    let mut ordered: Vec<&Patch> = patches.iter().filter(|p| p.target == target).collect();
+    ordered.sort_by_key(|p| p.priority);
+    let mut patched = vanilla.clone();
+    for patch in ordered {
+        if let Ok((next, _)) = patch.apply(&patched, mod_dir) {
+            patched = next;
+        }
+    }
+    Ok((vanilla, patched))
+}
+
+/// A unified diff between two versions of `name`.
+pub fn unified_diff(old: &str, new: &str, name: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{name}"), &format!("b/{name}"))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    const GAME: &str = "function Game:start_run(args)\n    self.GAME = {}\n    self.GAME.round = 0\n    self.GAME.round = 0\nend\n";
+
+    const PATCHES: &str = r#"
+[manifest]
+version = "1.0.0"
+priority = 5
+
+[[patches]]
+[patches.pattern]
+target = "game.lua"
+pattern = "self.GAME = {}"
+position = "after"
+payload = "print('started')"
+match_indent = true
+
+[[patches]]
+[patches.pattern]
+target = "game.lua"
+pattern = "self.GAME.round = *"
+position = "at"
+payload = "self.GAME.round = 1"
+match_indent = true
+
+[[patches]]
+[patches.regex]
+target = "game.lua"
+pattern = '(?<indent>[\t ]*)self\.GAME = \{\}'
+position = "before"
+line_prepend = "$indent"
+payload = "-- new run\n"
+
+[[patches]]
+[patches.pattern]
+target = "card.lua"
+pattern = "nothing"
+position = "at"
+payload = ""
+
+[[patches]]
+[patches.copy]
+target = "main.lua"
+position = "append"
+sources = ["extra.lua"]
+
+[[patches]]
+[patches.pattern]
+target = '=[SMODS _ "src/utils.lua"]'
+pattern = "anything"
+position = "at"
+payload = ""
+
+[[patches]]
+[patches.module]
+source = "lib.lua"
+name = "lib"
+"#;
+
+    fn setup() -> (tempfile::TempDir, PathBuf, PathBuf) {
+        let dir = tempdir().unwrap();
+        let game = dir.path().join("game");
+        let mod_dir = dir.path().join("MyMod");
+        std::fs::create_dir_all(&game).unwrap();
+        std::fs::create_dir_all(mod_dir.join("lovely")).unwrap();
+        std::fs::write(game.join("game.lua"), GAME).unwrap();
+        std::fs::write(game.join("main.lua"), "love.load()\n").unwrap();
+        std::fs::write(mod_dir.join("lovely.toml"), PATCHES).unwrap();
+        std::fs::write(mod_dir.join("lovely").join("empty.toml"), "").unwrap();
+        std::fs::write(mod_dir.join("extra.lua"), "print('extra')").unwrap();
+        (dir, game, mod_dir)
+    }
+
+    #[test]
+    fn test_read_and_check() {
+        let (_dir, game, mod_dir) = setup();
+        assert_eq!(patch_files(&mod_dir).len(), 2);
+        let (patches, errors) = mod_patches(&mod_dir);
+        assert!(errors.is_empty());
+        assert_eq!(patches.len(), 7);
+        assert_eq!(patches[0].priority, 5);
+        assert_eq!(patches[2].kind_name(), "regex");
+
+        let statuses: Vec<Status> = patches.iter().map(|p| check(p, &game, &mod_dir)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Status::Matched(1),
+                Status::Ambiguous(2),
+                Status::Matched(1),
+                Status::MissingTarget,
+                Status::Matched(1),
+                Status::NotVanilla,
+                Status::Skipped,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_patch_target() {
+        let (_dir, game, mod_dir) = setup();
+        let (patches, _) = mod_patches(&mod_dir);
+        let (vanilla, patched) = patch_target(&patches, "game.lua", &game, &mod_dir).unwrap();
+        assert_eq!(vanilla, GAME);
+        assert_eq!(
+            patched,
+            "function Game:start_run(args)\n    -- new run\n    self.GAME = {}\n    print('started')\n    self.GAME.round = 1\n    self.GAME.round = 1\nend\n"
+        );
+        let diff = unified_diff(&vanilla, &patched, "game.lua");
+        assert!(diff.contains("+    print('started')"));
+        assert!(diff.contains("-    self.GAME.round = 0"));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("self.* = nil", "self.GAME = nil"));
+        assert!(wildcard_match("a?c*", "abcdef"));
+        assert!(!wildcard_match("a?c", "abcd"));
+        assert!(wildcard_match("*", ""));
+    }
+
+    #[test]
+    fn test_invalid_patch() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("lovely.toml");
+        std::fs::write(
+            &path,
+            "[[patches]]\n[patches.pattern]\ntarget = \"game.lua\"\nposition = \"after\"\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            read_patch_file(&path),
+            Err(PatchError::Invalid(_))
+        ));
+        std::fs::write(&path, "[[patches]\n").unwrap();
+        assert!(matches!(read_patch_file(&path), Err(PatchError::Toml(_))));
+    }
+}
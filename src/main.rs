@@ -1,8 +1,8 @@
 use tokio::io::{AsyncBufReadExt, BufReader};
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Command};
 use color_eyre::Result;
-use log::info;
+use log::{error, info};
 use balatro_tui::{clone_online_mod_list, download_to_tmp, get_balatro_appdata_dir, get_balatro_dir, get_repo_at, open, unzip, update_repo};
 use crate::app::App;
 use balatro_tui::motd::motd;
@@ -11,10 +11,13 @@ use crate::mods::ModList;
 
 mod action;
 mod app;
+mod bisect;
 mod cli;
 mod components;
 mod config;
+mod crash;
 mod errors;
+mod isolation;
 mod logging;
 mod tui;
 mod mods;
@@ -24,6 +27,9 @@ async fn main() -> Result<()> {
     crate::errors::init()?;
 
     let args = Cli::parse();
+    if let Some(command) = args.command {
+        return run_command(command);
+    }
     let mut app = App::new(args.tick_rate, args.frame_rate)?;
 
     // Set max_log_level to Info
@@ -35,6 +41,18 @@ async fn main() -> Result<()> {
 
     info!("{}", motd());
 
+    // an isolated launch that didn't finish left other mods disabled
+    match isolation::Isolation::recover(&isolation::journal_path()) {
+        Ok(true) => info!("Enabled the mods an unfinished isolated launch had disabled."),
+        Ok(false) => {}
+        Err(e) => error!("Failed to undo an unfinished isolated launch: {}", e),
+    }
+    match isolation::Isolation::recover(&isolation::bisect_journal_path()) {
+        Ok(true) => info!("Restored the enabled mods from before an unfinished bisect."),
+        Ok(false) => {}
+        Err(e) => error!("Failed to undo an unfinished bisect: {}", e),
+    }
+
     // let mut temp_file = download_to_tmp("https://github.com/colonthreeing/SealSealBalatro/releases/download/1.1.0/SealSeal.zip").await;
     // 
     // let file = temp_file.as_file();
@@ -45,3 +63,19 @@ async fn main() -> Result<()> {
     
     Ok(())
 }
+
+/// Runs a subcommand instead of the TUI.
+fn run_command(command: Command) -> Result<()> {
+    match command {
+        Command::Decode { path, compact } => {
+            let value = balatro_tui::jkr::read(&path)?;
+            let json = if compact {
+                serde_json::to_string(&value.to_json())?
+            } else {
+                serde_json::to_string_pretty(&value.to_json())?
+            };
+            println!("{json}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,348 @@
+//! Checking a mod's metadata JSON against what Steamodded expects of it.
+//!
+//! Steamodded (and this app) silently skip a mod whose metadata doesn't parse, so these checks
+//! say exactly what is wrong and where.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::{Map, Value};
+
+lazy_static! {
+    static ref PREFIX: Regex = Regex::new(r"^[A-Za-z0-9_]+$").unwrap();
+    static ref VERSION: Regex = Regex::new(r"^\d+(\.\d+){0,2}([~-][0-9A-Za-z._-]+)?$").unwrap();
+    static ref HEX_COLOUR: Regex = Regex::new(r"^([0-9A-Fa-f]{6}|[0-9A-Fa-f]{8})$").unwrap();
+    /// `ModId`, optionally followed by version constraints like `(>=1.0.0) (<<2.0)`.
+    static ref DEPENDENCY: Regex =
+        Regex::new(r"^[^\s()|]+(\s*\((>=|<=|>>|<<|==)[^\s()]+\))*$").unwrap();
+}
+
+/// Ids Steamodded keeps for itself.
+/// Ids Steamodded keeps for itself.
+pub const RESERVED_IDS: [&str; 3] = ["Steamodded", "Lovely", "Balatro"];
+
+/// Letters, digits and underscores only, what Steamodded allows in a prefix.
+pub fn is_identifier(s: &str) -> bool {
+    PREFIX.is_match(s)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// Line and column from 1, when the problem can be pinned down.
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(f, "{line}:{column}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// One item of the checklist, passed when it has no problems.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub problems: Vec<Problem>,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// The metadata JSON in `dir`: the one with an `"id"` in it, or else the only JSON file.
+pub fn find_metadata_file(dir: &Path) -> Option<PathBuf> {
+    let mut json_files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    json_files.sort();
+    json_files
+        .iter()
+        .find(|p| std::fs::read_to_string(p).is_ok_and(|t| t.contains("\"id\"")))
+        .cloned()
+        .or_else(|| (json_files.len() == 1).then(|| json_files.remove(0)))
+}
+
+/// Where `"field":` first appears in `text`, as line and column from 1.
+fn field_position(text: &str, field: &str) -> Option<(usize, usize)> {
+    let key = format!("\"{field}\"");
+    let mut from = 0;
+    while let Some(found) = text[from..].find(&key) {
+        let start = from + found;
+        from = start + key.len();
+        if text[from..].trim_start().starts_with(':') {
+            let before = &text[..start];
+            let line = before.matches('\n').count() + 1;
+            let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+            return Some((line, column));
+        }
+    }
+    None
+}
+
+struct Checker<'a> {
+    text: &'a str,
+    object: &'a Map<String, Value>,
+}
+
+impl Checker<'_> {
+    fn problem(&self, field: &str, message: impl Into<String>) -> Problem {
+        Problem {
+            position: field_position(self.text, field),
+            message: message.into(),
+        }
+    }
+
+    fn string(&self, field: &str) -> Result<Option<&str>, Problem> {
+        match self.object.get(field) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s)),
+            Some(_) => Err(self.problem(field, format!("{field} should be a string"))),
+        }
+    }
+
+    fn required(&self) -> Vec<Problem> {
+        let mut problems = vec![];
+        for field in ["id", "name", "description", "prefix", "main_file"] {
+            match self.string(field) {
+                Ok(Some(s)) if !s.trim().is_empty() => {}
+                Ok(_) => problems.push(Problem {
+                    position: None,
+                    message: format!("{field} is missing"),
+                }),
+                Err(e) => problems.push(e),
+            }
+        }
+        if let Ok(Some(id)) = self.string("id")
+            && RESERVED_IDS.contains(&id)
+        {
+            problems.push(self.problem("id", format!("the id {id} is taken by Steamodded")));
+        }
+        match self.object.get("author") {
+            None => problems.push(Problem {
+                position: None,
+                message: "author is missing".to_string(),
+            }),
+            Some(Value::Array(authors)) if authors.iter().all(Value::is_string) => {
+                if authors.is_empty() {
+                    problems.push(self.problem("author", "author needs at least one name"));
+                }
+            }
+            Some(_) => problems.push(self.problem("author", "author should be a list of names")),
+        }
+        problems
+    }
+
+    fn prefix(&self) -> Vec<Problem> {
+        match self.string("prefix") {
+            Ok(Some(prefix)) if !is_identifier(prefix) => vec![self.problem(
+                "prefix",
+                format!("the prefix {prefix:?} can only have letters, digits and underscores"),
+            )],
+            _ => vec![],
+        }
+    }
+
+    fn main_file(&self, mod_dir: &Path) -> Vec<Problem> {
+        match self.string("main_file") {
+            Ok(Some(main_file)) if !mod_dir.join(main_file).is_file() => {
+                vec![self.problem("main_file", format!("{main_file} doesn't exist"))]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn colours(&self) -> Vec<Problem> {
+        let mut problems = vec![];
+        for field in ["badge_colour", "badge_text_colour"] {
+            match self.string(field) {
+                Ok(Some(colour)) if !HEX_COLOUR.is_match(colour) => problems.push(self.problem(
+                    field,
+                    format!("{field} {colour:?} should be hex like FF8800, without a #"),
+                )),
+                Ok(_) => {}
+                Err(e) => problems.push(e),
+            }
+        }
+        problems
+    }
+
+    fn mod_list(&self, field: &str) -> Vec<Problem> {
+        let entries = match self.object.get(field) {
+            None => return vec![],
+            Some(Value::Array(entries)) => entries,
+            Some(_) => return vec![self.problem(field, format!("{field} should be a list"))],
+        };
+        let mut problems = vec![];
+        for entry in entries {
+            let Value::String(entry) = entry else {
+                problems.push(self.problem(field, format!("{field} should only have strings")));
+                continue;
+            };
+            // `A | B` means either will do
+            if entry
+                .split('|')
+                .any(|alternative| !DEPENDENCY.is_match(alternative.trim()))
+            {
+                problems.push(self.problem(
+                    field,
+                    format!("{entry:?} should look like \"ModId (>=1.0.0) (<<2.0)\""),
+                ));
+            }
+        }
+        problems
+    }
+
+    fn version(&self) -> Vec<Problem> {
+        match self.string("version") {
+            Ok(Some(version)) if !VERSION.is_match(version) => vec![self.problem(
+                "version",
+                format!("the version {version:?} should look like 1.2.3 or 1.2.3~beta"),
+            )],
+            Ok(_) => vec![],
+            Err(e) => vec![e],
+        }
+    }
+}
+
+/// Checks the metadata `text` of the mod in `mod_dir`.
+///
+/// When the JSON doesn't parse, that is the only check, since nothing else can be checked.
+pub fn check_metadata(text: &str, mod_dir: &Path) -> Vec<Check> {
+    let json = match serde_json::from_str::<Value>(text) {
+        Ok(json) => json,
+        Err(e) => {
+            return vec![Check {
+                name: "valid JSON",
+                problems: vec![Problem {
+                    position: Some((e.line(), e.column())),
+                    message: e.to_string(),
+                }],
+            }];
+        }
+    };
+    let Value::Object(object) = &json else {
+        return vec![Check {
+            name: "valid JSON",
+            problems: vec![Problem {
+                position: None,
+                message: "the metadata should be a JSON object".to_string(),
+            }],
+        }];
+    };
+    let checker = Checker { text, object };
+    vec![
+        Check {
+            name: "valid JSON",
+            problems: vec![],
+        },
+        Check {
+            name: "required fields",
+            problems: checker.required(),
+        },
+        Check {
+            name: "prefix",
+            problems: checker.prefix(),
+        },
+        Check {
+            name: "main_file exists",
+            problems: checker.main_file(mod_dir),
+        },
+        Check {
+            name: "badge colours",
+            problems: checker.colours(),
+        },
+        Check {
+            name: "dependencies",
+            problems: checker.mod_list("dependencies"),
+        },
+        Check {
+            name: "conflicts",
+            problems: checker.mod_list("conflicts"),
+        },
+        Check {
+            name: "version",
+            problems: checker.version(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn failed(checks: &[Check]) -> Vec<&'static str> {
+        checks
+            .iter()
+            .filter(|c| !c.passed())
+            .map(|c| c.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_check_metadata() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("main.lua"), "").unwrap();
+        let good = r#"{
+    "id": "SpeedMod",
+    "name": "Speed Mod",
+    "author": ["Jimbo"],
+    "description": "Faster",
+    "prefix": "speed",
+    "main_file": "main.lua",
+    "badge_colour": "FF8800",
+    "version": "1.0.0~beta",
+    "dependencies": ["Steamodded (>=1.0.0~BETA-0614a)", "Talisman | Cryptid (>=0.5) (<<1.0)"]
+}"#;
+        assert_eq!(
+            failed(&check_metadata(good, dir.path())),
+            Vec::<&str>::new()
+        );
+
+        let bad = r##"{
+    "id": "SpeedMod",
+    "author": "Jimbo",
+    "description": "Faster",
+    "prefix": "speed-mod",
+    "main_file": "speed.lua",
+    "badge_colour": "#FF8800",
+    "version": "one",
+    "conflicts": ["Other (>1.0)"]
+}"##;
+        let checks = check_metadata(bad, dir.path());
+        assert_eq!(
+            failed(&checks),
+            vec![
+                "required fields",
+                "prefix",
+                "main_file exists",
+                "badge colours",
+                "conflicts",
+                "version"
+            ]
+        );
+        let required = &checks[1].problems;
+        assert_eq!(required[0].message, "name is missing");
+        assert_eq!(required[1].position, Some((3, 5)));
+        assert_eq!(checks[7].problems[0].position, Some((8, 5)));
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let checks = check_metadata("{\n  \"id\": \"SpeedMod\",\n}", Path::new("."));
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].problems[0].position, Some((3, 1)));
+    }
+}
@@ -0,0 +1,312 @@
+//! Steamodded mod configs, which live in `config/<mod_id>.jkr` in the appdata dir.
+//!
+//! Steamodded writes these as plain Lua text rather than compressed, and falls back to the
+//! defaults from the mod's own `config.lua` when the file is missing.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::jkr::{self, JkrError, Key, Value};
+
+pub fn config_dir(appdata: &Path) -> PathBuf {
+    appdata.join("config")
+}
+
+/// The mod's config file. An existing file is matched ignoring case like Steamodded, so
+/// `speedmod.jkr` is found for `SpeedMod`.
+pub fn config_path(appdata: &Path, mod_id: &str) -> PathBuf {
+    let exact = config_dir(appdata).join(format!("{mod_id}.jkr"));
+    if exact.exists() {
+        return exact;
+    }
+    let Ok(entries) = std::fs::read_dir(config_dir(appdata)) else {
+        return exact;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| {
+            p.is_file()
+                && p.extension().is_some_and(|ext| ext == "jkr")
+                && p.file_stem()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|stem| stem.eq_ignore_ascii_case(mod_id))
+        })
+        .unwrap_or(exact)
+}
+
+/// A line in the config tree: a table or a value, with the keys leading to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub path: Vec<Key>,
+    pub value: Value,
+}
+
+impl Row {
+    pub fn depth(&self) -> usize {
+        self.path.len() - 1
+    }
+
+    pub fn key(&self) -> &Key {
+        self.path.last().unwrap()
+    }
+}
+
+fn sort_key(key: &Key) -> (u8, f64, String) {
+    match key {
+        Key::Number(n) => (0, *n, String::new()),
+        Key::String(s) => (1, 0.0, s.to_lowercase()),
+    }
+}
+
+fn push_rows(value: &Value, path: &mut Vec<Key>, rows: &mut Vec<Row>) {
+    let mut entries: Vec<&(Key, Value)> = value.entries().iter().collect();
+    entries.sort_by(|a, b| {
+        let (a, b) = (sort_key(&a.0), sort_key(&b.0));
+        a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)).then(a.2.cmp(&b.2))
+    });
+    for (key, value) in entries {
+        path.push(key.clone());
+        match value {
+            Value::Table(_) => {
+                rows.push(Row {
+                    path: path.clone(),
+                    value: Value::Table(vec![]),
+                });
+                push_rows(value, path, rows);
+            }
+            _ => rows.push(Row {
+                path: path.clone(),
+                value: value.clone(),
+            }),
+        }
+        path.pop();
+    }
+}
+
+/// Flattens a config into rows, depth first with keys sorted, numbers before names.
+/// Tables get a row of their own with an empty table as the value.
+pub fn rows(value: &Value) -> Vec<Row> {
+    let mut rows = vec![];
+    push_rows(value, &mut vec![], &mut rows);
+    rows
+}
+
+/// Config files in `config/` whose mod isn't in `installed_ids`, ignoring case like Steamodded.
+/// Empty ids, from mods without metadata, are skipped.
+pub fn orphaned_configs(appdata: &Path, installed_ids: &[&str]) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(config_dir(appdata)) else {
+        return vec![];
+    };
+    let mut orphans: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "jkr"))
+        .filter(|p| {
+            p.file_stem().and_then(|s| s.to_str()).is_some_and(|stem| {
+                !installed_ids
+                    .iter()
+                    .any(|id| !id.is_empty() && id.eq_ignore_ascii_case(stem))
+            })
+        })
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+#[derive(Debug)]
+pub enum ModConfigError {
+    Jkr(JkrError),
+    Io(PathBuf, io::Error),
+    /// Encoding and decoding the config gave something different, so writing it is unsafe.
+    RoundTrip,
+}
+
+impl fmt::Display for ModConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModConfigError::Jkr(e) => write!(f, "{e}"),
+            ModConfigError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ModConfigError::RoundTrip => write!(
+                f,
+                "the config did not survive being encoded and decoded, refusing to write it"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ModConfigError {}
+
+impl From<JkrError> for ModConfigError {
+    fn from(e: JkrError) -> Self {
+        ModConfigError::Jkr(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModConfig {
+    pub path: PathBuf,
+    pub value: Value,
+    /// Whether the file was compressed, so it is written back the same way.
+    compressed: bool,
+}
+
+impl ModConfig {
+    /// Reads a mod's config, `None` if it has never been saved and so uses its defaults.
+    pub fn load(appdata: &Path, mod_id: &str) -> Result<Option<Self>, ModConfigError> {
+        let path = config_path(appdata, mod_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = std::fs::read(&path).map_err(|e| ModConfigError::Io(path.clone(), e))?;
+        let compressed = !data.trim_ascii_start().starts_with(b"return");
+        let value = jkr::decode(&data)?;
+        Ok(Some(Self {
+            path,
+            value,
+            compressed,
+        }))
+    }
+
+    pub fn rows(&self) -> Vec<Row> {
+        rows(&self.value)
+    }
+
+    pub fn get(&self, path: &[Key]) -> Option<&Value> {
+        self.value.at(path)
+    }
+
+    /// Replaces an existing value, tables can't be replaced.
+    pub fn set(&mut self, path: &[Key], value: Value) -> bool {
+        match self.value.at_mut(path) {
+            Some(Value::Table(_)) | None => false,
+            Some(old) => {
+                *old = value;
+                true
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ModConfigError> {
+        let encoded = if self.compressed {
+            jkr::encode(&self.value).map_err(|e| ModConfigError::Io(self.path.clone(), e))?
+        } else {
+            jkr::encode_text(&self.value)
+        };
+        if jkr::decode(&encoded).ok().as_ref() != Some(&self.value) {
+            return Err(ModConfigError::RoundTrip);
+        }
+        std::fs::write(&self.path, encoded).map_err(|e| ModConfigError::Io(self.path.clone(), e))
+    }
+}
+
+/// Deletes a mod's config so Steamodded goes back to the mod's defaults.
+pub fn reset(appdata: &Path, mod_id: &str) -> io::Result<()> {
+    match std::fs::remove_file(config_path(appdata, mod_id)) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    // the way Steamodded's `serialize` writes it
+    const CONFIG: &str = "return {\n\t\"first\",\n\t[\"volume\"] = 0.5,\n\t[\"enabled\"] = true,\n\t[\"keys\"] = {\n\t\t[\"pause\"] = \"p\",\n\t},\n}";
+
+    fn write_config(appdata: &Path, id: &str) {
+        std::fs::create_dir_all(config_dir(appdata)).unwrap();
+        std::fs::write(config_path(appdata, id), CONFIG).unwrap();
+    }
+
+    #[test]
+    fn test_rows() {
+        let dir = tempdir().unwrap();
+        write_config(dir.path(), "MyMod");
+        let config = ModConfig::load(dir.path(), "MyMod").unwrap().unwrap();
+
+        let rows: Vec<(String, usize)> = config
+            .rows()
+            .iter()
+            .map(|r| (r.key().to_string(), r.depth()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("1".to_string(), 0),
+                ("enabled".to_string(), 0),
+                ("keys".to_string(), 0),
+                ("pause".to_string(), 1),
+                ("volume".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_edit_save_and_reset() {
+        let dir = tempdir().unwrap();
+        write_config(dir.path(), "MyMod");
+        let mut config = ModConfig::load(dir.path(), "MyMod").unwrap().unwrap();
+
+        let pause = [Key::String("keys".into()), Key::String("pause".into())];
+        assert!(config.set(&pause, Value::String("escape".into())));
+        assert!(!config.set(&[Key::String("keys".into())], Value::Bool(false)));
+        assert!(!config.set(&[Key::String("missing".into())], Value::Nil));
+        config.save().unwrap();
+
+        // still plain text, so Steamodded can load it
+        let written = std::fs::read(config_path(dir.path(), "MyMod")).unwrap();
+        assert!(written.starts_with(b"return {"));
+        let config = ModConfig::load(dir.path(), "MyMod").unwrap().unwrap();
+        assert_eq!(config.get(&pause), Some(&Value::String("escape".into())));
+        assert_eq!(
+            config.get(&[Key::Number(1.0)]),
+            Some(&Value::String("first".into()))
+        );
+
+        reset(dir.path(), "MyMod").unwrap();
+        assert!(ModConfig::load(dir.path(), "MyMod").unwrap().is_none());
+        reset(dir.path(), "MyMod").unwrap();
+    }
+
+    #[test]
+    fn test_config_path_ignores_case() {
+        let dir = tempdir().unwrap();
+        write_config(dir.path(), "speedmod");
+
+        let path = config_path(dir.path(), "SpeedMod");
+        assert_eq!(path, config_dir(dir.path()).join("speedmod.jkr"));
+        assert!(ModConfig::load(dir.path(), "SpeedMod").unwrap().is_some());
+        reset(dir.path(), "SpeedMod").unwrap();
+        assert!(!path.exists());
+        // no file yet, so the mod's own id names it
+        assert_eq!(
+            config_path(dir.path(), "SpeedMod"),
+            config_dir(dir.path()).join("SpeedMod.jkr")
+        );
+    }
+
+    #[test]
+    fn test_orphaned_configs() {
+        let dir = tempdir().unwrap();
+        write_config(dir.path(), "Kept");
+        write_config(dir.path(), "Gone");
+        std::fs::write(config_dir(dir.path()).join("notes.txt"), "").unwrap();
+
+        assert_eq!(
+            orphaned_configs(dir.path(), &["Kept", "Other"]),
+            vec![config_path(dir.path(), "Gone")]
+        );
+        write_config(dir.path(), "Steamodded");
+        assert_eq!(
+            orphaned_configs(dir.path(), &["kept", "steamodded", ""]),
+            vec![config_path(dir.path(), "Gone")]
+        );
+        assert!(orphaned_configs(&dir.path().join("nowhere"), &[]).is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+//! Making the files for submitting a mod to the Balatro Mod Index: a `mods/<Author@Mod>/` folder
+//! with `meta.json`, `description.md` and optionally `thumbnail.jpg`.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::metadata::find_metadata_file;
+use crate::release::latest_tag;
+
+/// Where submissions go in a checkout of the index.
+pub fn index_mods_dir(index_dir: &Path) -> PathBuf {
+    index_dir.join("mods")
+}
+
+/// A mod's `meta.json` in the index, with the names the mod list reads it with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexMeta {
+    pub title: String,
+    #[serde(rename = "requires-steamodded")]
+    pub requires_steamodded: bool,
+    #[serde(rename = "requires-talisman")]
+    pub requires_talisman: bool,
+    pub categories: Vec<String>,
+    pub author: String,
+    pub repo: String,
+    #[serde(rename = "downloadURL")]
+    pub download_url: String,
+    #[serde(rename = "folderName")]
+    pub folder_name: String,
+    pub version: String,
+}
+
+#[derive(Debug)]
+pub enum SubmissionError {
+    Io(PathBuf, io::Error),
+    Metadata(String),
+    /// The mod has no git remote to link to.
+    NoRepo,
+    NoCategories,
+    /// Categories the index doesn't use.
+    UnknownCategories(Vec<String>),
+}
+
+impl fmt::Display for SubmissionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmissionError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            SubmissionError::Metadata(e) => write!(f, "{e}"),
+            SubmissionError::NoRepo => {
+                write!(f, "the mod's git repo has no origin remote to link to")
+            }
+            SubmissionError::NoCategories => write!(f, "pick at least one category"),
+            SubmissionError::UnknownCategories(categories) => write!(
+                f,
+                "the index doesn't use the categories {}",
+                categories.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SubmissionError {}
+
+/// Every category used by the mods in a checkout of the index.
+pub fn known_categories(index_dir: &Path) -> BTreeSet<String> {
+    let Ok(dir) = std::fs::read_dir(index_mods_dir(index_dir)) else {
+        return BTreeSet::new();
+    };
+    dir.filter_map(|e| e.ok())
+        .filter_map(|e| std::fs::read_to_string(e.path().join("meta.json")).ok())
+        .filter_map(|text| serde_json::from_str::<Value>(&text).ok())
+        .flat_map(|json| {
+            json.get("categories")
+                .and_then(Value::as_array)
+                .map(|c| {
+                    c.iter()
+                        .filter_map(|c| c.as_str().map(str::to_string))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+pub fn validate_categories(
+    categories: &[String],
+    known: &BTreeSet<String>,
+) -> Result<(), SubmissionError> {
+    if categories.is_empty() {
+        return Err(SubmissionError::NoCategories);
+    }
+    let unknown: Vec<String> = categories
+        .iter()
+        .filter(|c| !known.contains(*c))
+        .cloned()
+        .collect();
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(SubmissionError::UnknownCategories(unknown))
+    }
+}
+
+/// The web address of a git remote, so `git@github.com:a/b.git` becomes `https://github.com/a/b`.
+pub fn web_url(remote: &str) -> String {
+    let url = match remote.strip_prefix("git@") {
+        Some(rest) => format!("https://{}", rest.replacen(':', "/", 1)),
+        None => remote.to_string(),
+    };
+    url.strip_suffix(".git").unwrap_or(&url).to_string()
+}
+
+/// The repo of the mod and a zip of its latest tag, or of its current branch without tags.
+fn repo_and_download(mod_dir: &Path) -> Result<(String, String), SubmissionError> {
+    let repo = Repository::open(mod_dir).map_err(|_| SubmissionError::NoRepo)?;
+    let remote = repo
+        .find_remote("origin")
+        .map_err(|_| SubmissionError::NoRepo)?;
+    let url = web_url(remote.url().ok_or(SubmissionError::NoRepo)?);
+    let download = match latest_tag(mod_dir) {
+        Some(tag) => format!("{url}/archive/refs/tags/{tag}.zip"),
+        None => {
+            // HEAD names the branch even before its first commit
+            let branch = repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|head| {
+                    let target = head.symbolic_target()?;
+                    Some(target.strip_prefix("refs/heads/")?.to_string())
+                })
+                .unwrap_or_else(|| "main".to_string());
+            format!("{url}/archive/refs/heads/{branch}.zip")
+        }
+    };
+    Ok((url, download))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Submission {
+    /// The `Author@Mod` folder name.
+    pub folder: String,
+    pub meta: IndexMeta,
+    pub description: String,
+    pub thumbnail: Option<PathBuf>,
+}
+
+/// Builds the submission for the mod in `mod_dir` from its metadata and git repo.
+pub fn submission(mod_dir: &Path, categories: &[String]) -> Result<Submission, SubmissionError> {
+    let file = find_metadata_file(mod_dir)
+        .ok_or_else(|| SubmissionError::Metadata("the mod has no metadata JSON".to_string()))?;
+    let text = std::fs::read_to_string(&file).map_err(|e| SubmissionError::Io(file.clone(), e))?;
+    let json: Value = serde_json::from_str(&text)
+        .map_err(|e| SubmissionError::Metadata(format!("{}: {}", file.display(), e)))?;
+    let field = |name: &str| {
+        json.get(name)
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| SubmissionError::Metadata(format!("the metadata has no {name}")))
+    };
+    let strings = |name: &str| -> Vec<String> {
+        json.get(name)
+            .and_then(Value::as_array)
+            .map(|a| {
+                a.iter()
+                    .filter_map(|s| s.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let (id, name, version) = (field("id")?, field("name")?, field("version")?);
+    let authors = strings("author");
+    let Some(first_author) = authors.first() else {
+        return Err(SubmissionError::Metadata(
+            "the metadata has no author".to_string(),
+        ));
+    };
+    let requires_talisman = strings("dependencies")
+        .iter()
+        .any(|d| d.trim_start().to_lowercase().starts_with("talisman"));
+    let (repo, download_url) = repo_and_download(mod_dir)?;
+
+    let description = match std::fs::read_to_string(mod_dir.join("README.md")) {
+        Ok(readme) => readme,
+        Err(_) => format!(
+            "# {name}\n\n{}\n",
+            json.get("description")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        ),
+    };
+    let thumbnail = Some(mod_dir.join("thumbnail.jpg")).filter(|p| p.is_file());
+    let folder = folder_name(first_author, &id);
+
+    Ok(Submission {
+        folder,
+        meta: IndexMeta {
+            title: name,
+            // the metadata JSON is Steamodded's, so a mod with one needs it
+            requires_steamodded: true,
+            requires_talisman,
+            categories: categories.to_vec(),
+            author: authors.join(", "),
+            repo,
+            download_url,
+            folder_name: id,
+            version,
+        },
+        description,
+        thumbnail,
+    })
+}
+
+/// The `Author@Mod` folder, kept to one path component so it can't leave `mods/`.
+fn folder_name(author: &str, id: &str) -> String {
+    format!("{author}@{id}")
+        .chars()
+        .filter(|c| {
+            !c.is_whitespace()
+                && !c.is_control()
+                && !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+        })
+        .collect()
+}
+
+/// Writes the submission into a checkout of the index, returning the folder it went in.
+pub fn write_submission(
+    index_dir: &Path,
+    submission: &Submission,
+) -> Result<PathBuf, SubmissionError> {
+    let dir = index_mods_dir(index_dir).join(&submission.folder);
+    let io = |path: &Path| {
+        let path = path.to_path_buf();
+        move |e| SubmissionError::Io(path, e)
+    };
+    std::fs::create_dir_all(&dir).map_err(io(&dir))?;
+    let meta = dir.join("meta.json");
+    let json = serde_json::to_string_pretty(&submission.meta).unwrap() + "\n";
+    std::fs::write(&meta, json).map_err(io(&meta))?;
+    let description = dir.join("description.md");
+    std::fs::write(&description, &submission.description).map_err(io(&description))?;
+    if let Some(thumbnail) = &submission.thumbnail {
+        let to = dir.join("thumbnail.jpg");
+        std::fs::copy(thumbnail, &to).map_err(io(&to))?;
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_web_url() {
+        assert_eq!(
+            web_url("git@github.com:someone/SpeedMod.git"),
+            "https://github.com/someone/SpeedMod"
+        );
+        assert_eq!(
+            web_url("https://github.com/someone/SpeedMod"),
+            "https://github.com/someone/SpeedMod"
+        );
+    }
+
+    #[test]
+    fn test_folder_name() {
+        assert_eq!(folder_name("Some One", "SpeedMod"), "SomeOne@SpeedMod");
+        assert_eq!(folder_name("../..", "a/b\\c"), "....@abc");
+    }
+
+    #[test]
+    fn test_submission() {
+        let root = tempdir().unwrap();
+        let (dev, index) = (root.path().join("dev"), root.path().join("index"));
+        std::fs::create_dir_all(&dev).unwrap();
+        std::fs::write(
+            dev.join("SpeedMod.json"),
+            r#"{"id": "SpeedMod", "name": "Speed Mod", "author": ["Some One", "Other"],
+                "description": "Goes fast", "prefix": "speed", "main_file": "main.lua",
+                "version": "1.2.0", "dependencies": ["Talisman (>=2.0)"]}"#,
+        )
+        .unwrap();
+        let repo = Repository::init_opts(
+            &dev,
+            git2::RepositoryInitOptions::new().initial_head("main"),
+        )
+        .unwrap();
+        repo.remote("origin", "git@github.com:someone/SpeedMod.git")
+            .unwrap();
+        for (name, categories) in [("A@a", r#"["Content"]"#), ("B@b", r#"["Joker", "QoL"]"#)] {
+            std::fs::create_dir_all(index_mods_dir(&index).join(name)).unwrap();
+            std::fs::write(
+                index_mods_dir(&index).join(name).join("meta.json"),
+                format!(r#"{{"title": "x", "categories": {categories}}}"#),
+            )
+            .unwrap();
+        }
+
+        let known = known_categories(&index);
+        assert_eq!(
+            known.iter().collect::<Vec<_>>(),
+            vec!["Content", "Joker", "QoL"]
+        );
+        let categories = vec!["Joker".to_string()];
+        validate_categories(&categories, &known).unwrap();
+        assert!(matches!(
+            validate_categories(&["Jokers".to_string()], &known),
+            Err(SubmissionError::UnknownCategories(_))
+        ));
+        assert!(matches!(
+            validate_categories(&[], &known),
+            Err(SubmissionError::NoCategories)
+        ));
+
+        let submission = submission(&dev, &categories).unwrap();
+        assert_eq!(submission.folder, "SomeOne@SpeedMod");
+        assert_eq!(
+            submission.meta,
+            IndexMeta {
+                title: "Speed Mod".to_string(),
+                requires_steamodded: true,
+                requires_talisman: true,
+                categories,
+                author: "Some One, Other".to_string(),
+                repo: "https://github.com/someone/SpeedMod".to_string(),
+                download_url: "https://github.com/someone/SpeedMod/archive/refs/heads/main.zip"
+                    .to_string(),
+                folder_name: "SpeedMod".to_string(),
+                version: "1.2.0".to_string(),
+            }
+        );
+        assert_eq!(submission.description, "# Speed Mod\n\nGoes fast\n");
+
+        let dir = write_submission(&index, &submission).unwrap();
+        assert_eq!(dir, index_mods_dir(&index).join("SomeOne@SpeedMod"));
+        let written: Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("meta.json")).unwrap()).unwrap();
+        assert_eq!(
+            written["downloadURL"],
+            submission.meta.download_url.as_str()
+        );
+        assert_eq!(written["folderName"], "SpeedMod");
+        assert!(dir.join("description.md").exists());
+        assert!(!dir.join("thumbnail.jpg").exists());
+    }
+}
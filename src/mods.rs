@@ -105,6 +105,30 @@ impl ModList {
         mods
     }
 
+    /// Returns the indices of `roots` plus everything they (transitively) depend on.
+    /// Of a set of alternatives, the first installed one is used.
+    pub fn with_dependencies(mods: &[Mod], roots: &[usize]) -> Vec<usize> {
+        let mut result: Vec<usize> = vec![];
+        let mut queue: Vec<usize> = roots.to_vec();
+        while let Some(i) = queue.pop() {
+            if result.contains(&i) {
+                continue;
+            }
+            result.push(i);
+            for alternatives in mods[i].dependency_ids() {
+                let found = alternatives.iter().find_map(|id| {
+                    mods.iter()
+                        .position(|m| !m.id.is_empty() && m.id.eq_ignore_ascii_case(id))
+                });
+                if let Some(dep) = found {
+                    queue.push(dep);
+                }
+            }
+        }
+        result.sort();
+        result
+    }
+
     pub fn get_remote_mods() -> Vec<RemoteMod> {
         let mut mods = vec![];
 
@@ -220,6 +244,39 @@ impl Mod {
         enabled
     }
 
+    /// Enables or disables the mod by removing or creating its `.lovelyignore`.
+    /// Force enabled mods are never disabled.
+    pub fn set_enabled(&mut self, enabled: bool) -> std::io::Result<()> {
+        if self.force_enable && !enabled {
+            return Ok(());
+        }
+        let ignore = self.folder.join(".lovelyignore");
+        if enabled && ignore.exists() {
+            std::fs::remove_file(&ignore)?;
+        } else if !enabled && !ignore.exists() {
+            File::create(&ignore)?;
+        }
+        self.enabled = Some(enabled);
+        Ok(())
+    }
+
+    /// The ids this mod depends on, read from entries like `"Steamodded (>=1.0.0~ALPHA-0812d)"`.
+    /// Alternatives (`"Talisman | Cryptid"`) are grouped together.
+    pub fn dependency_ids(&self) -> Vec<Vec<String>> {
+        self.dependencies
+            .iter()
+            .map(|dep| {
+                dep.split('|')
+                    .filter_map(|alt| {
+                        let id = alt.trim().split(['(', ' ', '<', '>', '=']).next()?;
+                        (!id.is_empty()).then(|| id.to_string())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|alts| !alts.is_empty())
+            .collect()
+    }
+
     pub fn toggle_enabled(&mut self) -> () {
         if self.force_enable {
             error!("This mod is marked as force enabled!");
@@ -0,0 +1,43 @@
+//! Helpers for finding running processes by scanning `/proc`.
+
+/// Returns the pids of every process whose name (`/proc/<pid>/comm`) or executable
+/// (first argument of `/proc/<pid>/cmdline`) matches `name`.
+#[cfg(target_os = "linux")]
+pub fn find_processes(name: &str) -> Vec<u32> {
+    let mut pids = vec![];
+    let Ok(dir) = std::fs::read_dir("/proc") else {
+        return pids;
+    };
+    for entry in dir.filter_map(|e| e.ok()) {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let comm = std::fs::read_to_string(entry.path().join("comm")).unwrap_or_default();
+        if comm.trim_end() == name {
+            pids.push(pid);
+            continue;
+        }
+        // wine processes show up with a full windows path as their first argument
+        let cmdline = std::fs::read(entry.path().join("cmdline")).unwrap_or_default();
+        let exe = cmdline.split(|b| *b == 0).next().unwrap_or_default();
+        let exe = String::from_utf8_lossy(exe);
+        if exe.rsplit(['/', '\\']).next() == Some(name) {
+            pids.push(pid);
+        }
+    }
+    pids
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn find_processes(name: &str) -> Vec<u32> {
+    let _ = name;
+    vec![]
+}
+
+pub fn is_process_running(name: &str) -> bool {
+    !find_processes(name).is_empty()
+}
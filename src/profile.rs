@@ -0,0 +1,296 @@
+//! Career stats and win stickers from a profile's `profile.jkr`.
+
+use std::path::{Path, PathBuf};
+
+use crate::jkr::{self, JkrError, Key, Value};
+
+pub const STAKES: [&str; 8] = [
+    "White", "Red", "Green", "Black", "Blue", "Purple", "Orange", "Gold",
+];
+
+pub const VANILLA_DECKS: [(&str, &str); 15] = [
+    ("b_red", "Red Deck"),
+    ("b_blue", "Blue Deck"),
+    ("b_yellow", "Yellow Deck"),
+    ("b_green", "Green Deck"),
+    ("b_black", "Black Deck"),
+    ("b_magic", "Magic Deck"),
+    ("b_nebula", "Nebula Deck"),
+    ("b_ghost", "Ghost Deck"),
+    ("b_abandoned", "Abandoned Deck"),
+    ("b_checkered", "Checkered Deck"),
+    ("b_zodiac", "Zodiac Deck"),
+    ("b_painted", "Painted Deck"),
+    ("b_anaglyph", "Anaglyph Deck"),
+    ("b_plasma", "Plasma Deck"),
+    ("b_erratic", "Erratic Deck"),
+];
+
+/// Stakes past this are ignored, so a corrupt key can't make a huge table.
+const MAX_STAKE: usize = STAKES.len() + 32;
+
+pub fn profile_path(appdata: &Path, slot: &str) -> PathBuf {
+    appdata.join(slot).join("profile.jkr")
+}
+
+/// The name of a stake, counting from 1 for White. Mods can add stakes past Gold.
+pub fn stake_name(stake: usize) -> String {
+    match stake.checked_sub(1).and_then(|i| STAKES.get(i)) {
+        Some(name) => name.to_string(),
+        None => format!("Stake {stake}"),
+    }
+}
+
+/// Turns `c_dollars_earned` into `Dollars earned`.
+pub fn stat_label(key: &str) -> String {
+    let words = key.strip_prefix("c_").unwrap_or(key).replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
+
+/// Wins of a deck or joker, by stake.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Wins {
+    pub key: String,
+    /// Win counts, indexed by stake starting at White.
+    pub by_stake: Vec<u32>,
+}
+
+impl Wins {
+    fn from_usage(key: &str, usage: &Value) -> Self {
+        let mut by_stake = vec![0; STAKES.len()];
+        for (k, v) in usage.get("wins").map(Value::entries).unwrap_or_default() {
+            if let Key::Number(stake) = k {
+                let stake = *stake as usize;
+                if (1..=MAX_STAKE).contains(&stake) {
+                    if stake > by_stake.len() {
+                        by_stake.resize(stake, 0);
+                    }
+                    by_stake[stake - 1] = v.as_f64().unwrap_or(1.0) as u32;
+                }
+            }
+        }
+        Self {
+            key: key.to_string(),
+            by_stake,
+        }
+    }
+
+    /// The highest stake won on, which is the sticker the game shows. 1 is White.
+    pub fn highest(&self) -> Option<usize> {
+        self.by_stake.iter().rposition(|w| *w > 0).map(|i| i + 1)
+    }
+
+    pub fn won_at(&self, stake: usize) -> bool {
+        stake >= 1 && self.by_stake.get(stake - 1).is_some_and(|w| *w > 0)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfileStats {
+    pub name: String,
+    /// `career_stats`, in the order the game wrote them.
+    pub career: Vec<(String, f64)>,
+    pub high_scores: Vec<(String, f64)>,
+    /// `progress` entries as `(name, tally, of)`, e.g. `("joker_stickers", 12, 150)`.
+    pub progress: Vec<(String, f64, f64)>,
+    /// Every vanilla deck, then any modded decks that were played.
+    pub decks: Vec<Wins>,
+    /// Jokers that have been used, sorted by key.
+    pub jokers: Vec<Wins>,
+}
+
+fn usage(profile: &Value, table: &str) -> Vec<Wins> {
+    profile
+        .get(table)
+        .map(Value::entries)
+        .unwrap_or_default()
+        .iter()
+        .map(|(k, v)| Wins::from_usage(&k.to_string(), v))
+        .collect()
+}
+
+impl ProfileStats {
+    pub fn from_profile(profile: &Value) -> Self {
+        let career = profile
+            .get("career_stats")
+            .map(Value::entries)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|(k, v)| Some((k.to_string(), v.as_f64()?)))
+            .collect();
+
+        let high_scores = profile
+            .get("high_scores")
+            .map(Value::entries)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|(k, v)| Some((k.to_string(), v.get("amt")?.as_f64()?)))
+            .collect();
+
+        let mut progress: Vec<(String, f64, f64)> = profile
+            .get("progress")
+            .map(Value::entries)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|(k, v)| {
+                Some((
+                    k.to_string(),
+                    v.get("tally")?.as_f64()?,
+                    v.get("of")?.as_f64()?,
+                ))
+            })
+            .collect();
+        progress.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let played = usage(profile, "deck_usage");
+        let mut decks: Vec<Wins> = VANILLA_DECKS
+            .iter()
+            .map(|(key, _)| {
+                played
+                    .iter()
+                    .find(|d| d.key == *key)
+                    .cloned()
+                    .unwrap_or_else(|| Wins {
+                        key: key.to_string(),
+                        by_stake: vec![0; STAKES.len()],
+                    })
+            })
+            .collect();
+        let mut modded: Vec<Wins> = played
+            .into_iter()
+            .filter(|d| !VANILLA_DECKS.iter().any(|(key, _)| d.key == *key))
+            .collect();
+        modded.sort_by(|a, b| a.key.cmp(&b.key));
+        decks.extend(modded);
+
+        let mut jokers = usage(profile, "joker_usage");
+        jokers.sort_by(|a, b| a.key.cmp(&b.key));
+
+        Self {
+            name: profile
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            career,
+            high_scores,
+            progress,
+            decks,
+            jokers,
+        }
+    }
+
+    /// Reads a profile slot, `None` if the slot is empty.
+    pub fn read(appdata: &Path, slot: &str) -> Result<Option<Self>, JkrError> {
+        let path = profile_path(appdata, slot);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Self::from_profile(&jkr::read(&path)?)))
+    }
+
+    pub fn career_stat(&self, key: &str) -> Option<f64> {
+        self.career.iter().find(|(k, _)| k == key).map(|(_, v)| *v)
+    }
+
+    /// How many deck and stake combinations have been won, out of the vanilla ones.
+    pub fn deck_stakes_won(&self) -> usize {
+        self.decks
+            .iter()
+            .filter(|d| VANILLA_DECKS.iter().any(|(key, _)| d.key == *key))
+            .map(|d| (1..=STAKES.len()).filter(|s| d.won_at(*s)).count())
+            .sum()
+    }
+
+    /// How many used jokers have a sticker of at least `stake`.
+    pub fn jokers_with_sticker(&self, stake: usize) -> usize {
+        self.jokers
+            .iter()
+            .filter(|j| j.highest().is_some_and(|h| h >= stake))
+            .count()
+    }
+}
+
+/// The display name of a deck, falling back to its key for modded decks.
+pub fn deck_name(key: &str) -> &str {
+    VANILLA_DECKS
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, name)| *name)
+        .unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const PROFILE: &str = r#"return {
+        ["name"]="P1",
+        ["career_stats"]={["c_wins"]=4,["c_losses"]=10,["c_dollars_earned"]=1234,},
+        ["high_scores"]={["hand"]={["amt"]=98000,},["furthest_ante"]={["amt"]=9,},},
+        ["progress"]={["joker_stickers"]={["tally"]=3,["of"]=150,},["deck_stakes"]={["tally"]=5,["of"]=120,},},
+        ["deck_usage"]={
+            ["b_red"]={["count"]=10,["wins"]={[1]=2,[2]=1,},["losses"]={[1]=5,},},
+            ["b_mymod_deck"]={["count"]=1,["wins"]={[3]=1,[9]=1,[1000000000000000]=1,},},
+        },
+        ["joker_usage"]={
+            ["j_joker"]={["count"]=3,["wins"]={[1]=1,},},
+            ["j_blueprint"]={["count"]=2,["wins"]={[1]=1,[4]=1,},},
+            ["j_egg"]={["count"]=1,["wins"]={},},
+        },
+    }"#;
+
+    #[test]
+    fn test_profile_stats() {
+        let profile = jkr::parse(PROFILE.as_bytes()).unwrap();
+        let stats = ProfileStats::from_profile(&profile);
+
+        assert_eq!(stats.name, "P1");
+        assert_eq!(stats.career_stat("c_wins"), Some(4.0));
+        assert_eq!(stats.high_scores[0], ("hand".to_string(), 98000.0));
+        assert_eq!(
+            stats.progress[1],
+            ("joker_stickers".to_string(), 3.0, 150.0)
+        );
+
+        assert_eq!(stats.decks.len(), 16);
+        assert_eq!(stats.decks[0].highest(), Some(2));
+        assert_eq!(stats.decks[1].highest(), None);
+        assert_eq!(stats.decks[15].key, "b_mymod_deck");
+        // a modded stake past Gold, and a bogus one that is ignored
+        assert_eq!(stats.decks[15].highest(), Some(9));
+        assert_eq!(stats.decks[15].by_stake.len(), 9);
+        assert_eq!(stake_name(9), "Stake 9");
+        assert_eq!(stake_name(8), "Gold");
+        // modded decks don't count towards completion
+        assert_eq!(stats.deck_stakes_won(), 2);
+
+        let jokers: Vec<(&str, Option<usize>)> = stats
+            .jokers
+            .iter()
+            .map(|j| (j.key.as_str(), j.highest()))
+            .collect();
+        assert_eq!(
+            jokers,
+            vec![
+                ("j_blueprint", Some(4)),
+                ("j_egg", None),
+                ("j_joker", Some(1))
+            ]
+        );
+        assert_eq!(stats.jokers_with_sticker(1), 2);
+        assert_eq!(stats.jokers_with_sticker(4), 1);
+    }
+
+    #[test]
+    fn test_stat_label() {
+        assert_eq!(stat_label("c_dollars_earned"), "Dollars earned");
+        assert_eq!(stat_label("furthest_ante"), "Furthest ante");
+    }
+}
@@ -0,0 +1,262 @@
+//! Packaging a mod being authored into a release zip.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use git2::Repository;
+use ignore::WalkBuilder;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::metadata::find_metadata_file;
+
+/// Where releases are written, inside the mod and never packaged.
+pub fn releases_dir(mod_dir: &Path) -> PathBuf {
+    mod_dir.join("releases")
+}
+
+/// Files editors and operating systems leave around.
+const EDITOR_FILES: [&str; 5] = [".idea", ".vscode", ".DS_Store", "Thumbs.db", "desktop.ini"];
+
+pub(crate) fn is_editor_file(name: &str) -> bool {
+    EDITOR_FILES.contains(&name)
+        // emacs lock files and backups, vim swap files
+        || name.starts_with(".#")
+        || name.ends_with('~')
+        || name.ends_with(".swp")
+        || name.ends_with(".swo")
+}
+
+#[derive(Debug)]
+pub enum ReleaseError {
+    Io(PathBuf, io::Error),
+    Zip(zip::result::ZipError),
+    Metadata(String),
+    /// The metadata version isn't the one of the latest tag.
+    VersionMismatch {
+        version: String,
+        tag: String,
+    },
+}
+
+impl fmt::Display for ReleaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReleaseError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ReleaseError::Zip(e) => write!(f, "{e}"),
+            ReleaseError::Metadata(e) => write!(f, "{e}"),
+            ReleaseError::VersionMismatch { version, tag } => write!(
+                f,
+                "the metadata says version {version}, but the latest git tag is {tag}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReleaseError {}
+
+impl From<zip::result::ZipError> for ReleaseError {
+    fn from(e: zip::result::ZipError) -> Self {
+        ReleaseError::Zip(e)
+    }
+}
+
+/// The id and version from the mod's metadata.
+pub fn id_and_version(mod_dir: &Path) -> Result<(String, String), ReleaseError> {
+    let file = find_metadata_file(mod_dir)
+        .ok_or_else(|| ReleaseError::Metadata("the mod has no metadata JSON".to_string()))?;
+    let text = std::fs::read_to_string(&file).map_err(|e| ReleaseError::Io(file.clone(), e))?;
+    let json: Value = serde_json::from_str(&text)
+        .map_err(|e| ReleaseError::Metadata(format!("{}: {}", file.display(), e)))?;
+    let field = |name: &str| {
+        json.get(name)
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| ReleaseError::Metadata(format!("the metadata has no {name}")))
+    };
+    Ok((field("id")?, field("version")?))
+}
+
+/// The most recent tag of the repo at `mod_dir`, by the time of the commit it points at and
+/// then by name.
+pub fn latest_tag(mod_dir: &Path) -> Option<String> {
+    let repo = Repository::open(mod_dir).ok()?;
+    let names = repo.tag_names(None).ok()?;
+    names
+        .iter()
+        .flatten()
+        .filter_map(|name| {
+            let commit = repo.revparse_single(name).ok()?.peel_to_commit().ok()?;
+            Some((commit.time().seconds(), name.to_string()))
+        })
+        .max()
+        .map(|(_, name)| name)
+}
+
+/// The files that go in a release, relative to `mod_dir` and sorted.
+///
+/// Leaves out what `.gitignore` and `.modignore` ignore, `.git`, editor files, old releases and
+/// `.lovelyignore`, which would ship the mod disabled.
+pub fn release_files(mod_dir: &Path) -> Vec<PathBuf> {
+    let releases = releases_dir(mod_dir);
+    let mut files: Vec<PathBuf> = WalkBuilder::new(mod_dir)
+        .hidden(false)
+        .require_git(false)
+        .add_custom_ignore_filename(".modignore")
+        .filter_entry(move |entry| {
+            let name = entry.file_name().to_string_lossy();
+            name != ".git"
+                && name != ".lovelyignore"
+                && !is_editor_file(&name)
+                && entry.path() != releases
+        })
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|e| Some(e.path().strip_prefix(mod_dir).ok()?.to_path_buf()))
+        .collect();
+    files.sort();
+    files
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Release {
+    pub path: PathBuf,
+    /// The SHA-256 of the zip, in hex.
+    pub sha256: String,
+    pub files: usize,
+}
+
+/// Zips the mod into `releases/<id>-<version>.zip`, with everything inside one `<id>` folder.
+///
+/// Unless `ignore_tag` is set, refuses to when the metadata version isn't the latest git tag,
+/// with or without a leading `v`.
+pub fn build_release(mod_dir: &Path, ignore_tag: bool) -> Result<Release, ReleaseError> {
+    let (id, version) = id_and_version(mod_dir)?;
+    if !ignore_tag
+        && let Some(tag) = latest_tag(mod_dir)
+        && tag.strip_prefix('v').unwrap_or(&tag) != version.strip_prefix('v').unwrap_or(&version)
+    {
+        return Err(ReleaseError::VersionMismatch { version, tag });
+    }
+
+    let files = release_files(mod_dir);
+    let dir = releases_dir(mod_dir);
+    std::fs::create_dir_all(&dir).map_err(|e| ReleaseError::Io(dir.clone(), e))?;
+    let path = dir.join(format!("{id}-{version}.zip"));
+    let io_error = |e| ReleaseError::Io(path.clone(), e);
+
+    let mut zip = ZipWriter::new(File::create(&path).map_err(io_error)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for file in &files {
+        let name = format!("{id}/{}", file.to_string_lossy().replace('\\', "/"));
+        zip.start_file(name, options)?;
+        let contents =
+            std::fs::read(mod_dir.join(file)).map_err(|e| ReleaseError::Io(file.clone(), e))?;
+        zip.write_all(&contents).map_err(io_error)?;
+    }
+    zip.finish()?;
+
+    let zipped = std::fs::read(&path).map_err(io_error)?;
+    let sha256 = Sha256::digest(&zipped)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    Ok(Release {
+        path,
+        sha256,
+        files: files.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    fn write(dir: &Path, file: &str, contents: &str) {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn write_mod(dir: &Path, version: &str) {
+        write(
+            dir,
+            "SpeedMod.json",
+            &format!(r#"{{"id": "SpeedMod", "version": "{version}"}}"#),
+        );
+        write(dir, "main.lua", "");
+        write(dir, "assets/1x/jokers.png", "");
+        write(dir, "notes.md", "");
+        write(dir, "scratch/test.lua", "");
+        write(dir, "main.lua~", "");
+        write(dir, ".vscode/settings.json", "");
+        write(dir, ".gitignore", "notes.md\n");
+        write(dir, ".modignore", "scratch/\n");
+        write(dir, ".lovelyignore", "");
+    }
+
+    #[test]
+    fn test_build_release() {
+        let dir = tempdir().unwrap();
+        write_mod(dir.path(), "1.2.0");
+        write(dir.path(), "releases/SpeedMod-1.1.0.zip", "");
+
+        let release = build_release(dir.path(), false).unwrap();
+        assert_eq!(
+            release.path,
+            releases_dir(dir.path()).join("SpeedMod-1.2.0.zip")
+        );
+        assert_eq!(release.sha256.len(), 64);
+
+        let mut archive = ZipArchive::new(File::open(&release.path).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "SpeedMod/.gitignore",
+                "SpeedMod/.modignore",
+                "SpeedMod/SpeedMod.json",
+                "SpeedMod/assets/1x/jokers.png",
+                "SpeedMod/main.lua"
+            ]
+        );
+        assert!(archive.by_name("SpeedMod/main.lua").is_ok());
+    }
+
+    #[test]
+    fn test_version_must_match_latest_tag() {
+        let dir = tempdir().unwrap();
+        write_mod(dir.path(), "1.2.0");
+        let repo = Repository::init(dir.path()).unwrap();
+        let signature = git2::Signature::now("Jimbo", "jimbo@example.com").unwrap();
+        let tree = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree).unwrap();
+        let commit = repo
+            .commit(Some("HEAD"), &signature, &signature, "release", &tree, &[])
+            .unwrap();
+        let commit = repo.find_object(commit, None).unwrap();
+        repo.tag_lightweight("v1.1.0", &commit, false).unwrap();
+
+        assert_eq!(latest_tag(dir.path()), Some("v1.1.0".to_string()));
+        assert!(matches!(
+            build_release(dir.path(), false),
+            Err(ReleaseError::VersionMismatch { .. })
+        ));
+        assert!(build_release(dir.path(), true).is_ok());
+
+        // on the same commit, the greater name wins
+        repo.tag_lightweight("v1.2.0", &commit, false).unwrap();
+        assert!(build_release(dir.path(), false).is_ok());
+    }
+}
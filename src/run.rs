@@ -0,0 +1,261 @@
+//! Summarising the run in progress from a profile's `save.jkr`.
+
+use std::path::{Path, PathBuf};
+
+use crate::jkr::{self, JkrError, Value};
+
+/// Where the game saves the run in progress for a profile slot.
+pub fn save_path(appdata: &Path, slot: &str) -> PathBuf {
+    appdata.join(slot).join("save.jkr")
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CardSummary {
+    pub name: String,
+    /// The center key, e.g. `j_joker` or `m_glass` for an enhanced playing card.
+    pub key: String,
+    pub edition: Option<String>,
+    pub seal: Option<String>,
+    /// Eternal, perishable, rental and pinned.
+    pub stickers: Vec<String>,
+}
+
+impl CardSummary {
+    fn from_card(card: &Value) -> Self {
+        let key = card
+            .get_path(&["save_fields", "center"])
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        let playing_card = card
+            .get_path(&["base", "value"])
+            .and_then(Value::as_str)
+            .zip(card.get_path(&["base", "suit"]).and_then(Value::as_str));
+        let name = match playing_card {
+            Some((value, suit)) => {
+                let mut name = format!("{value} of {suit}");
+                let enhancement = card.get_path(&["ability", "name"]).and_then(Value::as_str);
+                if key != "c_base"
+                    && let Some(enhancement) = enhancement
+                {
+                    name = format!("{name} ({enhancement})");
+                }
+                name
+            }
+            None => card
+                .get("label")
+                .or(card.get_path(&["ability", "name"]))
+                .and_then(Value::as_str)
+                .unwrap_or(&key)
+                .to_string(),
+        };
+
+        let edition = card.get("edition").and_then(|e| {
+            e.get("type")
+                .and_then(Value::as_str)
+                .map(String::from)
+                .or_else(|| {
+                    // older saves only set a flag named after the edition
+                    e.entries()
+                        .iter()
+                        .find(|(_, v)| v.as_bool() == Some(true))
+                        .map(|(k, _)| k.to_string())
+                })
+        });
+
+        let stickers = ["eternal", "perishable", "rental"]
+            .into_iter()
+            .filter(|s| {
+                card.get_path(&["ability", s])
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false)
+            })
+            .chain(
+                card.get("pinned")
+                    .and_then(Value::as_bool)
+                    .filter(|p| *p)
+                    .map(|_| "pinned"),
+            )
+            .map(String::from)
+            .collect();
+
+        Self {
+            name,
+            key,
+            edition,
+            seal: card.get("seal").and_then(Value::as_str).map(String::from),
+            stickers,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunSummary {
+    pub seed: String,
+    pub deck: String,
+    pub stake: u32,
+    pub ante: u32,
+    pub round: u32,
+    pub money: f64,
+    pub jokers: Vec<CardSummary>,
+    pub consumables: Vec<CardSummary>,
+    pub vouchers: Vec<String>,
+    /// Every playing card in the run, wherever it is right now.
+    pub cards: Vec<CardSummary>,
+    pub game_version: Option<String>,
+}
+
+fn number(save: &Value, path: &[&str]) -> f64 {
+    save.get_path(path)
+        .and_then(Value::as_f64)
+        .unwrap_or_default()
+}
+
+fn area_cards(save: &Value, area: &str) -> Vec<CardSummary> {
+    save.get_path(&["cardAreas", area, "cards"])
+        .map(|cards| {
+            cards
+                .array()
+                .into_iter()
+                .map(CardSummary::from_card)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+impl RunSummary {
+    pub fn from_save(save: &Value) -> Self {
+        let mut vouchers: Vec<String> = save
+            .get_path(&["GAME", "used_vouchers"])
+            .map(|v| {
+                v.entries()
+                    .iter()
+                    .filter(|(_, used)| used.as_bool() == Some(true))
+                    .map(|(k, _)| k.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        vouchers.sort();
+
+        let cards = ["deck", "hand", "discard", "play"]
+            .into_iter()
+            .flat_map(|area| area_cards(save, area))
+            .collect();
+
+        let deck = save
+            .get_path(&["BACK", "name"])
+            .or(save.get_path(&["GAME", "selected_back", "name"]))
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown deck")
+            .to_string();
+
+        Self {
+            seed: save
+                .get_path(&["GAME", "pseudorandom", "seed"])
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            deck,
+            stake: number(save, &["GAME", "stake"]) as u32,
+            ante: number(save, &["GAME", "round_resets", "ante"]) as u32,
+            round: number(save, &["GAME", "round"]) as u32,
+            money: number(save, &["GAME", "dollars"]),
+            jokers: area_cards(save, "jokers"),
+            consumables: area_cards(save, "consumeables"),
+            vouchers,
+            cards,
+            game_version: save
+                .get("VERSION")
+                .and_then(Value::as_str)
+                .map(String::from),
+        }
+    }
+
+    /// Reads the run saved in a profile slot, `None` if there is no run in progress.
+    pub fn read(appdata: &Path, slot: &str) -> Result<Option<Self>, JkrError> {
+        let path = save_path(appdata, slot);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Self::from_save(&jkr::read(&path)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const SAVE: &str = r#"return {
+        ["VERSION"]="1.0.1o-FULL",
+        ["BACK"]={["name"]="Red Deck",["key"]="b_red",},
+        ["GAME"]={
+            ["round"]=7,["dollars"]=12,["stake"]=2,
+            ["round_resets"]={["ante"]=3,},
+            ["pseudorandom"]={["seed"]="7LB2WVPK",},
+            ["used_vouchers"]={["v_overstock_norm"]=true,["v_clearance_sale"]=true,},
+        },
+        ["cardAreas"]={
+            ["jokers"]={["cards"]={
+                [2]={["label"]="Blueprint",["save_fields"]={["center"]="j_blueprint",},["ability"]={["name"]="Blueprint",["eternal"]=true,},},
+                [1]={["label"]="Joker",["save_fields"]={["center"]="j_joker",},["edition"]={["foil"]=true,["type"]="foil",},["ability"]={["name"]="Joker",},},
+            },},
+            ["consumeables"]={["cards"]={
+                [1]={["label"]="The Fool",["save_fields"]={["center"]="c_fool",},["ability"]={["name"]="The Fool",},},
+            },},
+            ["deck"]={["cards"]={
+                [1]={["save_fields"]={["card"]="H_K",["center"]="m_glass",},["base"]={["value"]="King",["suit"]="Hearts",},["ability"]={["name"]="Glass Card",},["seal"]="Red",},
+            },},
+            ["hand"]={["cards"]={
+                [1]={["save_fields"]={["card"]="S_2",["center"]="c_base",},["base"]={["value"]="2",["suit"]="Spades",},["ability"]={["name"]="Default Base",},},
+            },},
+        },
+    }"#;
+
+    #[test]
+    fn test_run_summary() {
+        let save = jkr::parse(SAVE.as_bytes()).unwrap();
+        let run = RunSummary::from_save(&save);
+
+        assert_eq!(run.seed, "7LB2WVPK");
+        assert_eq!(run.deck, "Red Deck");
+        assert_eq!((run.stake, run.ante, run.round), (2, 3, 7));
+        assert_eq!(run.money, 12.0);
+        assert_eq!(run.vouchers, vec!["v_clearance_sale", "v_overstock_norm"]);
+        assert_eq!(run.game_version.as_deref(), Some("1.0.1o-FULL"));
+
+        assert_eq!(
+            run.jokers,
+            vec![
+                CardSummary {
+                    name: "Joker".into(),
+                    key: "j_joker".into(),
+                    edition: Some("foil".into()),
+                    ..Default::default()
+                },
+                CardSummary {
+                    name: "Blueprint".into(),
+                    key: "j_blueprint".into(),
+                    stickers: vec!["eternal".into()],
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(run.consumables[0].name, "The Fool");
+
+        let cards: Vec<(&str, Option<&str>)> = run
+            .cards
+            .iter()
+            .map(|c| (c.name.as_str(), c.seal.as_deref()))
+            .collect();
+        assert_eq!(
+            cards,
+            vec![
+                ("King of Hearts (Glass Card)", Some("Red")),
+                ("2 of Spades", None)
+            ]
+        );
+    }
+}
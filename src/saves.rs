@@ -0,0 +1,276 @@
+//! Snapshots of the game's save profiles, kept as zip files so they can be restored later.
+//!
+//! Balatro keeps each profile in a numbered folder (`1`, `2`, `3`) in its appdata folder, holding
+//! `profile.jkr`, `meta.jkr` and, while a run is in progress, `save.jkr`.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+pub const PROFILE_SLOTS: [&str; 3] = ["1", "2", "3"];
+
+const SNAPSHOT_PREFIX: &str = "profiles-";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub created: DateTime<Local>,
+    /// Size of the zip file in bytes.
+    pub size: u64,
+    /// The profile slots the snapshot holds.
+    pub slots: Vec<String>,
+}
+
+impl Snapshot {
+    fn read(path: PathBuf) -> io::Result<Self> {
+        let file = File::open(&path)?;
+        let size = file.metadata()?.len();
+        let created = file
+            .metadata()?
+            .modified()
+            .map(DateTime::<Local>::from)
+            .unwrap_or_default();
+        let archive = ZipArchive::new(file).map_err(io::Error::other)?;
+
+        let mut slots: Vec<String> = archive
+            .file_names()
+            .filter_map(|name| name.split_once('/').map(|(slot, _)| slot.to_string()))
+            .collect();
+        slots.sort();
+        slots.dedup();
+
+        Ok(Self {
+            path,
+            created,
+            size,
+            slots,
+        })
+    }
+}
+
+/// The profile slots in `appdata` that have a profile in them.
+pub fn existing_slots(appdata: &Path) -> Vec<&'static str> {
+    PROFILE_SLOTS
+        .into_iter()
+        .filter(|slot| appdata.join(slot).join("profile.jkr").is_file())
+        .collect()
+}
+
+/// Zips the given profile slots from `appdata` into a new timestamped file in `backups`.
+pub fn take_snapshot(appdata: &Path, backups: &Path, slots: &[&str]) -> io::Result<Snapshot> {
+    fs::create_dir_all(backups)?;
+
+    let timestamp = Local::now().format(TIMESTAMP_FORMAT);
+    let mut path = backups.join(format!("{SNAPSHOT_PREFIX}{timestamp}.zip"));
+    // two snapshots in the same second, e.g. the one taken before a restore
+    let mut n = 1;
+    while path.exists() {
+        n += 1;
+        path = backups.join(format!("{SNAPSHOT_PREFIX}{timestamp}-{n}.zip"));
+    }
+
+    let mut zip = ZipWriter::new(File::create(&path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for slot in slots {
+        let Ok(entries) = fs::read_dir(appdata.join(slot)) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            if !entry.path().is_file() {
+                continue;
+            }
+            let name = format!("{}/{}", slot, entry.file_name().to_string_lossy());
+            zip.start_file(name, options).map_err(io::Error::other)?;
+            zip.write_all(&fs::read(entry.path())?)?;
+        }
+    }
+    zip.finish().map_err(io::Error::other)?;
+
+    Snapshot::read(path)
+}
+
+/// Lists the snapshots in `backups`, newest first.
+pub fn list_snapshots(backups: &Path) -> Vec<Snapshot> {
+    let Ok(entries) = fs::read_dir(backups) else {
+        return vec![];
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension().is_some_and(|e| e == "zip")
+                && p.file_name()
+                    .is_some_and(|n| n.to_string_lossy().starts_with(SNAPSHOT_PREFIX))
+        })
+        .filter_map(|p| Snapshot::read(p).ok())
+        .collect();
+    // snapshots taken in the same second can share a modification time, and the `-n` suffix
+    // only sorts after the plain name when names are compared by length first
+    let name_key = |s: &Snapshot| {
+        let name = s.path.file_stem().unwrap_or_default().to_string_lossy();
+        (name.len(), name.into_owned())
+    };
+    snapshots.sort_by(|a, b| {
+        b.created
+            .cmp(&a.created)
+            .then_with(|| name_key(b).cmp(&name_key(a)))
+    });
+    snapshots
+}
+
+/// Restores `from_slot` of `snapshot` into `to_slot` of `appdata`, replacing what is there.
+///
+/// Whatever is in `to_slot` is snapshotted into `backups` first, and that snapshot is returned.
+pub fn restore(
+    snapshot: &Snapshot,
+    from_slot: &str,
+    appdata: &Path,
+    to_slot: &str,
+    backups: &Path,
+) -> io::Result<Option<Snapshot>> {
+    let mut archive = ZipArchive::new(File::open(&snapshot.path)?).map_err(io::Error::other)?;
+
+    // read everything up front so a broken snapshot doesn't leave the slot half-restored
+    let prefix = format!("{from_slot}/");
+    let mut files = vec![];
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(io::Error::other)?;
+        let Some(name) = file.name().strip_prefix(&prefix).map(|n| n.to_string()) else {
+            continue;
+        };
+        if name.is_empty() || name.contains(['/', '\\']) || name == ".." {
+            continue;
+        }
+        let mut contents = vec![];
+        file.read_to_end(&mut contents)?;
+        files.push((name, contents));
+    }
+    if files.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("the snapshot has no profile {from_slot}"),
+        ));
+    }
+
+    let target = appdata.join(to_slot);
+    // anything in the slot is about to be deleted, even without a profile.jkr
+    let has_files = fs::read_dir(&target)
+        .map(|entries| entries.filter_map(|e| e.ok()).any(|e| e.path().is_file()))
+        .unwrap_or(false);
+    let backup = if has_files {
+        Some(take_snapshot(appdata, backups, &[to_slot])?)
+    } else {
+        None
+    };
+
+    if target.exists() {
+        for entry in fs::read_dir(&target)?.filter_map(|e| e.ok()) {
+            if entry.path().is_file() {
+                fs::remove_file(entry.path())?;
+            }
+        }
+    }
+    fs::create_dir_all(&target)?;
+    for (name, contents) in files {
+        fs::write(target.join(name), contents)?;
+    }
+
+    Ok(backup)
+}
+
+/// Formats a size in bytes as KiB or MiB.
+pub fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn write_profile(appdata: &Path, slot: &str, contents: &str) {
+        let dir = appdata.join(slot);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("profile.jkr"), contents).unwrap();
+        fs::write(dir.join("meta.jkr"), format!("meta {contents}")).unwrap();
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let appdata = tempdir().unwrap();
+        let backups = tempdir().unwrap();
+        write_profile(appdata.path(), "1", "first");
+        write_profile(appdata.path(), "2", "second");
+        assert_eq!(existing_slots(appdata.path()), vec!["1", "2"]);
+
+        let snapshot = take_snapshot(appdata.path(), backups.path(), &["1", "2"]).unwrap();
+        assert_eq!(snapshot.slots, vec!["1", "2"]);
+
+        // profile 2 gets corrupted, and profile 1 is restored over it
+        fs::write(appdata.path().join("2").join("save.jkr"), "broken").unwrap();
+        let backup = restore(&snapshot, "1", appdata.path(), "2", backups.path())
+            .unwrap()
+            .unwrap();
+        assert_eq!(backup.slots, vec!["2"]);
+
+        let slot = appdata.path().join("2");
+        assert_eq!(
+            fs::read_to_string(slot.join("profile.jkr")).unwrap(),
+            "first"
+        );
+        assert_eq!(
+            fs::read_to_string(slot.join("meta.jkr")).unwrap(),
+            "meta first"
+        );
+        assert!(!slot.join("save.jkr").exists());
+
+        let snapshots = list_snapshots(backups.path());
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].path, backup.path);
+    }
+
+    #[test]
+    fn test_restore_missing_slot() {
+        let appdata = tempdir().unwrap();
+        let backups = tempdir().unwrap();
+        write_profile(appdata.path(), "1", "first");
+        let snapshot = take_snapshot(appdata.path(), backups.path(), &["1"]).unwrap();
+
+        assert!(restore(&snapshot, "3", appdata.path(), "1", backups.path()).is_err());
+        assert_eq!(list_snapshots(backups.path()).len(), 1);
+    }
+
+    #[test]
+    fn test_restore_backs_up_slot_without_profile() {
+        let appdata = tempdir().unwrap();
+        let backups = tempdir().unwrap();
+        write_profile(appdata.path(), "1", "first");
+        let snapshot = take_snapshot(appdata.path(), backups.path(), &["1"]).unwrap();
+
+        // a run in progress whose profile.jkr is gone
+        fs::create_dir_all(appdata.path().join("3")).unwrap();
+        fs::write(appdata.path().join("3").join("save.jkr"), "run").unwrap();
+        let backup = restore(&snapshot, "1", appdata.path(), "3", backups.path())
+            .unwrap()
+            .unwrap();
+        assert_eq!(backup.slots, vec!["3"]);
+
+        // an empty slot has nothing to lose
+        assert!(
+            restore(&snapshot, "1", appdata.path(), "2", backups.path())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
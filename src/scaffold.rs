@@ -0,0 +1,268 @@
+//! Generating the files of a new Steamodded mod.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use git2::Repository;
+use serde_json::json;
+
+use crate::metadata::{RESERVED_IDS, is_identifier};
+
+/// What the new mod wizard asks for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NewMod {
+    pub id: String,
+    pub name: String,
+    /// Prefixed to the keys of everything the mod adds, like `j_<prefix>_...` for jokers.
+    pub prefix: String,
+    pub authors: Vec<String>,
+    pub description: String,
+    /// The oldest Steamodded the mod works with, like `1.0.0~BETA-0614a`.
+    pub steamodded_version: String,
+    pub lovely_patch: bool,
+    pub git_init: bool,
+}
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    Invalid(String),
+    Io(PathBuf, io::Error),
+    Git(git2::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::Invalid(e) => write!(f, "{e}"),
+            ScaffoldError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ScaffoldError::Git(e) => write!(f, "git init failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+impl NewMod {
+    /// Checks the answers, returning what is wrong with the first bad one.
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_identifier(&self.id) {
+            return Err("the id can only have letters, digits and underscores".to_string());
+        }
+        if RESERVED_IDS.contains(&self.id.as_str()) {
+            return Err(format!("the id {} is taken by Steamodded", self.id));
+        }
+        if self.name.trim().is_empty() {
+            return Err("the name can't be empty".to_string());
+        }
+        if !is_identifier(&self.prefix) {
+            return Err("the prefix can only have letters, digits and underscores".to_string());
+        }
+        if self.authors.is_empty() {
+            return Err("the mod needs at least one author".to_string());
+        }
+        if self.steamodded_version.trim().is_empty() {
+            return Err("the Steamodded version can't be empty".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn metadata(&self) -> String {
+        let metadata = json!({
+            "id": self.id,
+            "name": self.name,
+            "author": self.authors,
+            "description": self.description,
+            "prefix": self.prefix,
+            "main_file": "main.lua",
+            "version": "0.1.0",
+            "dependencies": [format!("Steamodded (>={})", self.steamodded_version.trim())],
+        });
+        // serializing a json! value can't fail
+        serde_json::to_string_pretty(&metadata).unwrap() + "\n"
+    }
+
+    fn main_lua(&self) -> String {
+        format!(
+            "-- {name} by {authors}\n\
+             \n\
+             -- Put the joker sheet at assets/1x/jokers.png and assets/2x/jokers.png first\n\
+             -- SMODS.Atlas {{\n\
+             --     key = \"{prefix}_jokers\",\n\
+             --     path = \"jokers.png\",\n\
+             --     px = 71,\n\
+             --     py = 95,\n\
+             -- }}\n\
+             \n\
+             -- SMODS.Joker {{\n\
+             --     key = \"example\",\n\
+             --     atlas = \"{prefix}_jokers\",\n\
+             --     pos = {{ x = 0, y = 0 }},\n\
+             --     rarity = 1,\n\
+             --     cost = 4,\n\
+             -- }}\n",
+            name = self.name,
+            authors = self.authors.join(", "),
+            prefix = self.prefix,
+        )
+    }
+
+    fn localization(&self) -> String {
+        format!(
+            "return {{\n\
+             \x20   descriptions = {{\n\
+             \x20       Joker = {{\n\
+             \x20           j_{prefix}_example = {{\n\
+             \x20               name = \"Example\",\n\
+             \x20               text = {{ \"Does nothing yet\" }},\n\
+             \x20           }},\n\
+             \x20       }},\n\
+             \x20   }},\n\
+             \x20   misc = {{\n\
+             \x20       dictionary = {{}},\n\
+             \x20   }},\n\
+             }}\n",
+            prefix = self.prefix,
+        )
+    }
+
+    fn lovely_toml(&self) -> String {
+        "[manifest]\n\
+         version = \"1.0.0\"\n\
+         dump_lua = true\n\
+         priority = 0\n\
+         \n\
+         # [[patches]]\n\
+         # [patches.pattern]\n\
+         # target = \"game.lua\"\n\
+         # pattern = \"self.SPEEDFACTOR = 1\"\n\
+         # position = \"after\"\n\
+         # payload = \"\"\n\
+         # match_indent = true\n"
+            .to_string()
+    }
+
+    /// The files of the mod, relative to its folder.
+    pub fn files(&self) -> Vec<(PathBuf, String)> {
+        let mut files = vec![
+            (PathBuf::from(format!("{}.json", self.id)), self.metadata()),
+            (PathBuf::from("main.lua"), self.main_lua()),
+            (
+                PathBuf::from("localization").join("en-us.lua"),
+                self.localization(),
+            ),
+            (
+                PathBuf::from(".gitignore"),
+                ".lovelyignore\n.DS_Store\nreleases/\n".to_string(),
+            ),
+        ];
+        if self.lovely_patch {
+            files.push((PathBuf::from("lovely.toml"), self.lovely_toml()));
+        }
+        files
+    }
+
+    /// Writes the mod into `dir`, refusing to overwrite anything there.
+    pub fn create(&self, dir: &Path) -> Result<(), ScaffoldError> {
+        self.validate().map_err(ScaffoldError::Invalid)?;
+        let files = self.files();
+        if let Some((existing, _)) = files.iter().find(|(path, _)| dir.join(path).exists()) {
+            return Err(ScaffoldError::Invalid(format!(
+                "{} already exists",
+                existing.display()
+            )));
+        }
+        for assets in ["1x", "2x"] {
+            let path = dir.join("assets").join(assets);
+            std::fs::create_dir_all(&path).map_err(|e| ScaffoldError::Io(path, e))?;
+        }
+        for (relative, contents) in files {
+            let path = dir.join(relative);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| ScaffoldError::Io(parent.to_path_buf(), e))?;
+            }
+            std::fs::write(&path, contents).map_err(|e| ScaffoldError::Io(path, e))?;
+        }
+        if self.git_init && Repository::open(dir).is_err() {
+            Repository::init(dir).map_err(ScaffoldError::Git)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn new_mod() -> NewMod {
+        NewMod {
+            id: "SpeedMod".to_string(),
+            name: "Speed Mod".to_string(),
+            prefix: "speed".to_string(),
+            authors: vec!["Jimbo".to_string()],
+            description: "Makes the game faster".to_string(),
+            steamodded_version: "1.0.0~BETA-0614a".to_string(),
+            lovely_patch: true,
+            git_init: true,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(new_mod().validate(), Ok(()));
+        let bad_id = NewMod {
+            id: "speed mod".to_string(),
+            ..new_mod()
+        };
+        assert!(bad_id.validate().is_err());
+        let reserved = NewMod {
+            id: "Lovely".to_string(),
+            ..new_mod()
+        };
+        assert!(reserved.validate().is_err());
+        let no_authors = NewMod {
+            authors: vec![],
+            ..new_mod()
+        };
+        assert!(no_authors.validate().is_err());
+    }
+
+    #[test]
+    fn test_create() {
+        let dir = tempdir().unwrap();
+        new_mod().create(dir.path()).unwrap();
+
+        let metadata: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("SpeedMod.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata["id"], "SpeedMod");
+        assert_eq!(metadata["prefix"], "speed");
+        assert_eq!(metadata["author"][0], "Jimbo");
+        assert_eq!(
+            metadata["dependencies"][0],
+            "Steamodded (>=1.0.0~BETA-0614a)"
+        );
+        for path in [
+            "main.lua",
+            "localization/en-us.lua",
+            "lovely.toml",
+            ".gitignore",
+            "assets/1x",
+            "assets/2x",
+            ".git",
+        ] {
+            assert!(dir.path().join(path).exists(), "{path} is missing");
+        }
+
+        // a second run would overwrite the mod
+        assert!(matches!(
+            new_mod().create(dir.path()),
+            Err(ScaffoldError::Invalid(_))
+        ));
+    }
+}
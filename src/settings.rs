@@ -0,0 +1,410 @@
+//! Editing the game's `settings.jkr`, for when a bad setting or a mod keeps the game from booting.
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::jkr::{self, JkrError, Key, Value};
+
+pub fn settings_path(appdata: &Path) -> PathBuf {
+    appdata.join("settings.jkr")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    Bool,
+    /// A number within a range.
+    Number {
+        min: f64,
+        max: f64,
+    },
+    /// One of a fixed set of numbers.
+    NumberChoice(&'static [f64]),
+    /// One of a fixed set of strings.
+    Choice(&'static [&'static str]),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Field {
+    pub label: &'static str,
+    /// Keys from the root of the settings table. Numeric keys index arrays.
+    pub path: &'static [&'static str],
+    pub kind: FieldKind,
+}
+
+/// The settings the editor knows how to change.
+pub const FIELDS: [Field; 16] = [
+    Field {
+        label: "Window mode",
+        path: &["WINDOW", "screenmode"],
+        kind: FieldKind::Choice(&["Windowed", "Borderless", "Fullscreen"]),
+    },
+    Field {
+        label: "Resolution width",
+        path: &["WINDOW", "DISPLAYS", "1", "screen_res", "w"],
+        kind: FieldKind::Number {
+            min: 640.0,
+            max: 7680.0,
+        },
+    },
+    Field {
+        label: "Resolution height",
+        path: &["WINDOW", "DISPLAYS", "1", "screen_res", "h"],
+        kind: FieldKind::Number {
+            min: 360.0,
+            max: 4320.0,
+        },
+    },
+    Field {
+        label: "Display",
+        path: &["WINDOW", "selected_display"],
+        kind: FieldKind::Number { min: 1.0, max: 8.0 },
+    },
+    Field {
+        label: "VSync",
+        path: &["WINDOW", "vsync"],
+        kind: FieldKind::NumberChoice(&[0.0, 1.0]),
+    },
+    Field {
+        label: "Game speed",
+        path: &["GAMESPEED"],
+        kind: FieldKind::NumberChoice(&[0.5, 1.0, 2.0, 4.0]),
+    },
+    Field {
+        label: "CRT shader",
+        path: &["GRAPHICS", "crt"],
+        kind: FieldKind::Number {
+            min: 0.0,
+            max: 100.0,
+        },
+    },
+    Field {
+        label: "Bloom",
+        path: &["GRAPHICS", "bloom"],
+        kind: FieldKind::NumberChoice(&[1.0, 2.0]),
+    },
+    Field {
+        label: "Shadows",
+        path: &["GRAPHICS", "shadows"],
+        kind: FieldKind::Choice(&["On", "Off"]),
+    },
+    Field {
+        label: "Texture scaling",
+        path: &["GRAPHICS", "texture_scaling"],
+        kind: FieldKind::NumberChoice(&[1.0, 2.0]),
+    },
+    Field {
+        label: "Send crash reports",
+        path: &["crashreports"],
+        kind: FieldKind::Bool,
+    },
+    Field {
+        label: "Screenshake",
+        path: &["screenshake"],
+        kind: FieldKind::Bool,
+    },
+    Field {
+        label: "Reduced motion",
+        path: &["reduced_motion"],
+        kind: FieldKind::Bool,
+    },
+    Field {
+        label: "High contrast cards",
+        path: &["colourblind_option"],
+        kind: FieldKind::Bool,
+    },
+    Field {
+        label: "Master volume",
+        path: &["SOUND", "volume"],
+        kind: FieldKind::Number {
+            min: 0.0,
+            max: 100.0,
+        },
+    },
+    Field {
+        label: "Music volume",
+        path: &["SOUND", "music_volume"],
+        kind: FieldKind::Number {
+            min: 0.0,
+            max: 100.0,
+        },
+    },
+];
+
+impl Field {
+    /// Checks that a value fits this field.
+    pub fn validate(&self, value: &Value) -> Result<(), String> {
+        match (self.kind, value) {
+            (FieldKind::Bool, Value::Bool(_)) => Ok(()),
+            (FieldKind::Number { min, max }, Value::Number(n)) => {
+                if (min..=max).contains(n) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "{} must be between {} and {}",
+                        self.label, min, max
+                    ))
+                }
+            }
+            (FieldKind::NumberChoice(choices), Value::Number(n)) => {
+                if choices.contains(n) {
+                    Ok(())
+                } else {
+                    Err(format!("{} must be one of {:?}", self.label, choices))
+                }
+            }
+            (FieldKind::Choice(choices), Value::String(s)) => {
+                if choices.contains(&s.as_str()) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "{} must be one of {}",
+                        self.label,
+                        choices.join(", ")
+                    ))
+                }
+            }
+            _ => Err(format!("{} has the wrong type", self.label)),
+        }
+    }
+
+    /// Parses text typed by the user into a value for this field.
+    pub fn parse(&self, text: &str) -> Result<Value, String> {
+        let text = text.trim();
+        let value = match self.kind {
+            FieldKind::Bool => match text {
+                "true" | "on" | "yes" => Value::Bool(true),
+                "false" | "off" | "no" => Value::Bool(false),
+                _ => return Err(format!("{} must be true or false", self.label)),
+            },
+            FieldKind::Number { .. } | FieldKind::NumberChoice(_) => text
+                .parse()
+                .map(Value::Number)
+                .map_err(|_| format!("{} must be a number", self.label))?,
+            FieldKind::Choice(_) => Value::String(text.to_string()),
+        };
+        self.validate(&value)?;
+        Ok(value)
+    }
+
+    /// The value after `current` when cycling through a bool or choice field.
+    pub fn next(&self, current: Option<&Value>) -> Option<Value> {
+        match self.kind {
+            FieldKind::Bool => Some(Value::Bool(
+                !current.and_then(Value::as_bool).unwrap_or(false),
+            )),
+            FieldKind::NumberChoice(choices) => {
+                let i = current
+                    .and_then(Value::as_f64)
+                    .and_then(|n| choices.iter().position(|c| *c == n));
+                let next = i.map(|i| (i + 1) % choices.len()).unwrap_or(0);
+                Some(Value::Number(choices[next]))
+            }
+            FieldKind::Choice(choices) => {
+                let i = current
+                    .and_then(Value::as_str)
+                    .and_then(|s| choices.iter().position(|c| *c == s));
+                let next = i.map(|i| (i + 1) % choices.len()).unwrap_or(0);
+                Some(Value::String(choices[next].to_string()))
+            }
+            FieldKind::Number { .. } => None,
+        }
+    }
+}
+
+fn key_of(segment: &str) -> Key {
+    match segment.parse::<f64>() {
+        Ok(n) => Key::Number(n),
+        Err(_) => Key::String(segment.to_string()),
+    }
+}
+
+fn get<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |v, segment| {
+        let key = key_of(segment);
+        v.entries().iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+    })
+}
+
+/// Sets a value, creating any missing tables along the way.
+fn set(value: &mut Value, path: &[&str], new: Value) -> Result<(), String> {
+    let Some((segment, rest)) = path.split_first() else {
+        *value = new;
+        return Ok(());
+    };
+    let Value::Table(entries) = value else {
+        return Err(format!("{segment} is not inside a table"));
+    };
+    let key = key_of(segment);
+    let index = match entries.iter().position(|(k, _)| *k == key) {
+        Some(i) => i,
+        None => {
+            entries.push((key, Value::Table(vec![])));
+            entries.len() - 1
+        }
+    };
+    set(&mut entries[index].1, rest, new)
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Jkr(JkrError),
+    Io(PathBuf, io::Error),
+    Invalid(String),
+    /// Encoding and decoding the settings gave something different, so writing them is unsafe.
+    RoundTrip,
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Jkr(e) => write!(f, "{e}"),
+            SettingsError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            SettingsError::Invalid(message) => write!(f, "{message}"),
+            SettingsError::RoundTrip => write!(
+                f,
+                "the settings did not survive being encoded and decoded, refusing to write them"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<JkrError> for SettingsError {
+    fn from(e: JkrError) -> Self {
+        SettingsError::Jkr(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub path: PathBuf,
+    pub value: Value,
+}
+
+impl Settings {
+    pub fn load(path: PathBuf) -> Result<Self, SettingsError> {
+        let value = jkr::read(&path)?;
+        if !matches!(value, Value::Table(_)) {
+            return Err(SettingsError::Invalid(format!(
+                "{} does not hold a table",
+                path.display()
+            )));
+        }
+        Ok(Self { path, value })
+    }
+
+    pub fn get(&self, field: &Field) -> Option<&Value> {
+        get(&self.value, field.path)
+    }
+
+    pub fn set(&mut self, field: &Field, value: Value) -> Result<(), SettingsError> {
+        field.validate(&value).map_err(SettingsError::Invalid)?;
+        set(&mut self.value, field.path, value).map_err(SettingsError::Invalid)
+    }
+
+    /// Backs up the current file and writes the settings. Returns the backup path.
+    pub fn save(&self) -> Result<PathBuf, SettingsError> {
+        let encoded =
+            jkr::encode(&self.value).map_err(|e| SettingsError::Io(self.path.clone(), e))?;
+        if jkr::decode(&encoded).ok().as_ref() != Some(&self.value) {
+            return Err(SettingsError::RoundTrip);
+        }
+
+        let backup = crate::backup_path(&self.path);
+        std::fs::copy(&self.path, &backup).map_err(|e| SettingsError::Io(backup.clone(), e))?;
+        std::fs::write(&self.path, encoded).map_err(|e| SettingsError::Io(self.path.clone(), e))?;
+        Ok(backup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    const SETTINGS: &str = r#"return {["GAMESPEED"]=1,["crashreports"]=false,["WINDOW"]={["screenmode"]="Fullscreen",["DISPLAYS"]={[1]={["screen_res"]={["w"]=1920,["h"]=1080,},},},},["language"]="en-us",}"#;
+
+    fn field(label: &str) -> &'static Field {
+        FIELDS.iter().find(|f| f.label == label).unwrap()
+    }
+
+    fn write_settings(dir: &Path) -> PathBuf {
+        let path = settings_path(dir);
+        std::fs::write(
+            &path,
+            jkr::encode(&jkr::parse(SETTINGS.as_bytes()).unwrap()).unwrap(),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_edit_and_save() {
+        let dir = tempdir().unwrap();
+        let mut settings = Settings::load(write_settings(dir.path())).unwrap();
+
+        let mode = field("Window mode");
+        assert_eq!(
+            settings.get(mode).and_then(Value::as_str),
+            Some("Fullscreen")
+        );
+        let next = mode.next(settings.get(mode)).unwrap();
+        assert_eq!(next, Value::String("Windowed".into()));
+        settings.set(mode, next).unwrap();
+
+        let width = field("Resolution width");
+        assert_eq!(settings.get(width).and_then(Value::as_f64), Some(1920.0));
+        settings.set(width, width.parse("1280").unwrap()).unwrap();
+        assert!(width.parse("12").is_err());
+        assert!(settings.set(width, Value::Bool(true)).is_err());
+
+        // missing keys are created
+        let crt = field("CRT shader");
+        settings.set(crt, Value::Number(0.0)).unwrap();
+
+        let backup = settings.save().unwrap();
+        assert!(backup.exists());
+
+        let saved = Settings::load(settings.path.clone()).unwrap();
+        assert_eq!(saved.get(mode).and_then(Value::as_str), Some("Windowed"));
+        assert_eq!(saved.get(width).and_then(Value::as_f64), Some(1280.0));
+        assert_eq!(saved.get(crt).and_then(Value::as_f64), Some(0.0));
+        assert_eq!(
+            saved.value.get("language").and_then(Value::as_str),
+            Some("en-us")
+        );
+
+        let original = Settings::load(backup.clone()).unwrap();
+        assert_eq!(
+            original.get(mode).and_then(Value::as_str),
+            Some("Fullscreen")
+        );
+
+        // a second save in the same second keeps the first backup
+        let second = saved.save().unwrap();
+        assert_ne!(second, backup);
+        assert_eq!(
+            Settings::load(backup)
+                .unwrap()
+                .get(mode)
+                .and_then(Value::as_str),
+            Some("Fullscreen")
+        );
+    }
+
+    #[test]
+    fn test_refuses_what_does_not_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = write_settings(dir.path());
+        let before = std::fs::read(&path).unwrap();
+
+        let mut settings = Settings::load(path.clone()).unwrap();
+        set(&mut settings.value, &["broken"], Value::Number(f64::NAN)).unwrap();
+        assert!(matches!(settings.save(), Err(SettingsError::RoundTrip)));
+        assert_eq!(std::fs::read(&path).unwrap(), before);
+    }
+}
@@ -0,0 +1,270 @@
+//! Editing Balatro's launch options in Steam's per-user `localconfig.vdf`.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::process::is_process_running;
+use crate::vdf;
+
+pub const BALATRO_APP_ID: &str = "2379780";
+pub const LOVELY_DLL_OVERRIDE: &str = "version=n,b";
+
+/// Offset between a 64 bit steam id and the account id used for `userdata/<id>`.
+const STEAM_ID_OFFSET: u64 = 76561197960265728;
+
+#[derive(Debug)]
+pub enum SteamError {
+    SteamRunning,
+    NoUser,
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, vdf::ParseError),
+}
+
+impl fmt::Display for SteamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SteamError::SteamRunning => {
+                write!(
+                    f,
+                    "Steam is running, close it first so it does not overwrite the change"
+                )
+            }
+            SteamError::NoUser => write!(f, "could not find a Steam user in loginusers.vdf"),
+            SteamError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            SteamError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for SteamError {}
+
+fn read_vdf(path: &Path) -> Result<vdf::Object, SteamError> {
+    let text = fs::read_to_string(path).map_err(|e| SteamError::Io(path.to_path_buf(), e))?;
+    vdf::parse(&text).map_err(|e| SteamError::Parse(path.to_path_buf(), e))
+}
+
+/// Finds the account id of the most recently logged in user from `config/loginusers.vdf`.
+pub fn active_user_id(steam_root: &Path) -> Result<u64, SteamError> {
+    let login_users = read_vdf(&steam_root.join("config").join("loginusers.vdf"))?;
+    let users = login_users.get_object("users").ok_or(SteamError::NoUser)?;
+
+    let mut first = None;
+    for (id, user) in &users.0 {
+        let (Ok(id), vdf::Value::Object(user)) = (id.parse::<u64>(), user) else {
+            continue;
+        };
+        if user.get_str("MostRecent") == Some("1") {
+            return Ok(id.saturating_sub(STEAM_ID_OFFSET));
+        }
+        first.get_or_insert(id);
+    }
+    first
+        .map(|id| id.saturating_sub(STEAM_ID_OFFSET))
+        .ok_or(SteamError::NoUser)
+}
+
+pub fn localconfig_path(steam_root: &Path, user_id: u64) -> PathBuf {
+    steam_root
+        .join("userdata")
+        .join(user_id.to_string())
+        .join("config")
+        .join("localconfig.vdf")
+}
+
+/// Adds the Lovely dll override to a set of launch options, or returns `None` if it is
+/// already there.
+pub fn add_dll_override(options: &str) -> Option<String> {
+    let options = options.trim();
+    if let Some(start) = options.find("WINEDLLOVERRIDES=") {
+        let value_start = start + "WINEDLLOVERRIDES=".len();
+        let rest = &options[value_start..];
+        let (value, value_end) = if let Some(quoted) = rest.strip_prefix('"') {
+            let len = quoted.find('"').unwrap_or(quoted.len());
+            (&quoted[..len], value_start + 1 + len)
+        } else {
+            let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            (&rest[..len], value_start + len)
+        };
+        if value
+            .split(';')
+            .any(|o| o.replace(' ', "") == LOVELY_DLL_OVERRIDE)
+        {
+            return None;
+        }
+        let separator = if value.is_empty() { "" } else { ";" };
+        return Some(format!(
+            "{}{}{}{}",
+            &options[..value_end],
+            separator,
+            LOVELY_DLL_OVERRIDE,
+            &options[value_end..]
+        ));
+    }
+
+    let env = format!("WINEDLLOVERRIDES=\"{LOVELY_DLL_OVERRIDE}\"");
+    if options.is_empty() {
+        Some(format!("{env} %command%"))
+    } else if options.contains("%command%") {
+        Some(format!("{env} {options}"))
+    } else {
+        // bare arguments are passed to the game, which is what %command% followed by them does
+        Some(format!("{env} %command% {options}"))
+    }
+}
+
+/// A pending edit of `localconfig.vdf`, built so it can be shown to the user before applying it.
+#[derive(Debug, Clone)]
+pub struct LaunchOptionsChange {
+    pub path: PathBuf,
+    pub old: String,
+    pub new: String,
+    /// Where the current file is copied before writing, fixed when the change is planned so the
+    /// preview names the file that gets written.
+    pub backup_path: PathBuf,
+    config: vdf::Object,
+}
+
+impl LaunchOptionsChange {
+    /// Backs up the current file and writes the new launch options. Returns the backup path.
+    pub fn apply(&self) -> Result<PathBuf, SteamError> {
+        if is_process_running("steam") {
+            return Err(SteamError::SteamRunning);
+        }
+        self.write()
+    }
+
+    fn write(&self) -> Result<PathBuf, SteamError> {
+        let backup = self.backup_path.clone();
+        fs::copy(&self.path, &backup).map_err(|e| SteamError::Io(backup.clone(), e))?;
+        fs::write(&self.path, vdf::to_string(&self.config))
+            .map_err(|e| SteamError::Io(self.path.clone(), e))?;
+        Ok(backup)
+    }
+}
+
+const APPS_PATH: [&str; 5] = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"];
+
+/// Reads the active user's launch options for Balatro and works out what they should be.
+/// Returns `Ok(None)` when the override is already set.
+pub fn plan_lovely_launch_options(
+    steam_root: &Path,
+) -> Result<Option<LaunchOptionsChange>, SteamError> {
+    let path = localconfig_path(steam_root, active_user_id(steam_root)?);
+    let mut config = read_vdf(&path)?;
+
+    let app = config.path_entry(&APPS_PATH).object_entry(BALATRO_APP_ID);
+    let old = app.get_str("LaunchOptions").unwrap_or_default().to_string();
+    let Some(new) = add_dll_override(&old) else {
+        return Ok(None);
+    };
+    app.set_str("LaunchOptions", &new);
+
+    Ok(Some(LaunchOptionsChange {
+        backup_path: crate::backup_path(&path),
+        path,
+        old,
+        new,
+        config,
+    }))
+}
+
+/// Like [`plan_lovely_launch_options`], but refuses to do anything while Steam is running, as
+/// Steam rewrites `localconfig.vdf` on exit.
+pub fn plan_lovely_launch_options_checked(
+    steam_root: &Path,
+) -> Result<Option<LaunchOptionsChange>, SteamError> {
+    if is_process_running("steam") {
+        return Err(SteamError::SteamRunning);
+    }
+    plan_lovely_launch_options(steam_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture_steam_root() -> tempfile::TempDir {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/steam");
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "config/loginusers.vdf",
+            "userdata/39734273/config/localconfig.vdf",
+            "userdata/11111111/config/localconfig.vdf",
+        ] {
+            let to = dir.path().join(file);
+            fs::create_dir_all(to.parent().unwrap()).unwrap();
+            fs::copy(fixtures.join(file), to).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_active_user_id() {
+        let root = fixture_steam_root();
+        assert_eq!(active_user_id(root.path()).unwrap(), 39734273);
+    }
+
+    #[test]
+    fn test_add_dll_override() {
+        assert_eq!(
+            add_dll_override("").unwrap(),
+            "WINEDLLOVERRIDES=\"version=n,b\" %command%"
+        );
+        assert_eq!(
+            add_dll_override("-windowed").unwrap(),
+            "WINEDLLOVERRIDES=\"version=n,b\" %command% -windowed"
+        );
+        assert_eq!(
+            add_dll_override("PROTON_LOG=1 %command%").unwrap(),
+            "WINEDLLOVERRIDES=\"version=n,b\" PROTON_LOG=1 %command%"
+        );
+        assert_eq!(
+            add_dll_override("WINEDLLOVERRIDES=\"winmm=n,b\" %command%").unwrap(),
+            "WINEDLLOVERRIDES=\"winmm=n,b;version=n,b\" %command%"
+        );
+        assert_eq!(
+            add_dll_override("WINEDLLOVERRIDES=winmm=n,b %command%").unwrap(),
+            "WINEDLLOVERRIDES=winmm=n,b;version=n,b %command%"
+        );
+        assert_eq!(
+            add_dll_override("WINEDLLOVERRIDES=\"version=n,b\" %command%"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_plan_and_write() {
+        let root = fixture_steam_root();
+        let change = plan_lovely_launch_options(root.path()).unwrap().unwrap();
+        assert_eq!(change.old, "-windowed");
+        assert_eq!(
+            change.new,
+            "WINEDLLOVERRIDES=\"version=n,b\" %command% -windowed"
+        );
+
+        let original = fs::read_to_string(&change.path).unwrap();
+        let backup = change.write().unwrap();
+        assert_eq!(backup, change.backup_path);
+        assert_eq!(fs::read_to_string(backup).unwrap(), original);
+
+        // other apps and settings are kept as they were
+        let written = read_vdf(&change.path).unwrap();
+        let apps = written.get_path(&APPS_PATH).unwrap();
+        assert_eq!(
+            apps.get_path(&["620"]).unwrap().get_str("LaunchOptions"),
+            Some("-novid")
+        );
+        assert_eq!(
+            apps.get_path(&[BALATRO_APP_ID])
+                .unwrap()
+                .get_str("LaunchOptions"),
+            Some(change.new.as_str())
+        );
+
+        // applying twice is a no-op
+        assert!(plan_lovely_launch_options(root.path()).unwrap().is_none());
+    }
+}
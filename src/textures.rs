@@ -0,0 +1,567 @@
+//! A mod's textures: making the 2x versions from the 1x ones, checking the two agree with
+//! each other and with the atlases the mod declares, and packing single sprites into atlases.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use image::RgbaImage;
+use image::imageops::{self, FilterType};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref ATLAS: Regex = Regex::new(r"(?s)SMODS\.Atlas\s*\(?\s*\{(.*?)\}").unwrap();
+    static ref STRING_FIELD: Regex = Regex::new(r#"\b(key|path)\s*=\s*["']([^"']*)["']"#).unwrap();
+    static ref NUMBER_FIELD: Regex = Regex::new(r"\b(px|py)\s*=\s*(\d+)").unwrap();
+}
+
+pub fn one_x_dir(mod_dir: &Path) -> PathBuf {
+    mod_dir.join("assets").join("1x")
+}
+
+pub fn two_x_dir(mod_dir: &Path) -> PathBuf {
+    mod_dir.join("assets").join("2x")
+}
+
+/// The PNGs under `dir`, relative to it and sorted.
+pub fn png_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(current) else {
+            continue;
+        };
+        for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("png"))
+                && let Ok(relative) = path.strip_prefix(dir)
+            {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Upscaled {
+    Written,
+    /// The 2x version is newer than the 1x one.
+    UpToDate,
+    Failed(String),
+}
+
+fn is_newer(a: &Path, b: &Path) -> bool {
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(a), modified(b)) {
+        (Some(a), Some(b)) => a > b,
+        _ => false,
+    }
+}
+
+fn upscale(from: &Path, to: &Path) -> Result<(), String> {
+    let image = image::open(from).map_err(|e| e.to_string())?;
+    let doubled = imageops::resize(
+        &image,
+        image.width() * 2,
+        image.height() * 2,
+        FilterType::Nearest,
+    );
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    doubled.save(to).map_err(|e| e.to_string())
+}
+
+/// Writes a nearest neighbour 2x copy of every 1x texture, skipping those whose 2x version is
+/// newer unless `force` is set.
+pub fn upscale_all(mod_dir: &Path, force: bool) -> Vec<(PathBuf, Upscaled)> {
+    let (one_x, two_x) = (one_x_dir(mod_dir), two_x_dir(mod_dir));
+    png_files(&one_x)
+        .into_iter()
+        .map(|file| {
+            let (from, to) = (one_x.join(&file), two_x.join(&file));
+            let result = if !force && is_newer(&to, &from) {
+                Upscaled::UpToDate
+            } else {
+                match upscale(&from, &to) {
+                    Ok(()) => Upscaled::Written,
+                    Err(e) => Upscaled::Failed(e),
+                }
+            };
+            (file, result)
+        })
+        .collect()
+}
+
+/// An `SMODS.Atlas` declared in the mod's Lua.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Atlas {
+    pub key: String,
+    pub path: String,
+    pub px: u32,
+    pub py: u32,
+}
+
+/// The atlases declared in `source`, skipping any without a path or sizes.
+pub fn find_atlases(source: &str) -> Vec<Atlas> {
+    ATLAS
+        .captures_iter(source)
+        .filter_map(|captures| {
+            let body = &captures[1];
+            let mut atlas = Atlas {
+                key: String::new(),
+                path: String::new(),
+                px: 0,
+                py: 0,
+            };
+            for field in STRING_FIELD.captures_iter(body) {
+                match &field[1] {
+                    "key" => atlas.key = field[2].to_string(),
+                    _ => atlas.path = field[2].to_string(),
+                }
+            }
+            for field in NUMBER_FIELD.captures_iter(body) {
+                let value = field[2].parse().ok()?;
+                match &field[1] {
+                    "px" => atlas.px = value,
+                    _ => atlas.py = value,
+                }
+            }
+            (!atlas.path.is_empty() && atlas.px > 0 && atlas.py > 0).then_some(atlas)
+        })
+        .collect()
+}
+
+fn lua_sources(mod_dir: &Path) -> Vec<String> {
+    let mut sources = vec![];
+    let mut dirs = vec![mod_dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(current) else {
+            continue;
+        };
+        for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+            if path.is_dir() {
+                if path
+                    .file_name()
+                    .is_none_or(|n| n != "assets" && n != ".git")
+                {
+                    dirs.push(path);
+                }
+            } else if path.extension().is_some_and(|e| e == "lua")
+                && let Ok(source) = std::fs::read_to_string(&path)
+            {
+                sources.push(source);
+            }
+        }
+    }
+    sources
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextureProblem {
+    Missing2x(PathBuf),
+    Missing1x(PathBuf),
+    NotDouble {
+        file: PathBuf,
+        one_x: (u32, u32),
+        two_x: (u32, u32),
+    },
+    NotMultiple {
+        atlas: String,
+        file: PathBuf,
+        size: (u32, u32),
+        px: u32,
+        py: u32,
+    },
+    MissingAtlasFile {
+        atlas: String,
+        file: PathBuf,
+    },
+    Unreadable(PathBuf, String),
+}
+
+impl fmt::Display for TextureProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextureProblem::Missing2x(file) => write!(f, "{} has no 2x version", file.display()),
+            TextureProblem::Missing1x(file) => write!(f, "{} has no 1x version", file.display()),
+            TextureProblem::NotDouble { file, one_x, two_x } => write!(
+                f,
+                "{} is {}x{} at 2x, should be {}x{}",
+                file.display(),
+                two_x.0,
+                two_x.1,
+                one_x.0 * 2,
+                one_x.1 * 2
+            ),
+            TextureProblem::NotMultiple {
+                atlas,
+                file,
+                size,
+                px,
+                py,
+            } => write!(
+                f,
+                "{} is {}x{}, not a multiple of the {}x{} sprites of atlas {}",
+                file.display(),
+                size.0,
+                size.1,
+                px,
+                py,
+                atlas
+            ),
+            TextureProblem::MissingAtlasFile { atlas, file } => {
+                write!(
+                    f,
+                    "atlas {} uses {}, which doesn't exist",
+                    atlas,
+                    file.display()
+                )
+            }
+            TextureProblem::Unreadable(file, e) => write!(f, "{}: {}", file.display(), e),
+        }
+    }
+}
+
+fn dimensions(dir: &Path, file: &Path, problems: &mut Vec<TextureProblem>) -> Option<(u32, u32)> {
+    match image::image_dimensions(dir.join(file)) {
+        Ok(size) => Some(size),
+        Err(e) => {
+            problems.push(TextureProblem::Unreadable(
+                file.to_path_buf(),
+                e.to_string(),
+            ));
+            None
+        }
+    }
+}
+
+/// Checks the 1x and 2x textures match up, and fit the atlases declared in the mod's Lua.
+pub fn verify(mod_dir: &Path) -> Vec<TextureProblem> {
+    let (one_x, two_x) = (one_x_dir(mod_dir), two_x_dir(mod_dir));
+    let one_x_files = png_files(&one_x);
+    let two_x_files = png_files(&two_x);
+    let mut problems = vec![];
+
+    for file in &one_x_files {
+        if !two_x_files.contains(file) {
+            problems.push(TextureProblem::Missing2x(file.clone()));
+            continue;
+        }
+        let (Some(small), Some(large)) = (
+            dimensions(&one_x, file, &mut problems),
+            dimensions(&two_x, file, &mut problems),
+        ) else {
+            continue;
+        };
+        if large != (small.0 * 2, small.1 * 2) {
+            problems.push(TextureProblem::NotDouble {
+                file: file.clone(),
+                one_x: small,
+                two_x: large,
+            });
+        }
+    }
+    for file in two_x_files.iter().filter(|f| !one_x_files.contains(f)) {
+        problems.push(TextureProblem::Missing1x(file.clone()));
+    }
+
+    for atlas in lua_sources(mod_dir).iter().flat_map(|s| find_atlases(s)) {
+        let file = PathBuf::from(&atlas.path);
+        if !one_x.join(&file).is_file() {
+            problems.push(TextureProblem::MissingAtlasFile {
+                atlas: atlas.key,
+                file,
+            });
+            continue;
+        }
+        let Some(size) = dimensions(&one_x, &file, &mut problems) else {
+            continue;
+        };
+        if size.0 % atlas.px != 0 || size.1 % atlas.py != 0 {
+            problems.push(TextureProblem::NotMultiple {
+                atlas: atlas.key,
+                file,
+                size,
+                px: atlas.px,
+                py: atlas.py,
+            });
+        }
+    }
+    problems
+}
+
+pub fn sprites_dir(mod_dir: &Path) -> PathBuf {
+    mod_dir.join("sprites")
+}
+
+/// The folders in `sprites/`, each holding the sprites of one atlas.
+pub fn sprite_folders(mod_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(sprites_dir(mod_dir)) else {
+        return vec![];
+    };
+    let mut folders: Vec<String> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_dir())
+        .filter_map(|p| Some(p.file_name()?.to_string_lossy().to_string()))
+        .collect();
+    folders.sort();
+    folders
+}
+
+/// The widest atlas the packer makes, like the game's own joker atlas.
+const ATLAS_COLUMNS: u32 = 10;
+
+/// An atlas made from a folder of sprites.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackedAtlas {
+    pub name: String,
+    pub px: u32,
+    pub py: u32,
+    /// Each sprite's name, from its file name, and its position in the atlas.
+    pub positions: Vec<(String, u32, u32)>,
+}
+
+impl PackedAtlas {
+    /// Lua declaring the atlas and where each sprite is, to paste into the mod.
+    pub fn lua_snippet(&self) -> String {
+        let mut lua = format!(
+            "SMODS.Atlas {{\n    key = \"{name}\",\n    path = \"{name}.png\",\n    px = {},\n    py = {},\n}}\n\n",
+            self.px,
+            self.py,
+            name = self.name
+        );
+        let table = self.name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        lua.push_str(&format!("local {table}_pos = {{\n"));
+        for (sprite, x, y) in &self.positions {
+            let key = if sprite
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !sprite.starts_with(|c: char| c.is_ascii_digit())
+            {
+                sprite.clone()
+            } else {
+                format!("[{sprite:?}]")
+            };
+            lua.push_str(&format!("    {key} = {{ x = {x}, y = {y} }},\n"));
+        }
+        lua.push_str("}\n");
+        lua
+    }
+}
+
+/// Packs the sprites in `sprites/<name>` into `assets/1x/<name>.png` and its 2x version, laid
+/// out in name order, and writes the [`PackedAtlas::lua_snippet`] to `sprites/<name>.lua`.
+///
+/// Every sprite has to be the same size.
+pub fn pack_atlas(mod_dir: &Path, name: &str) -> Result<PackedAtlas, String> {
+    let folder = sprites_dir(mod_dir).join(name);
+    let files: Vec<PathBuf> = png_files(&folder)
+        .into_iter()
+        .filter(|f| f.parent().is_none_or(|p| p.as_os_str().is_empty()))
+        .collect();
+    if files.is_empty() {
+        return Err(format!("no PNGs in {}", folder.display()));
+    }
+    let sprites = files
+        .iter()
+        .map(|f| {
+            image::open(folder.join(f))
+                .map(|i| i.to_rgba8())
+                .map_err(|e| format!("{}: {}", f.display(), e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let (px, py) = sprites[0].dimensions();
+    if let Some((file, sprite)) = files
+        .iter()
+        .zip(&sprites)
+        .find(|(_, s)| s.dimensions() != (px, py))
+    {
+        return Err(format!(
+            "{} is {}x{}, but {} is {}x{}, sprites have to be the same size",
+            file.display(),
+            sprite.width(),
+            sprite.height(),
+            files[0].display(),
+            px,
+            py
+        ));
+    }
+
+    let count = sprites.len() as u32;
+    let columns = count.min(ATLAS_COLUMNS);
+    let rows = count.div_ceil(columns);
+    let mut atlas = RgbaImage::new(columns * px, rows * py);
+    let mut positions = vec![];
+    for (i, (file, sprite)) in files.iter().zip(&sprites).enumerate() {
+        let (x, y) = (i as u32 % columns, i as u32 / columns);
+        imageops::replace(&mut atlas, sprite, (x * px) as i64, (y * py) as i64);
+        let sprite_name = file.file_stem().unwrap_or_default().to_string_lossy();
+        positions.push((sprite_name.to_string(), x, y));
+    }
+
+    let file = format!("{name}.png");
+    let doubled = imageops::resize(
+        &atlas,
+        atlas.width() * 2,
+        atlas.height() * 2,
+        FilterType::Nearest,
+    );
+    for (dir, image) in [(one_x_dir(mod_dir), &atlas), (two_x_dir(mod_dir), &doubled)] {
+        std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        image
+            .save(dir.join(&file))
+            .map_err(|e| format!("{}: {}", dir.join(&file).display(), e))?;
+    }
+    let packed = PackedAtlas {
+        name: name.to_string(),
+        px,
+        py,
+        positions,
+    };
+    let snippet = sprites_dir(mod_dir).join(format!("{name}.lua"));
+    std::fs::write(&snippet, packed.lua_snippet())
+        .map_err(|e| format!("{}: {}", snippet.display(), e))?;
+    Ok(packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use image::Rgba;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut image = RgbaImage::new(width, height);
+        image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        image.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_upscale_all() {
+        let dir = tempdir().unwrap();
+        let one_x = one_x_dir(dir.path()).join("jokers.png");
+        write_png(&one_x, 2, 3);
+        // so the 2x version written next is newer, even on filesystems with coarse times
+        std::fs::File::options()
+            .write(true)
+            .open(&one_x)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(10))
+            .unwrap();
+
+        assert_eq!(
+            upscale_all(dir.path(), false),
+            vec![(PathBuf::from("jokers.png"), Upscaled::Written)]
+        );
+        let doubled = image::open(two_x_dir(dir.path()).join("jokers.png"))
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(doubled.dimensions(), (4, 6));
+        // nearest neighbour keeps the red pixel sharp
+        assert_eq!(doubled.get_pixel(1, 1), &Rgba([255, 0, 0, 255]));
+        assert_eq!(doubled.get_pixel(2, 2), &Rgba([0, 0, 0, 0]));
+
+        assert_eq!(
+            upscale_all(dir.path(), false),
+            vec![(PathBuf::from("jokers.png"), Upscaled::UpToDate)]
+        );
+        assert_eq!(
+            upscale_all(dir.path(), true),
+            vec![(PathBuf::from("jokers.png"), Upscaled::Written)]
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let dir = tempdir().unwrap();
+        let (one_x, two_x) = (one_x_dir(dir.path()), two_x_dir(dir.path()));
+        write_png(&one_x.join("jokers.png"), 142, 95);
+        write_png(&two_x.join("jokers.png"), 284, 190);
+        write_png(&one_x.join("backs.png"), 10, 10);
+        write_png(&two_x.join("backs.png"), 21, 20);
+        write_png(&one_x.join("only_small.png"), 1, 1);
+        write_png(&two_x.join("only_large.png"), 2, 2);
+        std::fs::write(
+            dir.path().join("main.lua"),
+            "SMODS.Atlas {\n    key = 'jokers',\n    path = 'jokers.png',\n    px = 71,\n    py = 95,\n}\n\
+             SMODS.Atlas({ key = \"backs\", path = \"backs.png\", px = 3, py = 5 })\n\
+             SMODS.Atlas { key = 'gone', path = 'gone.png', px = 71, py = 95 }\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            verify(dir.path()),
+            vec![
+                TextureProblem::NotDouble {
+                    file: PathBuf::from("backs.png"),
+                    one_x: (10, 10),
+                    two_x: (21, 20),
+                },
+                TextureProblem::Missing2x(PathBuf::from("only_small.png")),
+                TextureProblem::Missing1x(PathBuf::from("only_large.png")),
+                TextureProblem::NotMultiple {
+                    atlas: "backs".to_string(),
+                    file: PathBuf::from("backs.png"),
+                    size: (10, 10),
+                    px: 3,
+                    py: 5,
+                },
+                TextureProblem::MissingAtlasFile {
+                    atlas: "gone".to_string(),
+                    file: PathBuf::from("gone.png"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pack_atlas() {
+        let dir = tempdir().unwrap();
+        let sprites = sprites_dir(dir.path()).join("jokers");
+        for name in ["blueprint", "brainstorm", "2 of a kind"] {
+            write_png(&sprites.join(format!("{name}.png")), 71, 95);
+        }
+        write_png(
+            &sprites_dir(dir.path()).join("backs").join("red.png"),
+            71,
+            95,
+        );
+        assert_eq!(sprite_folders(dir.path()), vec!["backs", "jokers"]);
+
+        let packed = pack_atlas(dir.path(), "jokers").unwrap();
+        assert_eq!(
+            packed.positions,
+            vec![
+                ("2 of a kind".to_string(), 0, 0),
+                ("blueprint".to_string(), 1, 0),
+                ("brainstorm".to_string(), 2, 0),
+            ]
+        );
+        assert_eq!(
+            image::image_dimensions(one_x_dir(dir.path()).join("jokers.png")).unwrap(),
+            (213, 95)
+        );
+        assert_eq!(
+            image::image_dimensions(two_x_dir(dir.path()).join("jokers.png")).unwrap(),
+            (426, 190)
+        );
+        let snippet = std::fs::read_to_string(sprites_dir(dir.path()).join("jokers.lua")).unwrap();
+        assert!(snippet.contains("    [\"2 of a kind\"] = { x = 0, y = 0 },\n"));
+        assert!(snippet.contains("    brainstorm = { x = 2, y = 0 },\n"));
+        assert_eq!(find_atlases(&snippet)[0].px, 71);
+
+        write_png(&sprites.join("huge.png"), 142, 190);
+        assert!(pack_atlas(dir.path(), "jokers").is_err());
+    }
+}
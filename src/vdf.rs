@@ -0,0 +1,307 @@
+//! A small reader/writer for Valve's text KeyValues format (`.vdf`), as used by
+//! Steam for `loginusers.vdf` and `localconfig.vdf`.
+//!
+//! Keys keep their original order and spelling so that a file can be read,
+//! edited and written back with as little churn as possible.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Object(Object),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Object(pub Vec<(String, Value)>);
+
+impl Object {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keys in Steam's files are case-insensitive (`apps` and `Apps` both show up in the wild).
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.0
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            Value::String(s) => Some(s),
+            Value::Object(_) => None,
+        }
+    }
+
+    pub fn get_object(&self, key: &str) -> Option<&Object> {
+        match self.get(key)? {
+            Value::Object(o) => Some(o),
+            Value::String(_) => None,
+        }
+    }
+
+    /// Follows a path of nested objects, e.g. `["Software", "Valve", "Steam"]`.
+    pub fn get_path(&self, path: &[&str]) -> Option<&Object> {
+        path.iter().try_fold(self, |obj, key| obj.get_object(key))
+    }
+
+    /// Returns the object stored at `key`, creating (or replacing a string with) an empty one.
+    pub fn object_entry(&mut self, key: &str) -> &mut Object {
+        let index = match self.0.iter().position(|(k, _)| k.eq_ignore_ascii_case(key)) {
+            Some(i) => {
+                if !matches!(self.0[i].1, Value::Object(_)) {
+                    self.0[i].1 = Value::Object(Object::new());
+                }
+                i
+            }
+            None => {
+                self.0.push((key.to_string(), Value::Object(Object::new())));
+                self.0.len() - 1
+            }
+        };
+        match &mut self.0[index].1 {
+            Value::Object(o) => o,
+            Value::String(_) => unreachable!(),
+        }
+    }
+
+    pub fn path_entry(&mut self, path: &[&str]) -> &mut Object {
+        path.iter().fold(self, |obj, key| obj.object_entry(key))
+    }
+
+    pub fn set_str(&mut self, key: &str, value: &str) {
+        match self.get_mut(key) {
+            Some(v) => *v = Value::String(value.to_string()),
+            None => self
+                .0
+                .push((key.to_string(), Value::String(value.to_string()))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl Lexer<'_> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            line: self.line,
+            message: message.to_string(),
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, ParseError> {
+        loop {
+            match self.chars.peek() {
+                None => return Ok(None),
+                Some('\n') => {
+                    self.line += 1;
+                    self.chars.next();
+                }
+                Some(c) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some('/') => {
+                    // `//` comments run to the end of the line
+                    while let Some(c) = self.chars.peek() {
+                        if *c == '\n' {
+                            break;
+                        }
+                        self.chars.next();
+                    }
+                }
+                Some('[') => {
+                    // conditionals such as `[$WIN32]` are ignored
+                    for c in self.chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                Some('{') => {
+                    self.chars.next();
+                    return Ok(Some(Token::Open));
+                }
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(Some(Token::Close));
+                }
+                Some('"') => {
+                    self.chars.next();
+                    let mut s = String::new();
+                    loop {
+                        match self.chars.next() {
+                            None => return Err(self.error("unterminated string")),
+                            Some('"') => break,
+                            Some('\\') => match self.chars.next() {
+                                Some('n') => s.push('\n'),
+                                Some('t') => s.push('\t'),
+                                Some(c) => s.push(c),
+                                None => return Err(self.error("unterminated string")),
+                            },
+                            Some(c) => {
+                                if c == '\n' {
+                                    self.line += 1;
+                                }
+                                s.push(c)
+                            }
+                        }
+                    }
+                    return Ok(Some(Token::Str(s)));
+                }
+                Some(_) => {
+                    let mut s = String::new();
+                    while let Some(c) = self.chars.peek() {
+                        if c.is_whitespace() || matches!(c, '{' | '}' | '"') {
+                            break;
+                        }
+                        s.push(*c);
+                        self.chars.next();
+                    }
+                    return Ok(Some(Token::Str(s)));
+                }
+            }
+        }
+    }
+
+    fn parse_object(&mut self, nested: bool) -> Result<Object, ParseError> {
+        let mut obj = Object::new();
+        loop {
+            let key = match self.next_token()? {
+                None if nested => return Err(self.error("unexpected end of file, missing `}`")),
+                None => return Ok(obj),
+                Some(Token::Close) if nested => return Ok(obj),
+                Some(Token::Close) => return Err(self.error("unexpected `}`")),
+                Some(Token::Open) => return Err(self.error("expected a key, found `{`")),
+                Some(Token::Str(s)) => s,
+            };
+            let value = match self.next_token()? {
+                Some(Token::Str(s)) => Value::String(s),
+                Some(Token::Open) => Value::Object(self.parse_object(true)?),
+                _ => return Err(self.error(&format!("expected a value for key `{key}`"))),
+            };
+            obj.0.push((key, value));
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Object, ParseError> {
+    let mut lexer = Lexer {
+        chars: input.chars().peekable(),
+        line: 1,
+    };
+    lexer.parse_object(false)
+}
+
+/// The inverse of the lexer's escapes, so every string survives being written and read back.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn write_object(out: &mut String, obj: &Object, depth: usize) {
+    let indent = "\t".repeat(depth);
+    for (key, value) in &obj.0 {
+        match value {
+            Value::String(s) => {
+                out.push_str(&format!(
+                    "{indent}\"{}\"\t\t\"{}\"\n",
+                    escape(key),
+                    escape(s)
+                ));
+            }
+            Value::Object(o) => {
+                out.push_str(&format!("{indent}\"{}\"\n{indent}{{\n", escape(key)));
+                write_object(out, o, depth + 1);
+                out.push_str(&format!("{indent}}}\n"));
+            }
+        }
+    }
+}
+
+/// Serializes an object the same way Steam lays out its own files.
+pub fn to_string(obj: &Object) -> String {
+    let mut out = String::new();
+    write_object(&mut out, obj, 0);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_nested() {
+        let obj = parse("\"a\"\n{\n\t\"B\"\t\t\"1\"\n\t\"c\" { \"d\" \"x y\" }\n}\n").unwrap();
+        assert_eq!(obj.get_path(&["a"]).unwrap().get_str("b"), Some("1"));
+        assert_eq!(obj.get_path(&["A", "c"]).unwrap().get_str("d"), Some("x y"));
+    }
+
+    #[test]
+    fn test_escapes_round_trip() {
+        let mut obj = Object::new();
+        obj.path_entry(&["apps", "2379780"]).set_str(
+            "LaunchOptions",
+            "WINEDLLOVERRIDES=\"version=n,b\" %command%",
+        );
+        let text = to_string(&obj);
+        assert!(text.contains("\"WINEDLLOVERRIDES=\\\"version=n,b\\\" %command%\""));
+        assert_eq!(parse(&text).unwrap(), obj);
+
+        // tabs and newlines would otherwise come back as the control characters themselves
+        obj.path_entry(&["apps", "2379780"])
+            .set_str("LaunchOptions", "a\tb\nc \\n \\t");
+        let text = to_string(&obj);
+        assert!(text.contains("\"a\\tb\\nc \\\\n \\\\t\""));
+        assert_eq!(parse(&text).unwrap(), obj);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("\"a\"\n{\n\"b\" \"c\"\n").unwrap_err().line, 4);
+        assert!(parse("\"a\" \"unterminated").is_err());
+        assert!(parse("}").is_err());
+    }
+}
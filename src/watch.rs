@@ -0,0 +1,230 @@
+//! The pieces of the dev loop that watches a mod being authored and relaunches the game when it
+//! changes: which changes count, waiting for them to settle, and syncing the mod into `Mods`.
+
+use std::collections::BTreeSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::devlink::{LinkState, installed_copies, link_path, link_state};
+use crate::release::{is_editor_file, release_files, releases_dir};
+
+/// Whether a change to `path` should relaunch the game: Lua, TOML and JSON files, and anything
+/// in `assets`, but nothing in `.git`, releases or editor files.
+pub fn is_watched(path: &Path, mod_dir: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(mod_dir) else {
+        return false;
+    };
+    if relative.starts_with(".git")
+        || path.starts_with(releases_dir(mod_dir))
+        || relative
+            .components()
+            .any(|c| is_editor_file(&c.as_os_str().to_string_lossy()))
+    {
+        return false;
+    }
+    relative.starts_with("assets")
+        || path
+            .extension()
+            .is_some_and(|e| e == "lua" || e == "toml" || e == "json")
+}
+
+/// Collects changes until none have come in for `delay`, so saving several files at once, or an
+/// editor writing a file in steps, relaunches the game once.
+#[derive(Debug)]
+pub struct Debouncer {
+    delay: Duration,
+    pending: BTreeSet<PathBuf>,
+    last_change: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: BTreeSet::new(),
+            last_change: None,
+        }
+    }
+
+    pub fn record(&mut self, path: PathBuf, now: Instant) {
+        self.pending.insert(path);
+        self.last_change = Some(now);
+    }
+
+    /// The changed files, once they have settled.
+    pub fn take_ready(&mut self, now: Instant) -> Option<Vec<PathBuf>> {
+        let last_change = self.last_change?;
+        if now.duration_since(last_change) < self.delay {
+            return None;
+        }
+        self.last_change = None;
+        Some(std::mem::take(&mut self.pending).into_iter().collect())
+    }
+}
+
+/// Whether the game needs a copy of the mod in `mods_dir`, which it doesn't when the mod is
+/// linked in or is being worked on in place.
+pub fn needs_sync(mods_dir: &Path, id: &str, mod_dir: &Path) -> bool {
+    let in_place = match (mod_dir.canonicalize(), mods_dir.canonicalize()) {
+        (Ok(mod_dir), Ok(mods_dir)) => mod_dir.starts_with(mods_dir),
+        _ => false,
+    };
+    !in_place && link_state(mods_dir, id, mod_dir) != LinkState::Linked
+}
+
+/// Other folders in `mods_dir` with the same mod id as `mod_dir`, besides the one it is synced
+/// into. The game loads them next to the synced copy.
+pub fn other_copies(mods_dir: &Path, id: &str, mod_dir: &Path) -> Vec<PathBuf> {
+    let target = link_path(mods_dir, id);
+    installed_copies(mods_dir, id, mod_dir)
+        .into_iter()
+        .filter(|c| *c != target)
+        .collect()
+}
+
+/// Written into `Mods/<id>` on the first sync, so later syncs only ever touch a folder they made.
+pub const SYNC_MARKER: &str = ".balatro-tui-sync";
+
+/// Whether `Mods/<id>` is missing or a copy made by [`sync_mod`], rather than an install or a
+/// link to another checkout that syncing would overwrite.
+pub fn can_sync(mods_dir: &Path, id: &str) -> bool {
+    let target = link_path(mods_dir, id);
+    match std::fs::symlink_metadata(&target) {
+        Ok(meta) => meta.is_dir() && target.join(SYNC_MARKER).is_file(),
+        Err(_) => true,
+    }
+}
+
+/// Compares contents, as copies get new modification times and mods are small.
+fn is_stale(from: &Path, to: &Path) -> bool {
+    match (std::fs::read(from), std::fs::read(to)) {
+        (Ok(from), Ok(to)) => from != to,
+        _ => true,
+    }
+}
+
+/// Makes `Mods/<id>` match the files of the mod that would go in a release, copying what
+/// changed and removing what is gone. Returns how many files were copied or removed.
+///
+/// Refuses with [`io::ErrorKind::AlreadyExists`] when something it didn't make is in the way.
+pub fn sync_mod(mods_dir: &Path, id: &str, mod_dir: &Path) -> io::Result<usize> {
+    let target = link_path(mods_dir, id);
+    if !can_sync(mods_dir, id) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists and wasn't made by syncing, move it away or link the mod instead",
+                target.display()
+            ),
+        ));
+    }
+    std::fs::create_dir_all(&target)?;
+    std::fs::write(target.join(SYNC_MARKER), "")?;
+    let files = release_files(mod_dir);
+    let mut changed = 0;
+    for file in &files {
+        let (from, to) = (mod_dir.join(file), target.join(file));
+        if is_stale(&from, &to) {
+            if let Some(parent) = to.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(from, to)?;
+            changed += 1;
+        }
+    }
+    for file in release_files(&target) {
+        if !files.contains(&file) && file != Path::new(SYNC_MARKER) {
+            std::fs::remove_file(target.join(file))?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_is_watched() {
+        let dir = Path::new("/mods/speed");
+        assert!(is_watched(&dir.join("main.lua"), dir));
+        assert!(is_watched(&dir.join("lovely/speed.toml"), dir));
+        assert!(is_watched(&dir.join("assets/2x/jokers.png"), dir));
+        assert!(!is_watched(&dir.join("README.md"), dir));
+        assert!(!is_watched(&dir.join(".git/config.json"), dir));
+        assert!(!is_watched(&dir.join("releases/speed-1.0.0.json"), dir));
+        assert!(!is_watched(&dir.join(".main.lua.swp"), dir));
+        assert!(!is_watched(Path::new("/elsewhere/main.lua"), dir));
+    }
+
+    #[test]
+    fn test_debouncer() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_millis(500));
+        assert_eq!(debouncer.take_ready(start), None);
+
+        debouncer.record(PathBuf::from("b.lua"), start);
+        debouncer.record(PathBuf::from("a.lua"), start + Duration::from_millis(300));
+        debouncer.record(PathBuf::from("b.lua"), start + Duration::from_millis(400));
+        assert_eq!(
+            debouncer.take_ready(start + Duration::from_millis(800)),
+            None
+        );
+        assert_eq!(
+            debouncer.take_ready(start + Duration::from_millis(900)),
+            Some(vec![PathBuf::from("a.lua"), PathBuf::from("b.lua")])
+        );
+        assert_eq!(debouncer.take_ready(start + Duration::from_secs(5)), None);
+    }
+
+    #[test]
+    fn test_sync_mod() {
+        let root = tempdir().unwrap();
+        let (mods, dev) = (root.path().join("Mods"), root.path().join("dev"));
+        std::fs::create_dir_all(dev.join("assets")).unwrap();
+        std::fs::write(dev.join("main.lua"), "print('one')").unwrap();
+        std::fs::write(dev.join("assets").join("jokers.png"), "").unwrap();
+        std::fs::create_dir_all(mods.join("SpeedMod")).unwrap();
+        std::fs::write(mods.join("SpeedMod").join("old.lua"), "").unwrap();
+
+        assert!(needs_sync(&mods, "SpeedMod", &dev));
+        assert!(!needs_sync(&mods, "SpeedMod", &mods.join("SpeedMod")));
+        // an install that wasn't synced is left alone
+        assert!(!can_sync(&mods, "SpeedMod"));
+        let refused = sync_mod(&mods, "SpeedMod", &dev).unwrap_err();
+        assert_eq!(refused.kind(), io::ErrorKind::AlreadyExists);
+        assert!(mods.join("SpeedMod").join("old.lua").exists());
+
+        std::fs::remove_dir_all(mods.join("SpeedMod")).unwrap();
+        assert!(can_sync(&mods, "SpeedMod"));
+        assert_eq!(sync_mod(&mods, "SpeedMod", &dev).unwrap(), 2);
+        assert!(can_sync(&mods, "SpeedMod"));
+        assert_eq!(
+            std::fs::read_to_string(mods.join("SpeedMod").join("main.lua")).unwrap(),
+            "print('one')"
+        );
+        std::fs::write(mods.join("SpeedMod").join("old.lua"), "").unwrap();
+        assert_eq!(sync_mod(&mods, "SpeedMod", &dev).unwrap(), 1);
+        assert!(!mods.join("SpeedMod").join("old.lua").exists());
+        assert_eq!(sync_mod(&mods, "SpeedMod", &dev).unwrap(), 0);
+
+        std::fs::write(dev.join("main.lua"), "print('two')").unwrap();
+        assert_eq!(sync_mod(&mods, "SpeedMod", &dev).unwrap(), 1);
+
+        // a downloaded release of the same mod next to the synced copy
+        let metadata = r#"{"id": "SpeedMod"}"#;
+        std::fs::write(dev.join("SpeedMod.json"), metadata).unwrap();
+        sync_mod(&mods, "SpeedMod", &dev).unwrap();
+        std::fs::create_dir_all(mods.join("SpeedMod-main")).unwrap();
+        std::fs::write(mods.join("SpeedMod-main").join("SpeedMod.json"), metadata).unwrap();
+        assert_eq!(
+            other_copies(&mods, "SpeedMod", &dev),
+            vec![mods.join("SpeedMod-main")]
+        );
+    }
+}